@@ -1,30 +1,41 @@
-use benchmarks::ballot::Ballot;
+use benchmarks::Backend;
+use primitives::ballots::ballot_context;
 use rand::Rng;
 
-pub fn generate_sample<PK, SK, BallotVariant>(
+pub fn generate_sample<B: Backend>(
     num_ballots: usize,
     num_candidates: usize,
-) -> (Vec<BallotVariant>, PK, SK)
-where
-    BallotVariant: Ballot<PK, SK>,
-{
-    let (public_key, secret_key) = BallotVariant::generate_kepair();
+) -> (Vec<B::Ballot>, Vec<u8>, Vec<u8>) {
+    let (public_key, secret_key) = B::generate_keypair().unwrap();
+    let context = ballot_context(&vec![1; 32], &vec![2; 20], &b"token".to_vec()).unwrap();
 
     let mut rng = rand::thread_rng();
 
-    let ballots: Vec<BallotVariant> = (0..num_ballots)
+    let ballots: Vec<B::Ballot> = (0..num_ballots)
         .map(|_| {
-            BallotVariant::new(
+            B::encrypt(
+                &public_key,
                 rng.gen_range(1..=(num_candidates - 1)),
                 num_candidates,
-                &public_key,
+                &context,
             )
+            .unwrap()
         })
         .collect();
 
     (ballots, public_key, secret_key)
 }
 
+pub fn sum_sample<B: Backend>(sample: &[B::Ballot], num_candidates: usize) -> B::Tally {
+    let mut sum = B::zero(num_candidates).unwrap();
+
+    for ballot in sample {
+        B::add(&mut sum, ballot).unwrap();
+    }
+
+    sum
+}
+
 #[macro_export]
 macro_rules! generate_benchmark_main {
     ($func:ident, $arg:expr, $num_candidates_values:expr, $num_ballots_values:expr) => {
@@ -46,13 +57,8 @@ macro_rules! generate_benchmark_main {
             let mut sum_ballots_group = criterion.benchmark_group($arg);
 
             for (num_candidates, num_ballots) in combinations {
-                bench_function::<bfv::PublicKey, bfv::SecretKey, BfvBallot>(
-                    &mut sum_ballots_group,
-                    num_candidates,
-                    num_ballots,
-                    "BFV",
-                );
-                bench_function::<elgamal::PublicKey, elgamal::SecretKey, ElGamalBallot>(
+                bench_function::<Bfv>(&mut sum_ballots_group, num_candidates, num_ballots, "BFV");
+                bench_function::<ElGamal>(
                     &mut sum_ballots_group,
                     num_candidates,
                     num_ballots,
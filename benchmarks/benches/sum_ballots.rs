@@ -1,37 +1,24 @@
 mod common;
 
-use std::ops::Add;
+use benchmarks::{Backend, Bfv, ElGamal};
 
-use benchmarks::{
-    ballot::Ballot,
-    bfv::{self, BfvBallot},
-    elgamal::{self, ElGamalBallot},
-};
-
-use common::generate_sample;
+use common::{generate_sample, sum_sample};
 use criterion::{black_box, measurement::WallTime, BenchmarkGroup, BenchmarkId, Criterion};
 
-fn bench_function<PK, SK, BallotType>(
+fn bench_function<B: Backend>(
     group: &mut BenchmarkGroup<'_, WallTime>,
     num_candidates: usize,
     num_ballots: usize,
     alg: &str,
-) where
-    BallotType: Ballot<PK, SK> + Clone + Add<Output = BallotType>,
-{
+) {
     group.bench_with_input(
         BenchmarkId::new(alg, format!("{:04} {:02}", num_ballots, num_candidates)),
         &(num_ballots, num_candidates),
         |b, (num_ballots, num_candidates)| {
-            let (sample, _, _) =
-                generate_sample::<PK, SK, BallotType>(*num_ballots, *num_candidates);
+            let (sample, _, _) = generate_sample::<B>(*num_ballots, *num_candidates);
 
             b.iter(|| {
-                let sum = sample
-                    .iter()
-                    .cloned()
-                    .reduce(|acc, ballot| (acc + ballot))
-                    .unwrap();
+                let sum = sum_sample::<B>(&sample, *num_candidates);
                 black_box(sum);
             })
         },
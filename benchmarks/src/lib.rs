@@ -1,3 +1,2 @@
-pub mod ballot;
-pub mod bfv;
-pub mod elgamal;
+pub use primitives::backend::{Backend, ElGamal};
+pub use primitives::bfv::Bfv;
@@ -1,5 +1,7 @@
 use wasm_bindgen::prelude::*;
 
+use std::error::Error;
+
 use crate::errors::JsError;
 
 #[wasm_bindgen(getter_with_clone)]
@@ -16,6 +18,24 @@ pub struct FlatVec {
     pub component_size: usize,
 }
 
+/// Splits a flattened vector back into its components.
+///
+/// # Arguments
+/// * `data` - The flattened data bytes.
+/// * `component_size` - The size of each individual component.
+///
+/// # Returns
+/// * `Result<Vec<Vec<u8>>, JsError>` - The components, or an error if `data` is not a whole number of components.
+pub(crate) fn unflatten(data: Vec<u8>, component_size: usize) -> Result<Vec<Vec<u8>>, JsError> {
+    if component_size == 0 || !data.len().is_multiple_of(component_size) {
+        return Err(JsError::from(Box::<dyn Error>::from(
+            "Invalid component size",
+        )));
+    }
+
+    Ok(data.chunks(component_size).map(<[u8]>::to_vec).collect())
+}
+
 #[wasm_bindgen(getter_with_clone)]
 /// Structure representing an exported ElGamal key pair.
 ///
@@ -41,7 +61,7 @@ pub struct ExportedKeyPair {
 pub fn generate_elgamal_keypair(k: usize, n: usize) -> Result<ExportedKeyPair, JsError> {
     let keypair = primitives::ballots::generate_elgamal_keypair();
 
-    let shares = primitives::secret_sharing::split_secret(&keypair.1, k, n);
+    let shares = primitives::secret_sharing::split_secret(&keypair.1, k, n)?;
     let share_size = shares[0].len();
     let mut flat_shares = Vec::new();
 
@@ -58,6 +78,117 @@ pub fn generate_elgamal_keypair(k: usize, n: usize) -> Result<ExportedKeyPair, J
     })
 }
 
+#[wasm_bindgen]
+/// Encodes the context a ballot is bound to.
+///
+/// Ballots only verify under the same context, so a copied ballot is rejected for another voter token or election.
+///
+/// # Arguments
+/// * `election_id` - The 32-byte election identifier.
+/// * `contract_address` - The 20-byte address of the election contract.
+/// * `token` - The unblinded voter token the ballot is cast with.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The ABI encoded context, or an error.
+pub fn ballot_context(
+    election_id: Vec<u8>,
+    contract_address: Vec<u8>,
+    token: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::ballot_context(
+        &election_id,
+        &contract_address,
+        &token,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Computes the tracker of a cast ballot, chained to the tracker of the previous ballot.
+///
+/// # Arguments
+/// * `election_id` - The 32-byte election identifier.
+/// * `previous` - The 32-byte tracker of the previous ballot, all zeros for the first one.
+/// * `ballot` - The encrypted ballot bytes as cast.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The 32-byte tracker, or an error.
+pub fn ballot_tracker(
+    election_id: Vec<u8>,
+    previous: Vec<u8>,
+    ballot: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::ballot_tracker(
+        &election_id,
+        &previous,
+        &ballot,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Renders a ballot tracker as a short code such as `7K3M-Q9TX-B2WD-H4RE`.
+///
+/// # Arguments
+/// * `tracker` - The 32-byte tracker from `ballot_tracker`.
+///
+/// # Returns
+/// * `Result<String, JsError>` - The tracking code, or an error.
+pub fn tracker_code(tracker: Vec<u8>) -> Result<String, JsError> {
+    Ok(primitives::ballots::tracker_code(&tracker)?)
+}
+
+#[wasm_bindgen]
+/// Encodes an unblinded voter token in the versioned wire format expected by `verify`.
+///
+/// # Arguments
+/// * `signature` - The unblinded signature of the token.
+/// * `msg_randomizer` - The 32-byte message randomizer the token was blinded with, none in the deterministic mode.
+/// * `election_id` - The 32-byte identifier of the election the token was issued for.
+/// * `scheme` - The blind signature mode of the election, `RSA_BLIND_PSS` or `RSA_BLIND_PSS_DETERMINISTIC`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encoded token, or an error.
+pub fn encode_token(
+    signature: Vec<u8>,
+    msg_randomizer: Option<Vec<u8>>,
+    election_id: Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::encode_token(
+        &signature,
+        &msg_randomizer.unwrap_or_default(),
+        &election_id,
+        scheme,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Encodes an unblinded voter token with the ID of its signing key, as expected by `verify_by_key_id`.
+///
+/// # Arguments
+/// * `signature` - The unblinded signature of the token.
+/// * `msg_randomizer` - The 32-byte message randomizer the token was blinded with, none in the deterministic mode.
+/// * `key_id` - The 32-byte ID of the signing key, as returned with the blinding request.
+/// * `election_id` - The 32-byte identifier of the election the token was issued for.
+/// * `scheme` - The blind signature mode of the election.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encoded token, or an error.
+pub fn encode_keyed_token(
+    signature: Vec<u8>,
+    msg_randomizer: Option<Vec<u8>>,
+    key_id: Vec<u8>,
+    election_id: Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::encode_keyed_token(
+        &signature,
+        &msg_randomizer.unwrap_or_default(),
+        &key_id,
+        &election_id,
+        scheme,
+    )?)
+}
+
 #[wasm_bindgen]
 /// Encrypts a vote choice using ElGamal encryption.
 ///
@@ -65,6 +196,7 @@ pub fn generate_elgamal_keypair(k: usize, n: usize) -> Result<ExportedKeyPair, J
 /// * `pub_key_bytes` - The public key bytes.
 /// * `choice` - The index of the chosen option.
 /// * `options_count` - The total number of options available.
+/// * `context` - The ballot context from `ballot_context`.
 ///
 /// # Returns
 /// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
@@ -72,11 +204,211 @@ pub fn encrypt_vote(
     pub_key_bytes: Vec<u8>,
     choice: usize,
     options_count: usize,
+    context: Vec<u8>,
 ) -> Result<Vec<u8>, JsError> {
     Ok(primitives::ballots::encrypt_vote(
         &pub_key_bytes,
         choice,
         options_count,
+        &context,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Encrypts a vote choice with the homomorphic backend the election was set up with.
+///
+/// # Arguments
+/// * `scheme` - The scheme of the election, e.g. `RISTRETTO_ELGAMAL` or `BFV`.
+/// * `pub_key_bytes` - The public key bytes.
+/// * `choice` - The index of the chosen option.
+/// * `options_count` - The total number of options available.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
+pub fn encrypt_backend_vote(
+    scheme: u8,
+    pub_key_bytes: Vec<u8>,
+    choice: usize,
+    options_count: usize,
+    context: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::backend::encrypt_backend_vote(
+        scheme,
+        &pub_key_bytes,
+        choice,
+        options_count,
+        &context,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Encrypts a vote that selects several options using ElGamal encryption.
+///
+/// The ballot proves that the number of selected options lies between `min` and `max`.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `choices` - The indexes of the chosen options.
+/// * `options_count` - The total number of options available.
+/// * `min` - The minimum number of options to select.
+/// * `max` - The maximum number of options to select.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
+pub fn encrypt_multi_vote(
+    pub_key_bytes: Vec<u8>,
+    choices: Vec<usize>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::encrypt_multi_vote(
+        &pub_key_bytes,
+        &choices,
+        options_count,
+        min,
+        max,
+        &context,
+    )?)
+}
+
+#[wasm_bindgen(getter_with_clone)]
+/// Structure representing an encrypted ballot together with its encryption randomness.
+///
+/// # Fields
+/// * `ballot` - The encrypted ballot bytes.
+/// * `randomness` - The randomness revealed when the ballot is spoiled.
+pub struct AuditableBallot {
+    pub ballot: Vec<u8>,
+    pub randomness: Vec<u8>,
+}
+
+#[wasm_bindgen]
+/// Encrypts a vote choice and keeps its randomness for a cast-or-audit challenge.
+///
+/// Cast the ballot and discard the randomness, or spoil it by publishing the randomness and encrypt a new one.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `choice` - The index of the chosen option.
+/// * `options_count` - The total number of options available.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<AuditableBallot, JsError>` - The encrypted ballot and its randomness, or an error.
+pub fn encrypt_vote_for_audit(
+    pub_key_bytes: Vec<u8>,
+    choice: usize,
+    options_count: usize,
+    context: Vec<u8>,
+) -> Result<AuditableBallot, JsError> {
+    let (ballot, randomness) = primitives::ballots::encrypt_vote_for_audit(
+        &pub_key_bytes,
+        choice,
+        options_count,
+        &context,
+    )?;
+
+    Ok(AuditableBallot { ballot, randomness })
+}
+
+#[wasm_bindgen]
+/// Encrypts a vote that selects several options and keeps its randomness for a cast-or-audit challenge.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `choices` - The indexes of the chosen options.
+/// * `options_count` - The total number of options available.
+/// * `min` - The minimum number of options to select.
+/// * `max` - The maximum number of options to select.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<AuditableBallot, JsError>` - The encrypted ballot and its randomness, or an error.
+pub fn encrypt_multi_vote_for_audit(
+    pub_key_bytes: Vec<u8>,
+    choices: Vec<usize>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: Vec<u8>,
+) -> Result<AuditableBallot, JsError> {
+    let (ballot, randomness) = primitives::ballots::encrypt_multi_vote_for_audit(
+        &pub_key_bytes,
+        &choices,
+        options_count,
+        min,
+        max,
+        &context,
+    )?;
+
+    Ok(AuditableBallot { ballot, randomness })
+}
+
+#[wasm_bindgen]
+/// Recovers the choices of a spoiled ballot from its randomness.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `ballot` - The encrypted ballot bytes.
+/// * `randomness` - The randomness revealed for the ballot.
+/// * `options_count` - The total number of options available.
+/// * `min` - The minimum number of options a voter must select, 1 for single-choice ballots.
+/// * `max` - The maximum number of options a voter may select, 1 for single-choice ballots.
+/// * `context` - The ballot context the ballot was encrypted with.
+///
+/// # Returns
+/// * `Result<Vec<usize>, JsError>` - The indexes of the selected options, or an error if the ballot belongs to another election, its proofs are invalid or it does not match the randomness.
+pub fn audit_ballot(
+    pub_key_bytes: Vec<u8>,
+    ballot: Vec<u8>,
+    randomness: Vec<u8>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: Vec<u8>,
+) -> Result<Vec<usize>, JsError> {
+    Ok(primitives::ballots::audit_ballot(
+        &pub_key_bytes,
+        &ballot,
+        &randomness,
+        options_count,
+        min,
+        max,
+        &context,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Encrypts a vote that gives every candidate a score between 0 and `max` using ElGamal encryption.
+///
+/// With a budget, the ballot also proves that the scores add up to at most `budget` (cumulative voting).
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `scores` - The score of every candidate.
+/// * `max` - The highest score a candidate can get.
+/// * `budget` - The optional upper bound for the sum of the scores.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
+pub fn encrypt_score_vote(
+    pub_key_bytes: Vec<u8>,
+    scores: Vec<u64>,
+    max: u64,
+    budget: Option<u64>,
+    context: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::encrypt_score_vote(
+        &pub_key_bytes,
+        &scores,
+        max,
+        budget,
+        &context,
     )?)
 }
 
@@ -90,6 +422,8 @@ pub fn encrypt_vote(
 /// * `component_size` - The size of each share.
 /// * `k` - The threshold associated with the secret sharing.
 /// * `raw_result` - The aggregated encrypted result bytes.
+/// * `max_score` - The highest value one ballot adds to an option, 1 if omitted. Pass the highest score for score ballots.
+/// * `baby_steps` - The baby steps the discrete log solver stores, about the square root of the largest count if omitted. More make decryption faster and use more memory.
 ///
 /// # Returns
 /// * `Result<Vec<u64>, JsError>` - A vector containing the tally for each option, or an error.
@@ -98,26 +432,95 @@ pub fn decrypt_result(
     component_size: usize,
     k: usize,
     raw_result: Vec<u8>,
+    max_score: Option<u64>,
+    baby_steps: Option<u64>,
 ) -> Result<Vec<u64>, JsError> {
-    let mut shares = Vec::new();
+    let shares = unflatten(flat_vec, component_size)?;
 
-    let mut current_share = Vec::with_capacity(component_size);
+    let secret_key = primitives::secret_sharing::recover_secret(&shares, k)?;
+    Ok(primitives::ballots::decrypt_result(
+        &secret_key,
+        &raw_result,
+        max_score.unwrap_or(1),
+        baby_steps,
+    )?)
+}
 
-    for el in flat_vec {
-        current_share.push(el);
+#[wasm_bindgen]
+/// Computes a trustee's partial decryption of the election result.
+///
+/// The key share never leaves the trustee; only the decryption share and its proof are published.
+///
+/// # Arguments
+/// * `key_share` - The trustee's key share from the distributed key generation.
+/// * `raw_result` - The aggregated encrypted result bytes.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The partial decryption with its proofs of correctness, or an error.
+pub fn decrypt_share(key_share: Vec<u8>, raw_result: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ballots::decrypt_share(&key_share, &raw_result)?)
+}
 
-        if current_share.len() == component_size {
-            shares.push(current_share);
-            current_share = Vec::with_capacity(component_size);
-        }
-    }
+#[wasm_bindgen]
+/// Verifies a trustee's partial decryption of the election result.
+///
+/// # Arguments
+/// * `key_set` - The public key set of the election.
+/// * `raw_result` - The aggregated encrypted result bytes.
+/// * `index` - The 0-based index of the trustee that produced the share.
+/// * `share` - The partial decryption bytes.
+///
+/// # Returns
+/// * `Result<bool, JsError>` - Whether the share is valid, or an error.
+pub fn verify_decryption_share(
+    key_set: Vec<u8>,
+    raw_result: Vec<u8>,
+    index: usize,
+    share: Vec<u8>,
+) -> Result<bool, JsError> {
+    Ok(primitives::ballots::verify_decryption_share(
+        &key_set,
+        &raw_result,
+        index,
+        &share,
+    )?)
+}
 
-    println!("{:?}", shares);
+#[wasm_bindgen]
+/// Combines the partial decryptions of at least `k` trustees into the election result.
+///
+/// Fails naming the offending trustee if any share is invalid.
+///
+/// # Arguments
+/// * `key_set` - The public key set of the election.
+/// * `raw_result` - The aggregated encrypted result bytes.
+/// * `indexes` - The 0-based trustee index of each share.
+/// * `flat_vec` - The flattened partial decryptions, in the same order as `indexes`.
+/// * `component_size` - The size of each partial decryption.
+/// * `max_score` - The highest value one ballot adds to an option, 1 if omitted. Pass the highest score for score ballots.
+/// * `baby_steps` - The baby steps the discrete log solver stores, about the square root of the largest count if omitted. More make decryption faster and use more memory.
+///
+/// # Returns
+/// * `Result<Vec<u64>, JsError>` - A vector containing the tally for each option, or an error.
+pub fn combine_decryption_shares(
+    key_set: Vec<u8>,
+    raw_result: Vec<u8>,
+    indexes: Vec<usize>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+    max_score: Option<u64>,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, JsError> {
+    let shares = indexes
+        .into_iter()
+        .zip(unflatten(flat_vec, component_size)?)
+        .collect();
 
-    let secret_key = primitives::secret_sharing::recover_secret(&shares, k).unwrap();
-    Ok(primitives::ballots::decrypt_result(
-        &secret_key,
+    Ok(primitives::ballots::combine_decryption_shares(
+        &key_set,
         &raw_result,
-        10_000,
+        &shares,
+        max_score.unwrap_or(1),
+        baby_steps,
     )?)
 }
@@ -1,3 +1,4 @@
+use crate::ballots::unflatten;
 use crate::errors::JsError;
 use primitives::{alloy_primitives::U256, alloy_sol_types::SolValue};
 use wasm_bindgen::prelude::*;
@@ -10,9 +11,13 @@ use wasm_bindgen::prelude::*;
 /// # Fields
 /// * `blind_msg` - The blinded message bytes.
 /// * `secret` - The blinding factor (secret) used for unblinding.
+/// * `msg_randomizer` - The message randomizer of randomized and partially blind requests, none in the deterministic mode.
+/// * `key_id` - The ID of the signing key, so that the signer picks the right one.
 pub struct ExportedBlindingResult {
     pub blind_msg: Vec<u8>,
     pub secret: Vec<u8>,
+    pub msg_randomizer: Option<Vec<u8>>,
+    pub key_id: Vec<u8>,
 }
 
 #[wasm_bindgen]
@@ -43,26 +48,31 @@ pub fn encoded_req(client_addr: String, election_id: String, iat: u32) -> Vec<u8
 /// * `client_addr` - The client's address.
 /// * `election_id` - The election ID.
 /// * `iat` - Issued at timestamp.
+/// * `scheme` - The blind signature mode of the election, `RSA_BLIND_PSS` (randomized, recommended) or `RSA_BLIND_PSS_DETERMINISTIC`.
 ///
 /// # Returns
-/// * `Result<ExportedBlindingResult, JsError>` - The blinded message and secret, or an error.
+/// * `Result<ExportedBlindingResult, JsError>` - The blinded message, secret and message randomizer, or an error.
 pub fn create_request(
     public_key: Vec<u8>,
     client_addr: String,
     election_id: String,
     iat: u32,
+    scheme: u8,
 ) -> Result<ExportedBlindingResult, JsError> {
     let encoded = (client_addr, election_id, U256::from(iat)).abi_encode_sequence();
 
-    let request = primitives::blind_signatures::create_request(&public_key, &encoded)?;
+    let request = primitives::blind_signatures::create_request(&public_key, &encoded, scheme)?;
 
     Ok(ExportedBlindingResult {
-        blind_msg: request.0,
-        secret: request.1,
+        blind_msg: request.blind_message,
+        secret: request.secret,
+        msg_randomizer: Some(request.msg_randomizer).filter(|randomizer| !randomizer.is_empty()),
+        key_id: primitives::blind_signatures::key_id(&public_key)?,
     })
 }
 
 #[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
 /// Unblinds a blind signature.
 ///
 /// Verify and unblind the signature received from the signer using the original blinding secret.
@@ -73,7 +83,9 @@ pub fn create_request(
 /// * `election_id` - The election ID in the original request.
 /// * `iat` - Issued at timestamp in the original request.
 /// * `secret` - The blinding secret generated during request creation.
+/// * `msg_randomizer` - The message randomizer generated during request creation, none in the deterministic mode.
 /// * `blind_sig` - The blind signature received from the signer.
+/// * `scheme` - The blind signature mode the request was created in.
 ///
 /// # Returns
 /// * `Result<Vec<u8>, JsError>` - The unblinded, valid signature, or an error.
@@ -83,11 +95,160 @@ pub fn unblind(
     election_id: String,
     iat: u32,
     secret: Vec<u8>,
+    msg_randomizer: Option<Vec<u8>>,
     blind_sig: Vec<u8>,
+    scheme: u8,
 ) -> Result<Vec<u8>, JsError> {
     let msg = (client_addr, election_id, U256::from(iat)).abi_encode_sequence();
 
     Ok(primitives::blind_signatures::unblind(
-        &public_key, &msg, secret, blind_sig,
+        &public_key,
+        &msg,
+        secret,
+        &msg_randomizer.unwrap_or_default(),
+        blind_sig,
+        scheme,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Creates a partially blind signing request.
+///
+/// Only the client address and issued at timestamp are blinded. The election ID and expiry
+/// are public metadata the signer derives its key from, so the token is bound to them.
+///
+/// # Arguments
+/// * `public_key` - The signer's master public key.
+/// * `client_addr` - The client's address.
+/// * `iat` - Issued at timestamp.
+/// * `election_id` - The 32-byte election ID.
+/// * `expiry` - Timestamp after which the token is no longer valid.
+///
+/// # Returns
+/// * `Result<ExportedBlindingResult, JsError>` - The blinded message, secret and message randomizer, or an error.
+pub fn create_partially_blind_request(
+    public_key: Vec<u8>,
+    client_addr: String,
+    iat: u32,
+    election_id: Vec<u8>,
+    expiry: u32,
+) -> Result<ExportedBlindingResult, JsError> {
+    let encoded = (client_addr, U256::from(iat)).abi_encode_sequence();
+
+    let request = primitives::blind_signatures::create_partially_blind_request(
+        &public_key,
+        &encoded,
+        &election_id,
+        expiry.into(),
+    )?;
+
+    Ok(ExportedBlindingResult {
+        blind_msg: request.blind_message,
+        secret: request.secret,
+        msg_randomizer: Some(request.msg_randomizer),
+        key_id: primitives::blind_signatures::key_id(&public_key)?,
+    })
+}
+
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+/// Unblinds a partially blind signature.
+///
+/// # Arguments
+/// * `public_key` - The signer's master public key.
+/// * `client_addr` - The client's address in the original request.
+/// * `iat` - Issued at timestamp in the original request.
+/// * `election_id` - The election ID in the original request.
+/// * `expiry` - The expiry in the original request.
+/// * `secret` - The blinding secret generated during request creation.
+/// * `msg_randomizer` - The message randomizer generated during request creation.
+/// * `blind_sig` - The blind signature received from the signer.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The unblinded, valid signature, or an error.
+pub fn unblind_partially_blind(
+    public_key: Vec<u8>,
+    client_addr: String,
+    iat: u32,
+    election_id: Vec<u8>,
+    expiry: u32,
+    secret: Vec<u8>,
+    msg_randomizer: Vec<u8>,
+    blind_sig: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    let msg = (client_addr, U256::from(iat)).abi_encode_sequence();
+
+    Ok(primitives::blind_signatures::unblind_partially_blind(
+        &public_key,
+        &msg,
+        &election_id,
+        expiry.into(),
+        secret,
+        &msg_randomizer,
+        blind_sig,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Combines the partial blind signatures of the registrars into a blind signature.
+///
+/// Partial signatures that cannot be decoded or have an invalid proof are skipped. The result is unblinded with `unblind`, as a blind signature from a single signer.
+///
+/// # Arguments
+/// * `key_set` - The key set published when the signing key was dealt.
+/// * `blind_msg` - The blinded message of the request.
+/// * `flat_vec` - The flattened vector of partial signatures.
+/// * `component_size` - The size of each partial signature.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The blind signature, or an error if fewer than the threshold of partial signatures are valid.
+pub fn combine_threshold_partials(
+    key_set: Vec<u8>,
+    blind_msg: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let partials = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::blind_signatures::combine_threshold_partials(
+        &key_set, &blind_msg, &partials,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Bundles the tokens several registrars issued for the same voter message.
+///
+/// Each token comes from `create_request` and `unblind` with the key of its registrar, for the same client address, election ID and timestamp.
+///
+/// # Arguments
+/// * `registrars` - The index of the registrar of every signature, in the list of registrar keys of the election.
+/// * `flat_vec` - The flattened vector of unblinded signatures.
+/// * `component_size` - The size of each signature.
+/// * `msg_randomizers` - The flattened vector of 32-byte message randomizers, in the order of the signatures, or empty in the deterministic mode.
+/// * `election_id` - The 32-byte election ID.
+/// * `scheme` - The blind signature mode of the election.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encoded eligibility bundle, or an error.
+pub fn encode_eligibility_bundle(
+    registrars: Vec<usize>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+    msg_randomizers: Vec<u8>,
+    election_id: Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, JsError> {
+    let signatures = unflatten(flat_vec, component_size)?;
+    let msg_randomizers = match msg_randomizers.is_empty() {
+        true => vec![Vec::new(); signatures.len()],
+        false => unflatten(msg_randomizers, 32)?,
+    };
+
+    Ok(primitives::blind_signatures::encode_eligibility_bundle(
+        &registrars,
+        &signatures,
+        &msg_randomizers,
+        &election_id,
+        scheme,
     )?)
 }
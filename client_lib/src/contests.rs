@@ -0,0 +1,48 @@
+use wasm_bindgen::prelude::*;
+
+use std::error::Error;
+
+use crate::errors::JsError;
+
+#[wasm_bindgen]
+/// Encrypts the selections of every contest of a ballot manifest into a single ballot.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `manifest` - The ABI encoded ballot manifest.
+/// * `selections` - The flattened selections of all contests, in manifest order.
+/// * `selection_lengths` - The number of values in the selection of each contest.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
+pub fn encrypt_contest_votes(
+    pub_key_bytes: Vec<u8>,
+    manifest: Vec<u8>,
+    selections: Vec<u64>,
+    selection_lengths: Vec<usize>,
+    context: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    if selection_lengths.iter().sum::<usize>() != selections.len() {
+        return Err(JsError::from(Box::<dyn Error>::from(
+            "Selection lengths do not match the selections",
+        )));
+    }
+
+    let mut rest = selections.as_slice();
+    let selections = selection_lengths
+        .into_iter()
+        .map(|len| {
+            let (selection, tail) = rest.split_at(len);
+            rest = tail;
+            selection.to_vec()
+        })
+        .collect();
+
+    Ok(primitives::contests::encrypt_contest_votes(
+        &pub_key_bytes,
+        &manifest,
+        &selections,
+        &context,
+    )?)
+}
@@ -0,0 +1,190 @@
+use wasm_bindgen::prelude::*;
+
+use crate::ballots::unflatten;
+use crate::errors::JsError;
+
+#[wasm_bindgen]
+/// Starts the distributed key generation ceremony for one trustee.
+///
+/// The returned state holds the trustee's secret polynomial and must never leave the trustee's browser.
+///
+/// # Arguments
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `index` - The 0-based index of this trustee.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The trustee state for the commitment phase, or an error.
+pub fn generate_participant(k: usize, n: usize, index: usize) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::generate_participant(k, n, index)?)
+}
+
+#[wasm_bindgen]
+/// Returns the commitment a trustee publishes during the first phase.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `generate_participant`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The 32-byte commitment, or an error.
+pub fn get_commitment(state: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::get_commitment(&state)?)
+}
+
+#[wasm_bindgen]
+/// Inserts the commitments of every trustee and moves to the polynomial phase.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `generate_participant`.
+/// * `flat_vec` - The flattened commitments of all trustees, ordered by index.
+/// * `component_size` - The size of each commitment.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The trustee state for the polynomial phase, or an error.
+pub fn insert_commitments(
+    state: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let commitments = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::dkg::insert_commitments(&state, &commitments)?)
+}
+
+#[wasm_bindgen]
+/// Returns the public information a trustee publishes during the second phase.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `insert_commitments`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The public polynomial, proof of possession and commitment opening, or an error.
+pub fn get_public_info(state: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::get_public_info(&state)?)
+}
+
+#[wasm_bindgen]
+/// Inserts the public information of every trustee and moves to the share exchange phase.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `insert_commitments`.
+/// * `flat_vec` - The flattened public information of all trustees, ordered by index.
+/// * `component_size` - The size of each public information entry.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The trustee state for the share exchange phase, or an error.
+pub fn insert_public_infos(
+    state: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let infos = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::dkg::insert_public_infos(&state, &infos)?)
+}
+
+#[wasm_bindgen]
+/// Returns the secret share a trustee sends to another trustee.
+///
+/// The share must be encrypted for the receiving trustee (e.g. with `ecc_encrypt`) before it is sent.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `insert_public_infos`.
+/// * `participant_index` - The 0-based index of the receiving trustee.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The secret share bytes, or an error.
+pub fn get_secret_share(state: Vec<u8>, participant_index: usize) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::get_secret_share(
+        &state,
+        participant_index,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Inserts the secret shares received from every trustee and completes the ceremony.
+///
+/// # Arguments
+/// * `state` - The trustee state returned by `insert_public_infos`.
+/// * `flat_vec` - The flattened secret shares addressed to this trustee, ordered by sender index.
+/// * `component_size` - The size of each secret share.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The trustee's key share, or an error.
+pub fn insert_secret_shares(
+    state: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let shares = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::dkg::insert_secret_shares(&state, &shares)?)
+}
+
+#[wasm_bindgen]
+/// Returns the joint election public key held by a trustee after the ceremony.
+///
+/// # Arguments
+/// * `key_share` - The key share returned by `insert_secret_shares`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The joint public key bytes, or an error.
+pub fn get_shared_public_key(key_share: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::get_shared_public_key(&key_share)?)
+}
+
+#[wasm_bindgen]
+/// Returns the public key set held by a trustee after the ceremony.
+///
+/// # Arguments
+/// * `key_share` - The key share returned by `insert_secret_shares`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The public key set used to verify partial decryptions, or an error.
+pub fn get_key_set(key_share: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::dkg::get_key_set(&key_share)?)
+}
+
+#[wasm_bindgen]
+/// Derives the public key set from the public information published by the trustees.
+///
+/// # Arguments
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `flat_vec` - The flattened public information of all trustees, ordered by index.
+/// * `component_size` - The size of each public information entry.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The public key set used to verify partial decryptions, or an error.
+pub fn derive_key_set(
+    k: usize,
+    n: usize,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let infos = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::dkg::derive_key_set(k, n, &infos)?)
+}
+
+#[wasm_bindgen]
+/// Derives the joint election public key from the public information published by the trustees.
+///
+/// # Arguments
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `flat_vec` - The flattened public information of all trustees, ordered by index.
+/// * `component_size` - The size of each public information entry.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The joint public key bytes, or an error.
+pub fn derive_public_key(
+    k: usize,
+    n: usize,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+) -> Result<Vec<u8>, JsError> {
+    let infos = unflatten(flat_vec, component_size)?;
+
+    Ok(primitives::dkg::derive_public_key(k, n, &infos)?)
+}
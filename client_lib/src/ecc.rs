@@ -1,5 +1,7 @@
 use wasm_bindgen::prelude::*;
 
+use crate::errors::JsError;
+
 #[wasm_bindgen]
 /// Encrypts a message using Elliptic Curve Integrated Encryption Scheme (ECIES).
 ///
@@ -8,12 +10,9 @@ use wasm_bindgen::prelude::*;
 /// * `msg` - The message bytes to encrypt.
 ///
 /// # Returns
-/// * `Vec<u8>` - The encrypted message bytes.
-///
-/// # Panics
-/// Panics if the encryption fails.
-pub fn ecc_encrypt(pk: Vec<u8>, msg: Vec<u8>) -> Vec<u8> {
-    primitives::ecc::ecc_encrypt(&pk, &msg).unwrap()
+/// * `Result<Vec<u8>, JsError>` - The encrypted message bytes, or an error if the encryption fails.
+pub fn ecc_encrypt(pk: Vec<u8>, msg: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ecc::ecc_encrypt(&pk, &msg)?)
 }
 #[wasm_bindgen]
 
@@ -24,12 +23,9 @@ pub fn ecc_encrypt(pk: Vec<u8>, msg: Vec<u8>) -> Vec<u8> {
 /// * `encrypted` - The encrypted message bytes.
 ///
 /// # Returns
-/// * `Vec<u8>` - The decrypted message bytes.
-///
-/// # Panics
-/// Panics if the decryption fails.
-pub fn ecc_decrypt(sk: Vec<u8>, encrypted: Vec<u8>) -> Vec<u8> {
-    primitives::ecc::ecc_decrypt(&sk, &encrypted).unwrap()
+/// * `Result<Vec<u8>, JsError>` - The decrypted message bytes, or an error if the decryption fails.
+pub fn ecc_decrypt(sk: Vec<u8>, encrypted: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ecc::ecc_decrypt(&sk, &encrypted)?)
 }
 
 
@@ -14,8 +14,14 @@ impl From<Box<dyn Error>> for JsError {
     }
 }
 
-impl Into<JsValue> for JsError {
-    fn into(self) -> JsValue {
-        JsValue::from_str(&self.0.to_string())
+impl From<primitives::Error> for JsError {
+    fn from(err: primitives::Error) -> Self {
+        JsError(Box::new(err))
+    }
+}
+
+impl From<JsError> for JsValue {
+    fn from(val: JsError) -> Self {
+        JsValue::from_str(&val.0.to_string())
     }
 }
@@ -2,5 +2,9 @@ pub mod errors;
 
 pub mod ballots;
 pub mod blind_signatures;
+pub mod contests;
+pub mod dkg;
 pub mod ecc;
+pub mod mixnet;
+pub mod ranked_ballots;
 pub mod signatures;
@@ -0,0 +1,95 @@
+use wasm_bindgen::prelude::*;
+
+use crate::ballots::unflatten;
+use crate::errors::JsError;
+
+#[wasm_bindgen]
+/// Encrypts a write-in or free-form answer for the mix-net.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `message` - The answer to encrypt, shorter than `width * 30` bytes.
+/// * `width` - The number of 30-byte chunks every answer of the election is padded to.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted answer bytes, or an error.
+pub fn encrypt_message(
+    pub_key_bytes: Vec<u8>,
+    message: Vec<u8>,
+    width: usize,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::mixnet::encrypt_message(
+        &pub_key_bytes,
+        &message,
+        width,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Shuffles the output of a mix chain and appends the shuffle with its proof.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `election_id` - The 32-byte identifier of the election, which the proof commits to.
+/// * `flat_vec` - The flattened encrypted answers submitted by the voters.
+/// * `component_size` - The size of each encrypted answer.
+/// * `chain` - The chain returned by the previous trustee, empty for the first one.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The extended chain, or an error.
+pub fn shuffle(
+    pub_key_bytes: Vec<u8>,
+    election_id: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+    chain: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    let messages = unflatten(flat_vec, component_size)?;
+    Ok(primitives::mixnet::shuffle(
+        &pub_key_bytes,
+        &election_id,
+        &messages,
+        &chain,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Verifies every shuffle of a mix chain.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `election_id` - The 32-byte identifier of the election the chain was shuffled for.
+/// * `flat_vec` - The flattened encrypted answers submitted by the voters.
+/// * `component_size` - The size of each encrypted answer.
+/// * `chain` - The chain returned by the last trustee.
+///
+/// # Returns
+/// * `Result<bool, JsError>` - Whether the chain is valid, or an error.
+pub fn verify_shuffle_chain(
+    pub_key_bytes: Vec<u8>,
+    election_id: Vec<u8>,
+    flat_vec: Vec<u8>,
+    component_size: usize,
+    chain: Vec<u8>,
+) -> Result<bool, JsError> {
+    let messages = unflatten(flat_vec, component_size)?;
+    Ok(primitives::mixnet::verify_shuffle_chain(
+        &pub_key_bytes,
+        &election_id,
+        &messages,
+        &chain,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Computes a trustee's partial decryption of the output of a mix chain.
+///
+/// # Arguments
+/// * `key_share` - The trustee's key share from the distributed key generation.
+/// * `chain` - The chain returned by the last trustee, verified beforehand.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The partial decryption with its proofs of correctness, or an error.
+pub fn decrypt_mix_share(key_share: Vec<u8>, chain: Vec<u8>) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::mixnet::decrypt_share(&key_share, &chain)?)
+}
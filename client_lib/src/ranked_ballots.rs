@@ -0,0 +1,60 @@
+use wasm_bindgen::prelude::*;
+
+use crate::errors::JsError;
+
+#[wasm_bindgen]
+/// Encrypts a ranking of all the candidates as a pairwise preference matrix.
+///
+/// # Arguments
+/// * `pub_key_bytes` - The public key bytes.
+/// * `ranking` - The candidate indexes from most to least preferred.
+/// * `options_count` - The total number of candidates.
+/// * `context` - The ballot context from `ballot_context`.
+///
+/// # Returns
+/// * `Result<Vec<u8>, JsError>` - The encrypted ballot bytes, or an error.
+pub fn encrypt_ranked_vote(
+    pub_key_bytes: Vec<u8>,
+    ranking: Vec<usize>,
+    options_count: usize,
+    context: Vec<u8>,
+) -> Result<Vec<u8>, JsError> {
+    Ok(primitives::ranked_ballots::encrypt_ranked_vote(
+        &pub_key_bytes,
+        &ranking,
+        options_count,
+        &context,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Finds the Condorcet winner from the decrypted pairwise matrix.
+///
+/// # Arguments
+/// * `matrix` - The decrypted accumulator, row-major.
+/// * `options_count` - The total number of candidates.
+///
+/// # Returns
+/// * `Result<Option<usize>, JsError>` - The candidate that beats every other one head-to-head, if any, or an error.
+pub fn condorcet_winner(matrix: Vec<u64>, options_count: usize) -> Result<Option<usize>, JsError> {
+    Ok(primitives::ranked_ballots::condorcet_winner(
+        &matrix,
+        options_count,
+    )?)
+}
+
+#[wasm_bindgen]
+/// Computes the Schulze winners from the decrypted pairwise matrix.
+///
+/// # Arguments
+/// * `matrix` - The decrypted accumulator, row-major.
+/// * `options_count` - The total number of candidates.
+///
+/// # Returns
+/// * `Result<Vec<usize>, JsError>` - The winning candidates (more than one only in case of a tie), or an error.
+pub fn schulze_winners(matrix: Vec<u64>, options_count: usize) -> Result<Vec<usize>, JsError> {
+    Ok(primitives::ranked_ballots::schulze_winners(
+        &matrix,
+        options_count,
+    )?)
+}
@@ -1,7 +1,12 @@
-use blind_signatures::ballots::{encrypt_vote, decrypt_result, generate_elgamal_keypair};
-use primitives::ballots::{generate_acc, add_votes};
+mod common;
+
+use blind_signatures::ballots::{encrypt_vote, encrypt_multi_vote, encrypt_score_vote, decrypt_result, generate_elgamal_keypair, encrypt_vote_for_audit, encrypt_multi_vote_for_audit, audit_ballot, ballot_tracker, tracker_code, encode_token, encrypt_backend_vote};
+use primitives::ballots::{generate_acc, add_votes, add_multi_votes, add_score_votes, verify_multi_vote, verify_score_vote};
+use primitives::backend::{generate_backend_acc, generate_backend_keypair};
+use primitives::envelope::{inspect, BFV, RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC, TOKEN, VERSION};
 use primitives::alloy_sol_types::SolValue;
-use primitives::alloy_primitives::U256;
+use primitives::alloy_primitives::{B256, U256};
+use common::context;
 
 #[test]
 fn test_ballots_flow() {
@@ -10,22 +15,157 @@ fn test_ballots_flow() {
     let keypair = generate_elgamal_keypair(k, n).unwrap();
     
     let options_count = 5;
-    let encoded_count = U256::from(options_count).abi_encode();
-    let mut acc = generate_acc(&encoded_count);
+    let encoded_count = (U256::from(options_count), B256::from([1; 32])).abi_encode_sequence();
+    let mut acc = generate_acc(&encoded_count).unwrap();
 
     let choice = 1;
-    let vote_bytes = encrypt_vote(keypair.public.clone(), choice, options_count).unwrap();
+    let vote_bytes = encrypt_vote(keypair.public.clone(), choice, options_count, context()).unwrap();
     
     let encoded_add = (acc.clone(), vote_bytes).abi_encode_sequence();
-    acc = add_votes(&encoded_add);
+    acc = add_votes(&encoded_add).unwrap();
     
     let result = decrypt_result(
         keypair.private.data, 
         keypair.private.component_size, 
         k, 
-        acc
+        acc,
+        None,
+        None
     ).unwrap();
     
     assert_eq!(result[choice], 1);
     assert_eq!(result[0], 0);
 }
+
+#[test]
+fn test_multi_choice_ballots_flow() {
+    let k = 2;
+    let n = 3;
+    let keypair = generate_elgamal_keypair(k, n).unwrap();
+
+    let options_count = 4;
+    let mut acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence()).unwrap();
+
+    // Approval ballot: any number of options
+    let vote_bytes = encrypt_multi_vote(keypair.public.clone(), vec![1, 2, 3], options_count, 0, options_count, context()).unwrap();
+
+    let encoded_verify = (
+        U256::from(options_count),
+        U256::from(0),
+        U256::from(options_count),
+        context(),
+        keypair.public.clone(),
+        vote_bytes.clone(),
+    )
+        .abi_encode_sequence();
+    assert_eq!(verify_multi_vote(&encoded_verify).unwrap()[31], 1);
+
+    let encoded_add = (acc.clone(), vote_bytes).abi_encode_sequence();
+    acc = add_multi_votes(&encoded_add).unwrap();
+
+    let result = decrypt_result(
+        keypair.private.data,
+        keypair.private.component_size,
+        k,
+        acc,
+        None,
+        None
+    ).unwrap();
+
+    assert_eq!(result, vec![0, 1, 1, 1]);
+}
+
+#[test]
+fn test_audit_ballot_flow() {
+    let keypair = generate_elgamal_keypair(2, 3).unwrap();
+
+    let spoiled = encrypt_vote_for_audit(keypair.public.clone(), 3, 5, context()).unwrap();
+    let choices = audit_ballot(keypair.public.clone(), spoiled.ballot.clone(), spoiled.randomness.clone(), 5, 1, 1, context()).unwrap();
+    assert_eq!(choices, vec![3]);
+
+    let multi = encrypt_multi_vote_for_audit(keypair.public.clone(), vec![1, 4], 5, 0, 2, context()).unwrap();
+    assert_eq!(audit_ballot(keypair.public.clone(), multi.ballot, multi.randomness.clone(), 5, 0, 2, context()).unwrap(), vec![1, 4]);
+
+    // The randomness of one ballot does not open another
+    assert!(audit_ballot(keypair.public, spoiled.ballot, multi.randomness, 5, 1, 1, context()).is_err());
+}
+
+#[test]
+fn test_ballot_tracker() {
+    let genesis = vec![0; 32];
+    let first = ballot_tracker(vec![1; 32], genesis, b"ballot".to_vec()).unwrap();
+    let second = ballot_tracker(vec![1; 32], first.clone(), b"ballot".to_vec()).unwrap();
+
+    // Same vectors as the native bindings
+    assert_eq!(tracker_code(first).unwrap(), "YQ0S-VZCC-A5RM-2QWH");
+    assert_eq!(tracker_code(second).unwrap(), "AH09-TS42-3TB1-7SXC");
+    assert!(tracker_code(vec![0; 31]).is_err());
+}
+
+#[test]
+fn test_score_ballots_flow() {
+    let k = 2;
+    let n = 3;
+    let keypair = generate_elgamal_keypair(k, n).unwrap();
+
+    let options_count = 3;
+    let mut acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence()).unwrap();
+
+    // Cumulative ballot: 5 points over candidates rated up to 3
+    let vote_bytes = encrypt_score_vote(keypair.public.clone(), vec![3, 0, 2], 3, Some(5), context()).unwrap();
+    assert!(encrypt_score_vote(keypair.public.clone(), vec![3, 1, 2], 3, Some(5), context()).is_err());
+
+    let encoded_verify = (
+        U256::from(options_count),
+        U256::from(3),
+        U256::from(5),
+        context(),
+        keypair.public.clone(),
+        vote_bytes.clone(),
+    )
+        .abi_encode_sequence();
+    assert_eq!(verify_score_vote(&encoded_verify).unwrap()[31], 1);
+
+    let encoded_add = (acc.clone(), vote_bytes).abi_encode_sequence();
+    acc = add_score_votes(&encoded_add).unwrap();
+
+    let result = decrypt_result(
+        keypair.private.data,
+        keypair.private.component_size,
+        k,
+        acc,
+        Some(3),
+        None
+    ).unwrap();
+
+    assert_eq!(result, vec![3, 0, 2]);
+}
+
+#[test]
+fn test_encode_token() {
+    let token = encode_token(vec![5; 256], Some(vec![6; 32]), vec![1; 32], RSA_BLIND_PSS).unwrap();
+    assert_eq!(inspect(&token).unwrap(), (VERSION, RSA_BLIND_PSS, TOKEN, vec![1; 32]));
+
+    assert!(encode_token(vec![5; 256], Some(vec![6; 31]), vec![1; 32], RSA_BLIND_PSS).is_err());
+    assert!(encode_token(vec![5; 256], Some(vec![6; 32]), vec![1; 20], RSA_BLIND_PSS).is_err());
+
+    // Deterministic tokens have no message randomizer
+    assert!(encode_token(vec![5; 256], None, vec![1; 32], RSA_BLIND_PSS).is_err());
+    assert!(encode_token(vec![5; 256], None, vec![1; 32], RSA_BLIND_PSS_DETERMINISTIC).is_ok());
+}
+
+#[test]
+fn test_encrypt_backend_vote() {
+    let (pk, sk) = generate_backend_keypair(BFV).unwrap();
+
+    let encoded_count = (U256::from(3), B256::from([1; 32]), U256::from(BFV)).abi_encode_sequence();
+    let acc = generate_backend_acc(&encoded_count).unwrap();
+
+    let ballot = encrypt_backend_vote(BFV, pk.clone(), 2, 3, context()).unwrap();
+    assert_eq!(inspect(&ballot).unwrap().1, BFV);
+
+    let acc = add_votes(&(acc, ballot).abi_encode_sequence()).unwrap();
+    assert_eq!(primitives::ballots::decrypt_result(&sk, &acc, 1, None).unwrap(), vec![0, 0, 1]);
+
+    assert!(encrypt_backend_vote(9, pk, 2, 3, context()).is_err());
+}
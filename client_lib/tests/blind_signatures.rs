@@ -1,5 +1,15 @@
-use blind_signatures::blind_signatures::{create_request, encoded_req, unblind};
+use alloy_primitives::U256;
+use alloy_sol_types::SolValue;
+use blind_signatures::blind_signatures::{
+    combine_threshold_partials, create_partially_blind_request, create_request, encode_eligibility_bundle,
+    encoded_req, unblind, unblind_partially_blind,
+};
+use primitives::blind_signatures::{
+    deal_threshold_key, sign_partially_blind, sign_threshold_partial, verify_eligibility_bundle,
+    verify_partially_blind,
+};
 use primitives::blind_signatures::{generate_rsa_keypair, sign as blind_sign, verify as blind_verify};
+use primitives::envelope::{RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC};
 
 #[test]
 fn test_encoded_req() {
@@ -23,8 +33,10 @@ fn test_blind_signature_flow() {
         keypair.public.clone(),
         client_addr.clone(),
         election_id.clone(),
-        iat
+        iat,
+        RSA_BLIND_PSS
     ).unwrap();
+    let msg_randomizer = blinding_result.msg_randomizer.unwrap();
 
     let blinded_sig = blind_sign(&keypair.private, &blinding_result.blind_msg).unwrap();
     
@@ -34,9 +46,260 @@ fn test_blind_signature_flow() {
         election_id.clone(),
         iat,
         blinding_result.secret,
+        Some(msg_randomizer.clone()),
+        blinded_sig,
+        RSA_BLIND_PSS
+    ).unwrap();
+
+    let msg = encoded_req(client_addr, election_id, iat);
+    assert!(blind_verify(&keypair.public, unblinded_sig, &msg_randomizer, &msg, RSA_BLIND_PSS).unwrap());
+}
+
+/// Tests that tokens issued through this crate verify through the `verify` precompile, in both blind signature modes.
+#[test]
+fn test_token_verifies_through_precompile() {
+    use blind_rsa_signatures::{PublicKey, Randomized, Sha384, PSS};
+    use blind_signatures::ballots::encode_token;
+    use primitives::ballots::verify;
+    use primitives::blind_signatures::nullifier;
+
+    let keypair = generate_rsa_keypair().unwrap();
+    let public_key_pem = PublicKey::<Sha384, PSS, Randomized>::from_der(&keypair.public)
+        .unwrap()
+        .to_pem()
+        .unwrap();
+
+    let client_addr = "0x123".to_string();
+    let election_id = "election1".to_string();
+    let iat = 12345;
+
+    for scheme in [RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC] {
+        let blinding_result = create_request(
+            keypair.public.clone(),
+            client_addr.clone(),
+            election_id.clone(),
+            iat,
+            scheme
+        ).unwrap();
+        assert_eq!(blinding_result.msg_randomizer.is_some(), scheme == RSA_BLIND_PSS);
+
+        let blinded_sig = blind_sign(&keypair.private, &blinding_result.blind_msg).unwrap();
+        let unblinded_sig = unblind(
+            keypair.public.clone(),
+            client_addr.clone(),
+            election_id.clone(),
+            iat,
+            blinding_result.secret,
+            blinding_result.msg_randomizer.clone(),
+            blinded_sig,
+            scheme
+        ).unwrap();
+
+        let token = encode_token(unblinded_sig.clone(), blinding_result.msg_randomizer, vec![1; 32], scheme).unwrap();
+
+        let msg = encoded_req(client_addr.clone(), election_id.clone(), iat);
+        let input = (
+            public_key_pem.clone(),
+            alloy_primitives::Bytes::from(token),
+            alloy_primitives::Bytes::from(msg.clone()),
+            U256::from(scheme)
+        ).abi_encode_sequence();
+        let output = verify(&input).unwrap();
+        assert_eq!(output[31], 1);
+        assert_eq!(output[32..], nullifier(&msg, &unblinded_sig)[..]);
+    }
+}
+
+#[test]
+fn test_partially_blind_signature_flow() {
+    // Partially blind keys take minutes to generate, so a fixed one is used
+    let public_key = include_bytes!("../../primitives/tests/data/partially_blind_key.pub.der").to_vec();
+    let private_key = include_bytes!("../../primitives/tests/data/partially_blind_key.der").to_vec();
+
+    let client_addr = "0x123".to_string();
+    let election_id = vec![7; 32];
+    let iat = 12345;
+    let expiry = 1_900_000_000;
+
+    let blinding_result = create_partially_blind_request(
+        public_key.clone(),
+        client_addr.clone(),
+        iat,
+        election_id.clone(),
+        expiry
+    ).unwrap();
+    let msg_randomizer = blinding_result.msg_randomizer.unwrap();
+
+    let blinded_sig = sign_partially_blind(
+        &private_key,
+        &blinding_result.blind_msg,
+        &election_id,
+        expiry.into()
+    ).unwrap();
+
+    let unblinded_sig = unblind_partially_blind(
+        public_key.clone(),
+        client_addr.clone(),
+        iat,
+        election_id.clone(),
+        expiry,
+        blinding_result.secret,
+        msg_randomizer.clone(),
         blinded_sig
     ).unwrap();
 
+    let msg = (client_addr, U256::from(iat)).abi_encode_sequence();
+    assert!(verify_partially_blind(&public_key, unblinded_sig.clone(), &msg_randomizer, &msg, &election_id, expiry.into()).unwrap());
+
+    // The signature is bound to the election it was issued for
+    assert!(!verify_partially_blind(&public_key, unblinded_sig, &msg_randomizer, &msg, &vec![8; 32], expiry.into()).unwrap());
+}
+
+#[test]
+fn test_threshold_blind_signature_flow() {
+    // Dealing needs a modulus of safe primes, as the partially blind key has
+    let private_key = include_bytes!("../../primitives/tests/data/partially_blind_key.der").to_vec();
+    let key = deal_threshold_key(&private_key, 2, 3).unwrap();
+
+    let client_addr = "0x123".to_string();
+    let election_id = "election1".to_string();
+    let iat = 12345;
+
+    let blinding_result = create_request(
+        key.public.clone(),
+        client_addr.clone(),
+        election_id.clone(),
+        iat,
+        RSA_BLIND_PSS
+    ).unwrap();
+    let msg_randomizer = blinding_result.msg_randomizer.unwrap();
+
+    let partials: Vec<Vec<u8>> = key.shares[1..]
+        .iter()
+        .map(|share| sign_threshold_partial(&key.key_set, share, &blinding_result.blind_msg).unwrap())
+        .collect();
+    let component_size = partials[0].len();
+
+    let blinded_sig = combine_threshold_partials(
+        key.key_set.clone(),
+        blinding_result.blind_msg.clone(),
+        partials.concat(),
+        component_size
+    ).unwrap();
+
+    let unblinded_sig = unblind(
+        key.public.clone(),
+        client_addr.clone(),
+        election_id.clone(),
+        iat,
+        blinding_result.secret,
+        Some(msg_randomizer.clone()),
+        blinded_sig,
+        RSA_BLIND_PSS
+    ).unwrap();
+
+    let msg = encoded_req(client_addr, election_id, iat);
+    assert!(blind_verify(&key.public, unblinded_sig, &msg_randomizer, &msg, RSA_BLIND_PSS).unwrap());
+}
+
+#[test]
+fn test_eligibility_bundle() {
+    let registrars: Vec<_> = (0..3).map(|_| generate_rsa_keypair().unwrap()).collect();
+    let public_keys: Vec<Vec<u8>> = registrars.iter().map(|registrar| registrar.public.clone()).collect();
+
+    let client_addr = "0x123".to_string();
+    let election_id = "election1".to_string();
+    let iat = 12345;
+
+    // Registrars 1 and 2 vouch for the voter
+    let (signatures, msg_randomizers): (Vec<Vec<u8>>, Vec<Vec<u8>>) = registrars[1..]
+        .iter()
+        .map(|registrar| {
+            let blinding_result = create_request(
+                registrar.public.clone(),
+                client_addr.clone(),
+                election_id.clone(),
+                iat,
+                RSA_BLIND_PSS
+            ).unwrap();
+            let blinded_sig = blind_sign(&registrar.private, &blinding_result.blind_msg).unwrap();
+            let unblinded_sig = unblind(
+                registrar.public.clone(),
+                client_addr.clone(),
+                election_id.clone(),
+                iat,
+                blinding_result.secret,
+                blinding_result.msg_randomizer.clone(),
+                blinded_sig,
+                RSA_BLIND_PSS
+            ).unwrap();
+            (unblinded_sig, blinding_result.msg_randomizer.unwrap())
+        })
+        .unzip();
+    let component_size = signatures[0].len();
+
+    let bundle = encode_eligibility_bundle(
+        vec![1, 2],
+        signatures.concat(),
+        component_size,
+        msg_randomizers.concat(),
+        vec![1; 32],
+        RSA_BLIND_PSS
+    ).unwrap();
+
+    let msg = encoded_req(client_addr, election_id, iat);
+    assert!(verify_eligibility_bundle(&public_keys, 2, &bundle, &msg, &vec![1; 32], RSA_BLIND_PSS).unwrap());
+    assert!(!verify_eligibility_bundle(&public_keys, 3, &bundle, &msg, &vec![1; 32], RSA_BLIND_PSS).unwrap());
+}
+
+#[test]
+fn test_keyed_token() {
+    use blind_signatures::ballots::encode_keyed_token;
+    use primitives::ballots::verify_by_key_id;
+    use primitives::blind_signatures::register_key;
+
+    let keypair = generate_rsa_keypair().unwrap();
+    let registry = register_key(&vec![], &keypair.public, 0, 1000).unwrap();
+
+    let client_addr = "0x123".to_string();
+    let election_id = "election1".to_string();
+    let iat = 12345;
+
+    let blinding_result = create_request(
+        keypair.public.clone(),
+        client_addr.clone(),
+        election_id.clone(),
+        iat,
+        RSA_BLIND_PSS_DETERMINISTIC
+    ).unwrap();
+    let blinded_sig = blind_sign(&keypair.private, &blinding_result.blind_msg).unwrap();
+    let unblinded_sig = unblind(
+        keypair.public.clone(),
+        client_addr.clone(),
+        election_id.clone(),
+        iat,
+        blinding_result.secret,
+        None,
+        blinded_sig,
+        RSA_BLIND_PSS_DETERMINISTIC
+    ).unwrap();
+
+    // The token carries the key ID of the request instead of the public key
+    let token = encode_keyed_token(
+        unblinded_sig,
+        None,
+        blinding_result.key_id,
+        vec![1; 32],
+        RSA_BLIND_PSS_DETERMINISTIC
+    ).unwrap();
+
     let msg = encoded_req(client_addr, election_id, iat);
-    assert!(blind_verify(&keypair.public, unblinded_sig, &msg).unwrap());
+    let input = (
+        alloy_primitives::Bytes::from(registry),
+        alloy_primitives::Bytes::from(token),
+        alloy_primitives::Bytes::from(msg),
+        U256::from(500),
+        U256::from(RSA_BLIND_PSS_DETERMINISTIC)
+    ).abi_encode_sequence();
+    assert_eq!(verify_by_key_id(&input).unwrap()[31], 1);
 }
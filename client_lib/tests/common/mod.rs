@@ -0,0 +1,6 @@
+use blind_signatures::ballots::ballot_context;
+
+/// Context of the ballots cast with the test token in the election `[1; 32]`.
+pub fn context() -> Vec<u8> {
+    ballot_context(vec![1; 32], vec![2; 20], b"token".to_vec()).unwrap()
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use blind_signatures::ballots::{decrypt_result, generate_elgamal_keypair};
+use blind_signatures::contests::encrypt_contest_votes;
+use primitives::alloy_primitives::U256;
+use primitives::alloy_sol_types::SolValue;
+use primitives::contests::{add_contest_votes, generate_contest_acc, split_contest_results, verify_contest_votes, MULTI_CHOICE, SINGLE_CHOICE};
+use common::context;
+
+#[test]
+fn test_contests_flow() {
+    let k = 2;
+    let n = 3;
+    let keypair = generate_elgamal_keypair(k, n).unwrap();
+
+    // A president out of 3 and a yes/no/abstain referendum where up to 2 answers may be picked
+    let manifest = vec![
+        (U256::from(SINGLE_CHOICE), U256::from(3), U256::ZERO, U256::ZERO),
+        (U256::from(MULTI_CHOICE), U256::from(3), U256::from(0), U256::from(2)),
+    ]
+    .abi_encode();
+    let mut acc = generate_contest_acc(&manifest, &vec![1; 32]).unwrap();
+
+    let vote_bytes = encrypt_contest_votes(keypair.public.clone(), manifest.clone(), vec![2, 0, 1], vec![1, 2], context()).unwrap();
+    assert!(encrypt_contest_votes(keypair.public.clone(), manifest.clone(), vec![2, 0, 1], vec![1, 1], context()).is_err());
+
+    let encoded_verify = (manifest.clone(), context(), keypair.public.clone(), vote_bytes.clone()).abi_encode_sequence();
+    assert_eq!(verify_contest_votes(&encoded_verify).unwrap()[31], 1);
+
+    let encoded_add = (manifest.clone(), acc.clone(), vote_bytes).abi_encode_sequence();
+    acc = add_contest_votes(&encoded_add).unwrap();
+
+    let result = decrypt_result(
+        keypair.private.data,
+        keypair.private.component_size,
+        k,
+        acc,
+        None,
+        None
+    ).unwrap();
+
+    assert_eq!(split_contest_results(&manifest, &result).unwrap(), vec![vec![0, 0, 1], vec![1, 1, 0]]);
+}
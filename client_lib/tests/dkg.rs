@@ -0,0 +1,88 @@
+mod common;
+
+use blind_signatures::ballots::{combine_decryption_shares, decrypt_share, encrypt_vote};
+use blind_signatures::dkg::{
+    derive_key_set, derive_public_key, generate_participant, get_commitment, get_public_info,
+    get_secret_share, get_shared_public_key, insert_commitments, insert_public_infos,
+    insert_secret_shares,
+};
+use primitives::alloy_primitives::{B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use primitives::ballots::{add_votes, generate_acc};
+use common::context;
+
+#[test]
+fn test_dkg_flow() {
+    let (k, n) = (2, 3);
+
+    let states: Vec<Vec<u8>> = (0..n)
+        .map(|i| generate_participant(k, n, i).unwrap())
+        .collect();
+
+    let commitments: Vec<u8> = states
+        .iter()
+        .flat_map(|state| get_commitment(state.clone()).unwrap())
+        .collect();
+    let states: Vec<Vec<u8>> = states
+        .into_iter()
+        .map(|state| insert_commitments(state, commitments.clone(), 32).unwrap())
+        .collect();
+
+    let infos: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| get_public_info(state.clone()).unwrap())
+        .collect();
+    let info_size = infos[0].len();
+    let flat_infos = infos.concat();
+    let states: Vec<Vec<u8>> = states
+        .into_iter()
+        .map(|state| insert_public_infos(state, flat_infos.clone(), info_size).unwrap())
+        .collect();
+
+    let key_shares: Vec<Vec<u8>> = (0..n)
+        .map(|i| {
+            let shares: Vec<u8> = states
+                .iter()
+                .flat_map(|state| get_secret_share(state.clone(), i).unwrap())
+                .collect();
+            insert_secret_shares(states[i].clone(), shares, 32).unwrap()
+        })
+        .collect();
+
+    let public_key = get_shared_public_key(key_shares[0].clone()).unwrap();
+    for key_share in &key_shares {
+        assert_eq!(
+            get_shared_public_key(key_share.clone()).unwrap(),
+            public_key
+        );
+    }
+
+    assert_eq!(
+        derive_public_key(k, n, flat_infos.clone(), info_size).unwrap(),
+        public_key
+    );
+
+    // Threshold decryption with trustees 1 and 2
+    let options_count = 3;
+    let mut acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence()).unwrap();
+    let vote = encrypt_vote(public_key, 2, options_count, context()).unwrap();
+    acc = add_votes(&(acc, vote).abi_encode_sequence()).unwrap();
+
+    let key_set = derive_key_set(k, n, flat_infos, info_size).unwrap();
+    let share_1 = decrypt_share(key_shares[1].clone(), acc.clone()).unwrap();
+    let share_2 = decrypt_share(key_shares[2].clone(), acc.clone()).unwrap();
+    let share_size = share_1.len();
+
+    let result = combine_decryption_shares(
+        key_set,
+        acc,
+        vec![1, 2],
+        [share_1, share_2].concat(),
+        share_size,
+        None,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(result, vec![0, 0, 1]);
+}
@@ -7,8 +7,8 @@ fn test_ecc_encrypt_decrypt() {
     let sk_vec = sk.serialize().to_vec();
 
     let msg = b"Test Message".to_vec();
-    let encrypted = ecc_encrypt(pk_vec, msg.clone());
-    let decrypted = ecc_decrypt(sk_vec, encrypted);
+    let encrypted = ecc_encrypt(pk_vec, msg.clone()).unwrap();
+    let decrypted = ecc_decrypt(sk_vec, encrypted).unwrap();
 
     assert_eq!(decrypted, msg);
 }
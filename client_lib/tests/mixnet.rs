@@ -0,0 +1,33 @@
+use blind_signatures::mixnet::{encrypt_message, shuffle, verify_shuffle_chain};
+use primitives::ballots::generate_elgamal_keypair;
+
+#[test]
+fn test_mixnet_flow() {
+    let (pk, _) = generate_elgamal_keypair();
+    let election_id = vec![1; 32];
+
+    let messages: Vec<Vec<u8>> = ["Alice", "Bob", "a write-in that spans two chunks"]
+        .iter()
+        .map(|answer| encrypt_message(pk.clone(), answer.as_bytes().to_vec(), 2).unwrap())
+        .collect();
+    let component_size = messages[0].len();
+    let flat_vec = messages.concat();
+
+    assert!(encrypt_message(pk.clone(), vec![1; 60], 2).is_err());
+
+    let mut chain = Vec::new();
+    for _ in 0..2 {
+        chain = shuffle(pk.clone(), election_id.clone(), flat_vec.clone(), component_size, chain).unwrap();
+    }
+    assert!(verify_shuffle_chain(pk.clone(), election_id.clone(), flat_vec.clone(), component_size, chain.clone()).unwrap());
+
+    // The chain does not verify in another election
+    assert!(!verify_shuffle_chain(pk.clone(), vec![9; 32], flat_vec.clone(), component_size, chain.clone()).unwrap());
+
+    // The chain does not match other submitted answers
+    let mut other = messages.clone();
+    other[1] = encrypt_message(pk.clone(), b"Bob".to_vec(), 2).unwrap();
+    assert!(!verify_shuffle_chain(pk.clone(), election_id.clone(), other.concat(), component_size, chain).unwrap());
+
+    assert!(shuffle(pk, election_id, flat_vec, component_size + 1, Vec::new()).is_err());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use blind_signatures::ballots::{decrypt_result, generate_elgamal_keypair};
+use blind_signatures::ranked_ballots::{condorcet_winner, encrypt_ranked_vote, schulze_winners};
+use primitives::alloy_primitives::{B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use primitives::ranked_ballots::{add_ranked_votes, generate_ranked_acc, verify_ranked_vote};
+use common::context;
+
+#[test]
+fn test_ranked_ballots_flow() {
+    let k = 2;
+    let n = 3;
+    let keypair = generate_elgamal_keypair(k, n).unwrap();
+
+    let options_count = 3;
+    let mut acc = generate_ranked_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence()).unwrap();
+
+    for ranking in [vec![2, 0, 1], vec![2, 1, 0]] {
+        let vote_bytes = encrypt_ranked_vote(keypair.public.clone(), ranking, options_count, context()).unwrap();
+
+        let encoded_verify = (U256::from(options_count), context(), keypair.public.clone(), vote_bytes.clone()).abi_encode_sequence();
+        assert_eq!(verify_ranked_vote(&encoded_verify).unwrap()[31], 1);
+
+        let encoded_add = (acc.clone(), vote_bytes).abi_encode_sequence();
+        acc = add_ranked_votes(&encoded_add).unwrap();
+    }
+
+    let matrix = decrypt_result(
+        keypair.private.data,
+        keypair.private.component_size,
+        k,
+        acc,
+        None,
+        None
+    ).unwrap();
+
+    assert_eq!(condorcet_winner(matrix.clone(), options_count).unwrap(), Some(2));
+    assert_eq!(schulze_winners(matrix, options_count).unwrap(), vec![2]);
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// @title Bn254BallotVerifier
+/// @notice Verifies single-choice ballots encrypted with exponential ElGamal over BN254 by
+/// `primitives::bn254`, using only the ecAdd and ecMul precompiles.
+/// @dev Mirrors `Bn254::verify`: every flag carries a disjunctive Chaum-Pedersen proof that
+/// it encrypts 0 or 1, and the ballot a Chaum-Pedersen proof that its flags add up to 1.
+/// Malformed points make the precompiles fail, which reverts the call.
+contract Bn254BallotVerifier {
+    /// Modulus of the base field.
+    uint256 internal constant P = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+    /// Order of the G1 group.
+    uint256 internal constant R = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+
+    bytes32 internal constant FLAG_LABEL = keccak256("ballot_flag");
+    bytes32 internal constant SUM_LABEL = keccak256("multi_choice_sum");
+
+    /// Envelope header: magic bytes, version, scheme, kind and 32-byte election ID.
+    uint256 internal constant HEADER_LEN = 39;
+    bytes4 internal constant MAGIC = "VOTE";
+    uint8 internal constant VERSION = 1;
+    uint8 internal constant BN254_ELGAMAL = 4;
+    uint8 internal constant MULTI_CHOICE_BALLOT = 1;
+
+    struct Bn254Flag {
+        uint256[2] r;
+        uint256[2] c;
+        uint256[4] proof;
+    }
+
+    struct Bn254Ballot {
+        Bn254Flag[] flags;
+        uint256[2] sumProof;
+    }
+
+    /// @notice Verifies an encrypted vote, as the `verify_vote` precompile does.
+    /// @param optionsCount Total number of options available.
+    /// @param context Ballot context, `abi.encode(electionId, contractAddress, token)`.
+    /// @param publicKey Public key of the election authority.
+    /// @param ballot Ballot in its envelope, as returned by `encrypt_backend_vote`.
+    /// @return Whether the ballot is valid.
+    function verifyVote(
+        uint256 optionsCount,
+        bytes calldata context,
+        uint256[2] calldata publicKey,
+        bytes calldata ballot
+    ) external view returns (bool) {
+        if (ballot.length < HEADER_LEN || context.length < 32) {
+            return false;
+        }
+        if (
+            bytes4(bytes32(wordAt(ballot, 0))) != MAGIC || uint8(ballot[4]) != VERSION
+                || uint8(ballot[5]) != BN254_ELGAMAL || uint8(ballot[6]) != MULTI_CHOICE_BALLOT
+        ) {
+            return false;
+        }
+        if (wordAt(ballot, 7) != wordAt(context, 0)) {
+            return false;
+        }
+
+        Bn254Ballot memory decoded = decodeBallot(ballot);
+
+        return verifyBallot(optionsCount, keccak256(context), publicKey, decoded);
+    }
+
+    /// Decodes the ABI encoded `Bn254Ballot` after the envelope header word by word, with
+    /// the ABI offsets made absolute. Out of range offsets revert the call.
+    function decodeBallot(bytes calldata ballot) internal pure returns (Bn254Ballot memory decoded) {
+        uint256 head = HEADER_LEN + wordAt(ballot, HEADER_LEN);
+        uint256 flagsAt = head + wordAt(ballot, head);
+        uint256 count = wordAt(ballot, flagsAt);
+
+        decoded.sumProof = [wordAt(ballot, head + 32), wordAt(ballot, head + 64)];
+        decoded.flags = new Bn254Flag[](count);
+
+        for (uint256 i = 0; i < count; i++) {
+            uint256 at = flagsAt + 32 + i * 256;
+
+            uint256[2] memory r = [wordAt(ballot, at), wordAt(ballot, at + 32)];
+            uint256[2] memory c = [wordAt(ballot, at + 64), wordAt(ballot, at + 96)];
+            uint256[4] memory proof = [
+                wordAt(ballot, at + 128),
+                wordAt(ballot, at + 160),
+                wordAt(ballot, at + 192),
+                wordAt(ballot, at + 224)
+            ];
+
+            decoded.flags[i] = Bn254Flag(r, c, proof);
+        }
+    }
+
+    /// Reads the 32 bytes at `offset`, reverting if they run past the end of `data`.
+    function wordAt(bytes calldata data, uint256 offset) internal pure returns (uint256 word) {
+        require(offset <= data.length && data.length - offset >= 32, "Out of bounds");
+
+        assembly {
+            word := calldataload(add(data.offset, offset))
+        }
+    }
+
+    function verifyBallot(
+        uint256 optionsCount,
+        bytes32 contextHash,
+        uint256[2] memory h,
+        Bn254Ballot memory ballot
+    ) internal view returns (bool) {
+        if (ballot.flags.length != optionsCount) {
+            return false;
+        }
+
+        uint256[2] memory totalR;
+        uint256[2] memory totalC = negate(generator());
+
+        for (uint256 i = 0; i < ballot.flags.length; i++) {
+            if (!verifyFlag(contextHash, h, ballot.flags[i])) {
+                return false;
+            }
+
+            totalR = ecAdd(totalR, ballot.flags[i].r);
+            totalC = ecAdd(totalC, ballot.flags[i].c);
+        }
+
+        return verifySum(contextHash, h, totalR, totalC, ballot.sumProof);
+    }
+
+    /// Checks the proof `[c0, c1, s0, s1]` that a flag encrypts 0 or 1.
+    function verifyFlag(bytes32 contextHash, uint256[2] memory h, Bn254Flag memory flag)
+        internal
+        view
+        returns (bool)
+    {
+        uint256[4] memory proof = flag.proof;
+        if (proof[0] >= R || proof[1] >= R || proof[2] >= R || proof[3] >= R) {
+            return false;
+        }
+
+        uint256[2] memory a0 = ecSub(ecMul(generator(), proof[2]), ecMul(flag.r, proof[0]));
+        uint256[2] memory b0 = ecSub(ecMul(h, proof[2]), ecMul(flag.c, proof[0]));
+        uint256[2] memory a1 = ecSub(ecMul(generator(), proof[3]), ecMul(flag.r, proof[1]));
+        uint256[2] memory b1 =
+            ecSub(ecMul(h, proof[3]), ecMul(ecSub(flag.c, generator()), proof[1]));
+
+        bytes32 digest = keccak256(abi.encode(FLAG_LABEL, contextHash, h, flag.r, flag.c, a0, b0, a1, b1));
+
+        return addmod(proof[0], proof[1], R) == uint256(digest) % R;
+    }
+
+    /// Checks the proof `[c, s]` that `(totalR, totalC)` encrypts 0.
+    function verifySum(
+        bytes32 contextHash,
+        uint256[2] memory h,
+        uint256[2] memory totalR,
+        uint256[2] memory totalC,
+        uint256[2] memory proof
+    ) internal view returns (bool) {
+        if (proof[0] >= R || proof[1] >= R) {
+            return false;
+        }
+
+        uint256[2] memory a = ecSub(ecMul(generator(), proof[1]), ecMul(totalR, proof[0]));
+        uint256[2] memory b = ecSub(ecMul(h, proof[1]), ecMul(totalC, proof[0]));
+
+        bytes32 digest = keccak256(abi.encode(SUM_LABEL, contextHash, h, totalR, totalC, a, b));
+
+        return proof[0] == uint256(digest) % R;
+    }
+
+    function generator() internal pure returns (uint256[2] memory) {
+        return [uint256(1), 2];
+    }
+
+    function negate(uint256[2] memory point) internal pure returns (uint256[2] memory) {
+        if (point[0] == 0 && point[1] == 0) {
+            return point;
+        }
+
+        return [point[0], P - point[1]];
+    }
+
+    function ecAdd(uint256[2] memory a, uint256[2] memory b) internal view returns (uint256[2] memory sum) {
+        uint256[4] memory input = [a[0], a[1], b[0], b[1]];
+        bool success;
+
+        assembly {
+            success := staticcall(gas(), 0x06, input, 0x80, sum, 0x40)
+        }
+
+        require(success, "ecAdd failed");
+    }
+
+    function ecSub(uint256[2] memory a, uint256[2] memory b) internal view returns (uint256[2] memory) {
+        return ecAdd(a, negate(b));
+    }
+
+    function ecMul(uint256[2] memory point, uint256 scalar) internal view returns (uint256[2] memory product) {
+        uint256[3] memory input = [point[0], point[1], scalar];
+        bool success;
+
+        assembly {
+            success := staticcall(gas(), 0x07, input, 0x60, product, 0x40)
+        }
+
+        require(success, "ecMul failed");
+    }
+}
@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+
+# Regenerates Bn254BallotVerifier.bin, the creation code the revm tests of bn254 deploy,
+# from Bn254BallotVerifier.sol with a pinned solc release.
+#
+# Usage: ./build.sh
+#
+# The compiler is read from $SOLC, or `solc` on the PATH, and must be the pinned release,
+# e.g. after `solc-select install 0.8.24 && solc-select use 0.8.24`. The metadata hash is
+# left out so that the same source always yields the same bytecode.
+
+set -euo pipefail
+
+SOLC_VERSION="0.8.24"
+SOLC="${SOLC:-solc}"
+CONTRACT="Bn254BallotVerifier"
+
+exit_on_error() {
+    echo -e "❌ Error: $1" >&2
+    exit 1
+}
+
+cd "$(dirname "$0")"
+
+command -v "$SOLC" >/dev/null || exit_on_error "'$SOLC' was not found, install solc $SOLC_VERSION."
+
+version=$("$SOLC" --version | sed -n 's/^Version: \([0-9.]*\).*/\1/p')
+[ "$version" == "$SOLC_VERSION" ] || exit_on_error "solc $SOLC_VERSION is required, found '$version'."
+
+"$SOLC" --bin \
+    --via-ir --optimize --optimize-runs 200 \
+    --evm-version cancun \
+    --metadata-hash none \
+    "$CONTRACT.sol" |
+    sed -n "/^======= $CONTRACT.sol:$CONTRACT =======$/{n;n;p}" |
+    tr -d '\n' >"$CONTRACT.bin"
+
+[ -s "$CONTRACT.bin" ] || exit_on_error "solc produced no bytecode for $CONTRACT."
+
+echo "✅ $CONTRACT.bin regenerated with solc $SOLC_VERSION."
@@ -0,0 +1,486 @@
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, PublicKey, SecretKey};
+use postcard::{from_bytes, take_from_bytes, to_allocvec};
+
+use crate::Error;
+use crate::ballots::{
+    MultiChoiceBallot, accumulate, context_election, encrypt_multi_choice,
+    generate_elgamal_keypair, in_election, solve_counts, verify_multi_choice,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bfv::Bfv;
+use crate::bn254::Bn254;
+use crate::envelope::{
+    ACCUMULATOR, BFV, BN254_ELGAMAL, MULTI_CHOICE_BALLOT, RISTRETTO_ELGAMAL, SECP256K1_ELGAMAL,
+    open_with, scheme_of, seal_with,
+};
+use crate::secp256k1::Secp256k1;
+
+/// Additively homomorphic encryption scheme that single-choice ballots are cast and tallied
+/// with.
+///
+/// Every election picks a backend when its accumulator is generated, and the scheme of the
+/// backend is written in the envelope of its ballots and accumulator, so `add_votes` and
+/// `decrypt_result` dispatch on the accumulator. `verify_vote` is given the scheme of the
+/// election instead, since it has no accumulator to read it from. Multi-choice, score, ranked
+/// and contest ballots, threshold decryption and tally proofs are only supported by `ElGamal`.
+///
+/// `Bfv` has no validity proofs, so nothing stops a BFV ballot from holding more than one
+/// vote, or a negative one. BFV elections are for trusted clients only: `add_votes` rejects
+/// their accumulators, and their ballots are added off-chain with `add_trusted_backend_vote`.
+pub trait Backend {
+    /// Scheme written in the envelope of the ballots and accumulators of the backend.
+    const SCHEME: u8;
+
+    /// Encrypted ballot, together with the proofs of its validity if the backend has any.
+    type Ballot;
+
+    /// Encrypted sum of ballots.
+    type Tally;
+
+    /// Generates a key pair, encoded as `(public_key, secret_key)`.
+    fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), Error>;
+
+    /// Encrypts a vote for `choice` out of `options_count` options, bound to `context`.
+    fn encrypt(
+        public_key: &[u8],
+        choice: usize,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<Self::Ballot, Error>;
+
+    /// Tells whether a ballot holds a single vote out of `options_count` options.
+    fn verify(
+        public_key: &[u8],
+        ballot: &Self::Ballot,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<bool, Error>;
+
+    /// Returns the encrypted sum of no ballots, or an error if the backend cannot hold a
+    /// count for `options_count` options.
+    fn zero(options_count: usize) -> Result<Self::Tally, Error>;
+
+    /// Adds a ballot to a tally with the same number of options.
+    fn add(tally: &mut Self::Tally, ballot: &Self::Ballot) -> Result<(), Error>;
+
+    /// Returns the number of options a tally counts votes for.
+    fn options_count(tally: &Self::Tally) -> usize;
+
+    /// Decrypts the counts of a tally of `ballots` ballots, each adding at most `max_score`
+    /// to an entry.
+    ///
+    /// Backends that solve discrete logs store `baby_steps` baby steps, or about the square
+    /// root of the bound if `None`. Others ignore it.
+    fn decrypt(
+        secret_key: &[u8],
+        tally: &Self::Tally,
+        ballots: u64,
+        max_score: u64,
+        baby_steps: Option<u64>,
+    ) -> Result<Vec<u64>, Error>;
+
+    /// Encodes a ballot as the payload of its envelope.
+    fn encode_ballot(ballot: &Self::Ballot) -> Result<Vec<u8>, Error>;
+
+    /// Decodes the payload of a ballot envelope, rejecting malformed ballots without
+    /// checking their proofs, which is left to `verify`.
+    fn decode_ballot(ballot: &[u8]) -> Result<Self::Ballot, Error>;
+
+    /// Encodes a tally as it follows the ballot count in the payload of an accumulator.
+    fn encode_tally(tally: &Self::Tally) -> Result<Vec<u8>, Error>;
+
+    /// Decodes a tally written by `encode_tally`, rejecting malformed ones.
+    fn decode_tally(tally: &[u8]) -> Result<Self::Tally, Error>;
+}
+
+/// Exponential ElGamal over Ristretto, with proofs that every ballot is well formed.
+pub struct ElGamal;
+
+impl Backend for ElGamal {
+    const SCHEME: u8 = RISTRETTO_ELGAMAL;
+
+    type Ballot = MultiChoiceBallot;
+    type Tally = Vec<Ciphertext<Ristretto>>;
+
+    fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), Error> {
+        Ok(generate_elgamal_keypair())
+    }
+
+    fn encrypt(
+        public_key: &[u8],
+        choice: usize,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<Self::Ballot, Error> {
+        encrypt_multi_choice(public_key, &[choice], options_count, 1, 1, context)
+            .map(|(ballot, _)| ballot)
+    }
+
+    fn verify(
+        public_key: &[u8],
+        ballot: &Self::Ballot,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<bool, Error> {
+        let public_key = PublicKey::<Ristretto>::from_bytes(public_key)?;
+
+        Ok(verify_multi_choice(
+            ballot,
+            &public_key,
+            options_count,
+            1,
+            1,
+            context,
+        ))
+    }
+
+    fn zero(options_count: usize) -> Result<Self::Tally, Error> {
+        Ok(vec![Ciphertext::zero(); options_count])
+    }
+
+    fn add(tally: &mut Self::Tally, ballot: &Self::Ballot) -> Result<(), Error> {
+        let (choices, _, _) = ballot;
+
+        accumulate(tally, choices.iter().map(|(choice, _)| *choice))
+    }
+
+    fn options_count(tally: &Self::Tally) -> usize {
+        tally.len()
+    }
+
+    fn decrypt(
+        secret_key: &[u8],
+        tally: &Self::Tally,
+        ballots: u64,
+        max_score: u64,
+        baby_steps: Option<u64>,
+    ) -> Result<Vec<u64>, Error> {
+        let sk = SecretKey::<Ristretto>::from_bytes(secret_key)
+            .ok_or_else(|| Error::Key("Invalid secret key".into()))?;
+
+        let elements = tally
+            .iter()
+            .map(|choice| sk.decrypt_to_element(*choice))
+            .collect::<Vec<_>>();
+
+        solve_counts(&elements, ballots, max_score, baby_steps)
+    }
+
+    fn encode_ballot(ballot: &Self::Ballot) -> Result<Vec<u8>, Error> {
+        Ok(to_allocvec(ballot)?)
+    }
+
+    fn decode_ballot(ballot: &[u8]) -> Result<Self::Ballot, Error> {
+        Ok(from_bytes(ballot)?)
+    }
+
+    fn encode_tally(tally: &Self::Tally) -> Result<Vec<u8>, Error> {
+        Ok(to_allocvec(tally)?)
+    }
+
+    fn decode_tally(tally: &[u8]) -> Result<Self::Tally, Error> {
+        Ok(from_bytes(tally)?)
+    }
+}
+
+/// Calls a function generic over `Backend` with the backend of `scheme`.
+macro_rules! with_backend {
+    ($scheme:expr, $function:ident($($arg:expr),* $(,)?)) => {
+        match $scheme {
+            RISTRETTO_ELGAMAL => $function::<ElGamal>($($arg),*),
+            #[cfg(not(target_arch = "wasm32"))]
+            BFV => $function::<Bfv>($($arg),*),
+            BN254_ELGAMAL => $function::<Bn254>($($arg),*),
+            SECP256K1_ELGAMAL => $function::<Secp256k1>($($arg),*),
+            scheme => Err(Error::InvalidInput(format!(
+                "Unsupported ballot scheme {}",
+                scheme
+            ))),
+        }
+    };
+}
+
+/// Encodes an accumulator once `added` more ballots have been summed into its tally.
+///
+/// The payload is the number of ballots followed by the tally, which for `ElGamal` is the
+/// same encoding as `ballots::encode_acc`.
+fn encode_acc<B: Backend>(
+    election_id: &B256,
+    ballots: u64,
+    added: u64,
+    tally: &B::Tally,
+) -> Result<Vec<u8>, Error> {
+    let ballots = ballots
+        .checked_add(added)
+        .ok_or_else(|| Error::Arithmetic("Too many ballots".into()))?;
+
+    let mut payload = to_allocvec(&ballots)?;
+    payload.extend(B::encode_tally(tally)?);
+
+    Ok(seal_with(B::SCHEME, ACCUMULATOR, election_id, &payload))
+}
+
+/// Decodes an accumulator of the backend together with the ID of its election.
+fn decode_acc<B: Backend>(acc: &[u8]) -> Result<(B256, u64, B::Tally), Error> {
+    let (election_id, acc) = open_with(B::SCHEME, ACCUMULATOR, acc)?;
+    let (ballots, tally) = take_from_bytes::<u64>(acc)?;
+
+    Ok((election_id, ballots, B::decode_tally(tally)?))
+}
+
+fn empty_acc<B: Backend>(election_id: &B256, options_count: usize) -> Result<Vec<u8>, Error> {
+    encode_acc::<B>(election_id, 0, 0, &B::zero(options_count)?)
+}
+
+fn generate_keypair<B: Backend>() -> Result<(Vec<u8>, Vec<u8>), Error> {
+    B::generate_keypair()
+}
+
+fn encrypt_ballot<B: Backend>(
+    public_key: &[u8],
+    choice: usize,
+    options_count: usize,
+    context: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let election_id = context_election(context)?;
+    let ballot = B::encrypt(public_key, choice, options_count, context)?;
+
+    Ok(seal_with(
+        B::SCHEME,
+        MULTI_CHOICE_BALLOT,
+        &election_id,
+        &B::encode_ballot(&ballot)?,
+    ))
+}
+
+fn verify_ballot<B: Backend>(
+    options_count: usize,
+    context: &[u8],
+    public_key: &[u8],
+    ballot: &[u8],
+) -> Result<bool, Error> {
+    let (election_id, ballot) = open_with(B::SCHEME, MULTI_CHOICE_BALLOT, ballot)?;
+    let ballot = B::decode_ballot(ballot)?;
+
+    Ok(in_election(&election_id, context)
+        && B::verify(public_key, &ballot, options_count, context)?)
+}
+
+fn add_ballot<B: Backend>(acc: &[u8], ballot: &[u8]) -> Result<Vec<u8>, Error> {
+    let (election_id, ballots, mut tally) = decode_acc::<B>(acc)?;
+    let (ballot_election, ballot) = open_with(B::SCHEME, MULTI_CHOICE_BALLOT, ballot)?;
+
+    if ballot_election != election_id {
+        return Err(Error::InvalidInput(
+            "Ballot belongs to another election".into(),
+        ));
+    }
+
+    B::add(&mut tally, &B::decode_ballot(ballot)?)?;
+
+    encode_acc::<B>(&election_id, ballots, 1, &tally)
+}
+
+/// Adds the ballots that open, decode and, with `checks`, verify against their context and
+/// the public key, returning the accumulator and the indexes of the ballots left out.
+///
+/// Every ballot is added to a copy of the tally, so one that fails half way through `add`
+/// leaves no trace.
+fn add_ballots<B: Backend>(
+    acc: &[u8],
+    ballots: &[Bytes],
+    checks: Option<(&[Bytes], &[u8])>,
+) -> Result<(Vec<u8>, Vec<U256>), Error>
+where
+    B::Tally: Clone,
+{
+    let (election_id, count, mut tally) = decode_acc::<B>(acc)?;
+    let options_count = B::options_count(&tally);
+    let mut rejected = Vec::new();
+
+    for (index, ballot) in ballots.iter().enumerate() {
+        let next = open_with(B::SCHEME, MULTI_CHOICE_BALLOT, ballot)
+            .ok()
+            .filter(|(ballot_election, _)| *ballot_election == election_id)
+            .and_then(|(_, ballot)| B::decode_ballot(ballot).ok())
+            .filter(|ballot| {
+                checks.is_none_or(|(contexts, public_key)| {
+                    in_election(&election_id, &contexts[index])
+                        && B::verify(public_key, ballot, options_count, &contexts[index])
+                            .unwrap_or(false)
+                })
+            })
+            .and_then(|ballot| {
+                let mut next = tally.clone();
+                B::add(&mut next, &ballot).ok().map(|_| next)
+            });
+
+        match next {
+            Some(next) => tally = next,
+            None => rejected.push(U256::from(index)),
+        }
+    }
+
+    let added = (ballots.len() - rejected.len()) as u64;
+
+    Ok((
+        encode_acc::<B>(&election_id, count, added, &tally)?,
+        rejected,
+    ))
+}
+
+fn decrypt_tally<B: Backend>(
+    secret_key: &[u8],
+    acc: &[u8],
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, Error> {
+    let (_, ballots, tally) = decode_acc::<B>(acc)?;
+
+    B::decrypt(secret_key, &tally, ballots, max_score, baby_steps)
+}
+
+/// Generates a key pair for the backend of `scheme`.
+///
+/// # Arguments
+///
+/// * `scheme` - `RISTRETTO_ELGAMAL`, `BFV`, `BN254_ELGAMAL` or `SECP256K1_ELGAMAL`.
+///
+/// # Returns
+///
+/// Returns the public key and secret key as `Result<(Vec<u8>, Vec<u8>), Error>`.
+pub fn generate_backend_keypair(scheme: u8) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    with_backend!(scheme, generate_keypair())
+}
+
+/// Generates an empty accumulator for an election tallied with the backend of `scheme`.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, election_id, scheme)`.
+///
+/// # Returns
+///
+/// Returns the initialized accumulator as a `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn generate_backend_acc(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, election_id, scheme) = <(U256, B256, U256)>::abi_decode_sequence(data)?;
+    let candidate_count: usize = candidate_count.try_into()?;
+    let scheme: u8 = scheme.try_into()?;
+
+    with_backend!(scheme, empty_acc(&election_id, candidate_count))
+}
+
+/// Encrypts a vote for a specific choice with the backend of `scheme`.
+///
+/// # Arguments
+///
+/// * `scheme` - Scheme of the election, as given to `generate_backend_acc`.
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `choice` - Index of the selected option.
+/// * `options_count` - Total number of options available.
+/// * `context` - Ballot context from `ballot_context`.
+///
+/// # Returns
+///
+/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_backend_vote(
+    scheme: u8,
+    pub_key_bytes: &Vec<u8>,
+    choice: usize,
+    options_count: usize,
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    with_backend!(
+        scheme,
+        encrypt_ballot(pub_key_bytes, choice, options_count, context)
+    )
+}
+
+/// Verifies a single-choice ballot with the backend of `scheme`, the one of its election.
+///
+/// The scheme is not read from the ballot, which the voter controls: a ballot written with
+/// another scheme fails to open.
+pub(crate) fn verify_vote(
+    scheme: u8,
+    options_count: usize,
+    context: &[u8],
+    public_key: &[u8],
+    ballot: &[u8],
+) -> Result<bool, Error> {
+    with_backend!(
+        scheme,
+        verify_ballot(options_count, context, public_key, ballot)
+    )
+}
+
+/// Adds a single-choice ballot to an accumulator with the backend written in its envelope,
+/// unless the backend cannot prove that the ballot is valid.
+pub(crate) fn add_vote(acc: &[u8], ballot: &[u8]) -> Result<Vec<u8>, Error> {
+    let scheme = scheme_of(acc)?;
+    if scheme == BFV {
+        return Err(Error::InvalidInput(
+            "BFV ballots cannot be verified and are only added with add_trusted_backend_vote"
+                .into(),
+        ));
+    }
+
+    with_backend!(scheme, add_ballot(acc, ballot))
+}
+
+/// Adds single-choice ballots to an accumulator with the backend written in its envelope,
+/// leaving out those that cannot be decoded, belong to another election or, with `checks`,
+/// fail verification against their context and the public key. BFV accumulators are
+/// rejected as in `add_vote`.
+pub(crate) fn add_vote_batch(
+    acc: &[u8],
+    ballots: &[Bytes],
+    checks: Option<(&[Bytes], &[u8])>,
+) -> Result<(Vec<u8>, Vec<U256>), Error> {
+    let scheme = scheme_of(acc)?;
+    if scheme == BFV {
+        return Err(Error::InvalidInput(
+            "BFV ballots cannot be verified and are only added with add_trusted_backend_vote"
+                .into(),
+        ));
+    }
+
+    with_backend!(scheme, add_ballots(acc, ballots, checks))
+}
+
+/// Adds a ballot to an accumulator of any backend without any check of its validity.
+///
+/// This is the only way to tally a BFV election, whose ballots have no proof that they hold
+/// a single vote: a voter can add any count to any option. It must only run off-chain, on
+/// ballots written by trusted clients, and never on the path of `add_votes`.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_trusted_backend_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    with_backend!(scheme_of(&acc)?, add_ballot(&acc, &ballot))
+}
+
+/// Decrypts an accumulator with the backend written in its envelope.
+pub(crate) fn decrypt_result(
+    secret_key: &[u8],
+    acc: &[u8],
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, Error> {
+    with_backend!(
+        scheme_of(acc)?,
+        decrypt_tally(secret_key, acc, max_score, baby_steps)
+    )
+}
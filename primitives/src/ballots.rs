@@ -1,12 +1,76 @@
-use alloy_primitives::{Bytes, U256};
+use alloy_primitives::{Address, B256, Bytes, U256, keccak256};
 use alloy_sol_types::SolValue;
-use blind_rsa_signatures::{Deterministic, MessageRandomizer, PSS, Sha384, Signature};
+use curve25519_dalek::ristretto::RistrettoPoint;
 use elastic_elgamal::app::{ChoiceParams, EncryptedChoice, SingleChoice};
-use elastic_elgamal::group::Ristretto;
-use elastic_elgamal::{Ciphertext, DiscreteLogTable, Keypair, PublicKey, SecretKey};
+use elastic_elgamal::group::{Group, Ristretto};
+use elastic_elgamal::sharing::{ActiveParticipant, PublicKeySet};
+use elastic_elgamal::{
+    CandidateDecryption, Ciphertext, CiphertextWithValue, Keypair, LogEqualityProof, PreparedRange,
+    PublicKey, RangeDecomposition, RangeProof, SecretKey, SumOfSquaresProof, VerifiableDecryption,
+};
+use merlin::Transcript;
 use postcard::{from_bytes, to_allocvec};
-use rand_legacy::thread_rng;
-use std::error::Error;
+use rand_chacha::ChaCha20Rng;
+use rand_legacy::{CryptoRng, RngCore, SeedableRng, thread_rng};
+use rayon::prelude::*;
+
+use crate::Error;
+use crate::backend;
+use crate::blind_signatures::{
+    BlindPublic, PartiallyBlindPublic, eligibility_nullifier, is_eligible, nullifier, token,
+    token_metadata, token_randomizer, verify_by_key_id as verify_signature_by_key_id, verify_token,
+    verify_with_metadata,
+};
+use crate::discrete_log::DiscreteLog;
+use crate::envelope::{
+    ACCUMULATOR, KEYED_TOKEN, LEGACY_SINGLE_CHOICE_BALLOT, MULTI_CHOICE_BALLOT, RISTRETTO_ELGAMAL,
+    SCORE_BALLOT, TOKEN, open, open_with, scheme_of, seal, seal_with,
+};
+
+/// Decryption of a single candidate together with its proof of correctness.
+type ProvenDecryption = (CandidateDecryption<Ristretto>, LogEqualityProof<Ristretto>);
+
+/// Encrypted value together with its range proof.
+///
+/// The proof is JSON encoded, since postcard cannot encode its flattened fields.
+pub(crate) type ProvenRange = (Ciphertext<Ristretto>, Vec<u8>);
+
+/// Multi-choice ballot: one flag per option, `max - min` padding flags, each with the proof
+/// that it is in `0..=1`, and the proof that the squares of all the flags add up to `max`.
+pub(crate) type MultiChoiceBallot = (
+    Vec<ProvenRange>,
+    Vec<ProvenRange>,
+    SumOfSquaresProof<Ristretto>,
+);
+
+/// Single-choice ballot of the first format, whose proofs commit to no ballot context.
+pub(crate) type LegacyBallot = EncryptedChoice<Ristretto, SingleChoice>;
+
+/// Budget proof of a score ballot: `max` range-proven unary flags per candidate, the proofs
+/// that every score is the sum of its flags, the encrypted total with its range proof and
+/// the proof that the total is the sum of all the flags.
+type BudgetProof = (
+    Vec<ProvenRange>,
+    Vec<SumOfSquaresProof<Ristretto>>,
+    ProvenRange,
+    SumOfSquaresProof<Ristretto>,
+);
+
+/// Score ballot: one range-proven score per candidate and an optional budget proof.
+pub(crate) type ScoreBallot = (Vec<ProvenRange>, Option<BudgetProof>);
+
+/// Accumulator: the number of ballots added so far and the sum of their ciphertexts for
+/// every entry.
+///
+/// The ballot count bounds the decrypted entries, so tallies need no fixed upper bound.
+pub(crate) type Accumulator = (u64, Vec<Ciphertext<Ristretto>>);
+
+/// Voter token: the blind RSA signature and its message randomizer, all zeros for tokens
+/// issued in the deterministic mode.
+pub(crate) type Token = (Vec<u8>, [u8; 32]);
+
+/// Voter token naming its signing key: the key ID and the token.
+pub(crate) type KeyedToken = ([u8; 32], Token);
 
 /// Generates a new ElGamal key pair using the Ristretto group.
 ///
@@ -27,75 +91,308 @@ pub fn generate_elgamal_keypair() -> (Vec<u8>, Vec<u8>) {
 ///
 /// # Arguments
 ///
-/// * `data` - Encoded candidate count as `Vec<u8>`.
+/// * `data` - ABI encoded sequence of `(candidate_count, election_id)`.
 ///
 /// # Returns
 ///
 /// Returns the initialized accumulator as a `Vec<u8>`.
-pub fn generate_acc(data: &Vec<u8>) -> Vec<u8> {
-    let candidate_count = <U256>::abi_decode(&data).unwrap();
+#[allow(clippy::ptr_arg)]
+pub fn generate_acc(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, election_id) = <(U256, B256)>::abi_decode_sequence(data)?;
+
+    empty_acc(&election_id, candidate_count.try_into()?)
+}
+
+/// Encodes an accumulator with `len` entries and no ballots.
+pub(crate) fn empty_acc(election_id: &B256, len: usize) -> Result<Vec<u8>, Error> {
+    encode_acc(election_id, 0, 0, &vec![Ciphertext::zero(); len])
+}
+
+/// Encodes an accumulator once `added` more ballots have been summed into its entries.
+pub(crate) fn encode_acc(
+    election_id: &B256,
+    ballots: u64,
+    added: u64,
+    entries: &[Ciphertext<Ristretto>],
+) -> Result<Vec<u8>, Error> {
+    let ballots = ballots
+        .checked_add(added)
+        .ok_or_else(|| Error::Arithmetic("Too many ballots".into()))?;
+
+    Ok(seal(
+        ACCUMULATOR,
+        election_id,
+        &to_allocvec(&(ballots, entries))?,
+    ))
+}
+
+/// Decodes an accumulator together with the ID of its election.
+pub(crate) fn decode_acc(acc: &[u8]) -> Result<(B256, Accumulator), Error> {
+    let (election_id, acc) = open(ACCUMULATOR, acc)?;
+
+    Ok((election_id, from_bytes::<Accumulator>(acc)?))
+}
+
+/// Opens a ballot of `kind` that is added to an accumulator of the election `election_id`.
+pub(crate) fn open_ballot<'a>(
+    kind: u8,
+    ballot: &'a [u8],
+    election_id: &B256,
+) -> Result<&'a [u8], Error> {
+    let (ballot_election, ballot) = open(kind, ballot)?;
+
+    if ballot_election != *election_id {
+        return Err(Error::InvalidInput(
+            "Ballot belongs to another election".into(),
+        ));
+    }
+
+    Ok(ballot)
+}
+
+/// Encodes the context a ballot is bound to.
+///
+/// The encoding matches `abi.encode(electionId, contractAddress, token)`, so a contract
+/// can rebuild it on its own when verifying a ballot.
+///
+/// # Arguments
+///
+/// * `election_id` - 32-byte identifier of the election.
+/// * `contract_address` - 20-byte address of the election contract.
+/// * `token` - Unblinded voter token the ballot is cast with.
+///
+/// # Returns
+///
+/// Returns the ABI encoded context as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn ballot_context(
+    election_id: &Vec<u8>,
+    contract_address: &Vec<u8>,
+    token: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let election_id = B256::try_from(election_id.as_slice())?;
+    let contract_address = Address::try_from(contract_address.as_slice())?;
+
+    Ok((election_id, contract_address, Bytes::from(token.clone())).abi_encode_sequence())
+}
+
+/// Reads the election ID a ballot context starts with.
+pub(crate) fn context_election(context: &[u8]) -> Result<B256, Error> {
+    context
+        .get(..32)
+        .map(B256::from_slice)
+        .ok_or_else(|| Error::InvalidInput("Ballot context is too short".into()))
+}
+
+/// Tells whether a ballot context belongs to the election `election_id`.
+pub(crate) fn in_election(election_id: &B256, context: &[u8]) -> bool {
+    context.get(..32) == Some(election_id.as_slice())
+}
+
+/// Computes the tracker of a cast ballot, chained to the tracker of the previous ballot.
+///
+/// The tracker is `keccak256(abi.encode(electionId, previousTracker, ballot))`, so the
+/// bulletin board, the contract and the voter all derive the same chain, and a ballot
+/// cannot be dropped or reordered without changing every later tracker.
+///
+/// # Arguments
+///
+/// * `election_id` - 32-byte identifier of the election.
+/// * `previous` - 32-byte tracker of the previous ballot, all zeros for the first one.
+/// * `ballot` - The encrypted ballot as cast.
+///
+/// # Returns
+///
+/// Returns the 32-byte tracker as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn ballot_tracker(
+    election_id: &Vec<u8>,
+    previous: &Vec<u8>,
+    ballot: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let election_id = B256::try_from(election_id.as_slice())?;
+    let previous = B256::try_from(previous.as_slice())?;
+    let encoded = (election_id, previous, Bytes::from(ballot.clone())).abi_encode_sequence();
+
+    Ok(keccak256(encoded).to_vec())
+}
+
+/// Crockford's base32 alphabet, which leaves out `I`, `L`, `O` and `U`.
+const TRACKER_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Number of 4-character groups of a tracking code.
+const TRACKER_GROUPS: usize = 4;
+
+/// Renders a ballot tracker as a short code a voter can read out and look up.
+///
+/// The first 80 bits of the tracker are written in Crockford's base32 as groups of 4
+/// characters, e.g. `7K3M-Q9TX-B2WD-H4RE`.
+///
+/// # Arguments
+///
+/// * `tracker` - 32-byte tracker from `ballot_tracker`.
+///
+/// # Returns
+///
+/// Returns the tracking code as `Result<String, Error>`.
+pub fn tracker_code(tracker: &Vec<u8>) -> Result<String, Error> {
+    let tracker = B256::try_from(tracker.as_slice())?;
+    let bits = tracker[..10]
+        .iter()
+        .fold(0_u128, |bits, byte| (bits << 8) | u128::from(*byte));
+
+    let symbols = (0..TRACKER_GROUPS * 4)
+        .rev()
+        .map(|i| TRACKER_ALPHABET[(bits >> (5 * i)) as usize & 31] as char)
+        .collect::<Vec<_>>();
+
+    Ok(symbols
+        .chunks(4)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-"))
+}
 
-    to_allocvec(&vec![
-        Ciphertext::<Ristretto>::zero();
-        candidate_count.to::<usize>()
-    ])
-    .unwrap()
+/// Starts the transcript of a ballot proof.
+///
+/// Every ballot proof commits to the ballot context, so a copied ballot fails verification
+/// in another election or under another voter token.
+pub(crate) fn bound_transcript(label: &'static [u8], context: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(label);
+    transcript.append_message(b"ballot_context", context);
+    transcript
 }
 
 /// Encrypts a vote for a specific choice.
 ///
+/// The ballot is a multi-choice ballot that selects exactly one option.
+///
 /// # Arguments
 ///
 /// * `pub_key_bytes` - Public key of the election authority.
 /// * `choice` - Index of the selected option.
 /// * `options_count` - Total number of options available.
+/// * `context` - Ballot context from `ballot_context`.
 ///
 /// # Returns
 ///
-/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Box<dyn Error>>`.
+/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Error>`.
 pub fn encrypt_vote(
     pub_key_bytes: &Vec<u8>,
     choice: usize,
     options_count: usize,
-) -> Result<Vec<u8>, Box<dyn Error>> {
-    let rng = &mut thread_rng();
-    let receiver = PublicKey::<Ristretto>::from_bytes(&pub_key_bytes)?;
-
-    let params = ChoiceParams::single(receiver, options_count);
-    let ballot = EncryptedChoice::single(&params, choice, rng);
-
-    Ok(to_allocvec(&ballot).unwrap())
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    encrypt_multi_vote(pub_key_bytes, &vec![choice], options_count, 1, 1, context)
 }
 
-/// Verifies the validity of an encrypted vote using a Zero-Knowledge Proof.
+/// Verifies the validity of an encrypted vote using Zero-Knowledge Proofs.
+///
+/// The ballot is checked by the backend of the election, see `backend::Backend`, and a
+/// ballot written with another scheme is rejected. BFV ballots have no validity proof, so
+/// they are refused with an `Error::Proof`.
 ///
 /// # Arguments
 ///
-/// * `data` - ABI encoded sequence of `(candidate_count, public_key, ballot)`.
+/// * `data` - ABI encoded sequence of `(candidate_count, context, public_key, ballot,
+///   scheme)`, where `scheme` is the one the election was created with in
+///   `generate_backend_acc`, `RISTRETTO_ELGAMAL` for accumulators from `generate_acc`.
 ///
 /// # Returns
 ///
 /// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
-pub fn verify_vote(data: &Vec<u8>) -> Vec<u8> {
-    let (candidate_count, public_key, ballot) =
-        <(U256, Bytes, Bytes)>::abi_decode_sequence(&data).unwrap();
-
-    let public_key = PublicKey::<Ristretto>::from_bytes(&public_key.to_vec()).unwrap();
-    let ballot = from_bytes::<EncryptedChoice<Ristretto, SingleChoice>>(&ballot.to_vec()).unwrap();
-
-    let params = ChoiceParams::single(public_key, candidate_count.to::<usize>());
+#[allow(clippy::ptr_arg)]
+pub fn verify_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, context, public_key, ballot, scheme) =
+        <(U256, Bytes, Bytes, Bytes, U256)>::abi_decode_sequence(data)?;
 
     let mut output = vec![0; 32];
 
-    if let Ok(_) = ballot.verify(&params) {
+    if backend::verify_vote(
+        scheme.try_into()?,
+        candidate_count.try_into()?,
+        &context,
+        &public_key,
+        &ballot,
+    )? {
         output[31] = 1;
     };
 
-    output
+    Ok(output)
+}
+
+/// Verifies many encrypted votes for the same options and choice bounds at once.
+///
+/// elastic-elgamal has no batch verification for its proofs, so the ballots are checked in
+/// parallel on the rayon thread pool, in a single call instead of one call per ballot.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, min, max, contexts, public_key,
+///   ballots)`, where `contexts[i]` is the context of `ballots[i]`. Single-choice ballots
+///   have a `min` and `max` of 1.
+///
+/// # Returns
+///
+/// Returns the ABI encoded `bool[]` telling whether each ballot is valid. A ballot that
+/// cannot be decoded is invalid.
+#[allow(clippy::ptr_arg)]
+pub fn verify_votes_batch(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, min, max, contexts, public_key, ballots) =
+        <(U256, U256, U256, Vec<Bytes>, Bytes, Vec<Bytes>)>::abi_decode_sequence(data)?;
+
+    if contexts.len() != ballots.len() {
+        return Err(Error::InvalidInput(
+            "Ballots and contexts do not match".into(),
+        ));
+    }
+
+    let candidate_count = candidate_count.try_into()?;
+    let bounds = (min.try_into()?, max.try_into()?);
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let ballots = decode_ballots(&ballots);
+
+    Ok(verify_batch(&ballots, &contexts, &public_key, candidate_count, bounds).abi_encode())
+}
+
+/// Decodes multi-choice ballots together with their election IDs, mapping the malformed
+/// ones and those of another format version to `None`.
+fn decode_ballots(ballots: &[Bytes]) -> Vec<Option<(B256, MultiChoiceBallot)>> {
+    ballots
+        .iter()
+        .map(|ballot| {
+            let (election_id, ballot) = open(MULTI_CHOICE_BALLOT, ballot).ok()?;
+            Some((election_id, from_bytes::<MultiChoiceBallot>(ballot).ok()?))
+        })
+        .collect()
+}
+
+/// Checks the proofs of multi-choice ballots with `(min, max)` choices in parallel.
+fn verify_batch(
+    ballots: &[Option<(B256, MultiChoiceBallot)>],
+    contexts: &[Bytes],
+    receiver: &PublicKey<Ristretto>,
+    options_count: usize,
+    (min, max): (usize, usize),
+) -> Vec<bool> {
+    ballots
+        .par_iter()
+        .zip(contexts)
+        .map(|(ballot, context)| {
+            ballot.as_ref().is_some_and(|(election_id, ballot)| {
+                in_election(election_id, context)
+                    && verify_multi_choice(ballot, receiver, options_count, min, max, context)
+            })
+        })
+        .collect()
 }
 
 /// Adds an encrypted vote to the current accumulator.
 ///
+/// The ballot is added by the backend written in the envelope of the accumulator, and
+/// must have been encrypted with the same backend. BFV accumulators are rejected, since
+/// their ballots cannot be verified: see `backend::add_trusted_backend_vote`.
+///
 /// # Arguments
 ///
 /// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
@@ -103,81 +400,1539 @@ pub fn verify_vote(data: &Vec<u8>) -> Vec<u8> {
 /// # Returns
 ///
 /// Returns the updated accumulator as `Vec<u8>`.
-pub fn add_votes(data: &Vec<u8>) -> Vec<u8> {
-    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(&data).unwrap();
+#[allow(clippy::ptr_arg)]
+pub fn add_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    backend::add_vote(&acc, &ballot)
+}
 
-    let mut acc = from_bytes::<Vec<Ciphertext<Ristretto>>>(&acc.to_vec()).unwrap();
-    let ballot = from_bytes::<EncryptedChoice<Ristretto, SingleChoice>>(&ballot.to_vec()).unwrap();
+/// Transcript labels shared by ballot proofs and their verification.
+const MULTI_CHOICE_SUM_LABEL: &[u8] = b"multi_choice_sum";
+const SCORE_RANGE_LABEL: &[u8] = b"score_range";
+const SCORE_FLAGS_LABEL: &[u8] = b"score_flags";
+const SCORE_BUDGET_LABEL: &[u8] = b"score_budget";
 
-    for (i, choice) in ballot.choices_unchecked().iter().enumerate() {
-        acc[i] += *choice;
+/// Randomness source that only yields zeros.
+///
+/// Encrypting with it gives the trivial ciphertext of a public value, which sums of
+/// flags can be checked against.
+struct ZeroRng;
+
+impl RngCore for ZeroRng {
+    fn next_u32(&mut self) -> u32 {
+        0
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        0
     }
 
-    to_allocvec(&acc).unwrap()
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(0);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_legacy::Error> {
+        dest.fill(0);
+        Ok(())
+    }
 }
 
-/// Verifies a blinded signature against a message.
+impl CryptoRng for ZeroRng {}
+
+/// Encrypts a public value with zero randomness, so that it can be used in proofs that
+/// other ciphertexts add up to it.
+pub(crate) fn public_value(
+    value: u64,
+    receiver: &PublicKey<Ristretto>,
+) -> CiphertextWithValue<Ristretto> {
+    CiphertextWithValue::new(value, receiver, &mut ZeroRng).generalize()
+}
+
+/// Derives the randomness source of the flag `index` of a multi-choice ballot from the
+/// seed of the ballot.
+///
+/// Every flag draws from its own ChaCha20 stream, so `audit_ballot` can re-encrypt each
+/// flag on its own with the public API of elastic-elgamal.
+fn flag_rng(seed: &[u8; 32], index: usize) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::from_seed(*seed);
+    rng.set_stream(index as u64);
+    rng
+}
+
+/// Checks the selection bounds of a multi-choice ballot.
+fn check_bounds(options_count: usize, min: usize, max: usize) -> Result<(), Error> {
+    if options_count == 0 || min > max || max > options_count {
+        return Err(Error::InvalidInput("Invalid selection bounds".into()));
+    }
+
+    Ok(())
+}
+
+/// Encrypts a vote that selects several options.
+///
+/// Every option is encrypted as a flag with a proof that it is 0 or 1. To prove that the
+/// number of selected options lies in `min..=max`, the ballot carries `max - min` padding
+/// flags, of which exactly `max - choices.len()` are set, and a proof that all the flags add
+/// up to `max`. Approval voting corresponds to `min = 0, max = options_count`, and picking
+/// exactly `k` options to `min = max = k`.
 ///
 /// # Arguments
 ///
-/// * `data` - ABI encoded sequence of `(public_key_pem, signature, msg)`.
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `choices` - Indexes of the selected options.
+/// * `options_count` - Total number of options available.
+/// * `min` - Minimum number of options the voter must select.
+/// * `max` - Maximum number of options the voter may select.
+/// * `context` - Ballot context from `ballot_context`.
 ///
 /// # Returns
 ///
-/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
-pub fn verify(data: &Vec<u8>) -> Vec<u8> {
-    let (public_key_pem, signature, msg) =
-        <(String, Bytes, Bytes)>::abi_decode_sequence(&data).unwrap();
+/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_multi_vote(
+    pub_key_bytes: &Vec<u8>,
+    choices: &Vec<usize>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let election_id = context_election(context)?;
+    let (ballot, _) =
+        encrypt_multi_choice(pub_key_bytes, choices, options_count, min, max, context)?;
+
+    Ok(seal(
+        MULTI_CHOICE_BALLOT,
+        &election_id,
+        &to_allocvec(&ballot)?,
+    ))
+}
+
+/// Encrypts a multi-choice ballot and returns it with the seed the randomness of its flags
+/// is derived from.
+pub(crate) fn encrypt_multi_choice(
+    pub_key_bytes: &[u8],
+    choices: &[usize],
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: &[u8],
+) -> Result<(MultiChoiceBallot, [u8; 32]), Error> {
+    check_bounds(options_count, min, max)?;
+
+    let rng = &mut thread_rng();
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+
+    let mut selected = vec![false; options_count];
+
+    for &choice in choices {
+        if choice >= options_count {
+            return Err(Error::InvalidInput("Choice out of bounds".into()));
+        }
+        if selected[choice] {
+            return Err(Error::InvalidInput("Duplicate choice".into()));
+        }
 
-    let public_key =
-        blind_rsa_signatures::PublicKey::<Sha384, PSS, Deterministic>::from_pem(&public_key_pem)
-            .unwrap();
+        selected[choice] = true;
+    }
+
+    if choices.len() < min || choices.len() > max {
+        return Err(Error::InvalidInput(
+            "Number of choices out of bounds".into(),
+        ));
+    }
+
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+
+    let padding = (0..max - min).map(|i| i < max - choices.len());
+    let range = prepare_range(1);
+
+    let flags = selected
+        .into_iter()
+        .chain(padding)
+        .enumerate()
+        .map(|(index, flag)| {
+            let flag =
+                CiphertextWithValue::new(u64::from(flag), &receiver, &mut flag_rng(&seed, index));
+            prove_range(flag, &range, &receiver, context, rng)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Flags are 0 or 1, so the sum of their squares is the number of set flags
+    let sum_proof = SumOfSquaresProof::new(
+        flags.iter().map(|(flag, _)| flag),
+        &public_value(max as u64, &receiver),
+        &receiver,
+        &mut bound_transcript(MULTI_CHOICE_SUM_LABEL, context),
+        rng,
+    );
+
+    let mut flags = flags.into_iter().map(|(_, flag)| flag).collect::<Vec<_>>();
+    let padding = flags.split_off(options_count);
+
+    Ok(((flags, padding, sum_proof), seed))
+}
+
+/// Encrypts a vote for a specific choice and keeps its randomness for a Benaloh challenge.
+///
+/// The voter then either casts the ballot and discards the randomness, or spoils it by
+/// publishing the randomness so anyone can check with `audit_ballot` that it encrypts the
+/// intended choice. A spoiled ballot reveals the vote and must never be cast; the voter
+/// encrypts a fresh one instead.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `choice` - Index of the selected option.
+/// * `options_count` - Total number of options available.
+/// * `context` - Ballot context from `ballot_context`.
+///
+/// # Returns
+///
+/// Returns the encrypted vote (ballot) and the 32-byte seed of its randomness.
+pub fn encrypt_vote_for_audit(
+    pub_key_bytes: &Vec<u8>,
+    choice: usize,
+    options_count: usize,
+    context: &Vec<u8>,
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    encrypt_multi_vote_for_audit(pub_key_bytes, &vec![choice], options_count, 1, 1, context)
+}
+
+/// Encrypts a vote that selects several options and keeps its randomness for a Benaloh
+/// challenge, as `encrypt_vote_for_audit` does.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `choices` - Indexes of the selected options.
+/// * `options_count` - Total number of options available.
+/// * `min` - Minimum number of options the voter must select.
+/// * `max` - Maximum number of options the voter may select.
+/// * `context` - Ballot context from `ballot_context`.
+///
+/// # Returns
+///
+/// Returns the encrypted vote (ballot) and the 32-byte seed of its randomness.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_multi_vote_for_audit(
+    pub_key_bytes: &Vec<u8>,
+    choices: &Vec<usize>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: &Vec<u8>,
+) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let election_id = context_election(context)?;
+    let (ballot, seed) =
+        encrypt_multi_choice(pub_key_bytes, choices, options_count, min, max, context)?;
+    let ballot = seal(MULTI_CHOICE_BALLOT, &election_id, &to_allocvec(&ballot)?);
+
+    Ok((ballot, seed.to_vec()))
+}
+
+/// Recovers the choices of a spoiled ballot from the seed of its randomness.
+///
+/// The ballot must belong to the election of `context` and carry valid proofs for the
+/// options and bounds of the election, so the audit covers the ballot that would have been
+/// cast. Every flag is then encrypted again as 0 and as 1 with the randomness derived from
+/// the seed, and must match one of the two. This does not need the secret key, so the voter
+/// can run it on any device.
+///
+/// # Arguments
+///
+/// * `public_key` - Public key of the election authority.
+/// * `ballot` - Ballot returned by `encrypt_vote_for_audit` or `encrypt_multi_vote_for_audit`.
+/// * `randomness` - Seed returned together with the ballot.
+/// * `options_count` - Total number of options available.
+/// * `min` - Minimum number of options a voter must select, 1 for single-choice ballots.
+/// * `max` - Maximum number of options a voter may select, 1 for single-choice ballots.
+/// * `context` - Ballot context the ballot was encrypted with.
+///
+/// # Returns
+///
+/// Returns the indexes of the options the ballot selects, an `Error::InvalidInput` if the
+/// ballot belongs to another election, or an `Error::Proof` if its proofs are invalid or it
+/// does not match the seed.
+#[allow(clippy::ptr_arg)]
+pub fn audit_ballot(
+    public_key: &Vec<u8>,
+    ballot: &Vec<u8>,
+    randomness: &Vec<u8>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: &Vec<u8>,
+) -> Result<Vec<usize>, Error> {
+    let receiver = PublicKey::<Ristretto>::from_bytes(public_key)?;
+    let (election_id, ballot) = open(MULTI_CHOICE_BALLOT, ballot)?;
+    let ballot = from_bytes::<MultiChoiceBallot>(ballot)?;
 
-    let (signature_raw, msg_randomizer) = from_bytes::<(Vec<u8>, [u8; 32])>(&signature).unwrap();
+    if !in_election(&election_id, context) {
+        return Err(Error::InvalidInput(
+            "Ballot belongs to another election".into(),
+        ));
+    }
+    if !verify_multi_choice(&ballot, &receiver, options_count, min, max, context) {
+        return Err(Error::Proof("Ballot proofs are invalid".into()));
+    }
+
+    let (choices, padding, _) = ballot;
+    let seed = <[u8; 32]>::try_from(randomness.as_slice())?;
+
+    let flags = choices
+        .iter()
+        .chain(&padding)
+        .enumerate()
+        .map(|(index, (flag, _))| {
+            [false, true]
+                .into_iter()
+                .find(|&value| {
+                    let encrypted = CiphertextWithValue::new(
+                        u64::from(value),
+                        &receiver,
+                        &mut flag_rng(&seed, index),
+                    );
+                    encrypted.inner().to_bytes() == flag.to_bytes()
+                })
+                .ok_or_else(|| Error::Proof("Ballot does not match the randomness".into()))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(flags[..choices.len()]
+        .iter()
+        .enumerate()
+        .filter_map(|(i, flag)| flag.then_some(i))
+        .collect())
+}
+
+/// Checks every proof of a multi-choice ballot.
+pub(crate) fn verify_multi_choice(
+    ballot: &MultiChoiceBallot,
+    receiver: &PublicKey<Ristretto>,
+    options_count: usize,
+    min: usize,
+    max: usize,
+    context: &[u8],
+) -> bool {
+    let (choices, padding, sum_proof) = ballot;
+
+    if check_bounds(options_count, min, max).is_err()
+        || choices.len() != options_count
+        || padding.len() != max - min
+    {
+        return false;
+    }
+
+    verify_ranged_flags(choices, receiver, context)
+        && verify_ranged_flags(padding, receiver, context)
+        && sum_proof
+            .verify(
+                choices.iter().chain(padding).map(|(flag, _)| flag),
+                public_value(max as u64, receiver).inner(),
+                receiver,
+                &mut bound_transcript(MULTI_CHOICE_SUM_LABEL, context),
+            )
+            .is_ok()
+}
+
+/// Verifies the validity of an encrypted multi-choice vote using Zero-Knowledge Proofs.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, min, max, context, public_key, ballot)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_multi_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, min, max, context, public_key, ballot) =
+        <(U256, U256, U256, Bytes, Bytes, Bytes)>::abi_decode_sequence(data)?;
 
-    let signature = Signature::new(signature_raw);
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let (election_id, ballot) = open(MULTI_CHOICE_BALLOT, &ballot)?;
+    let ballot = from_bytes::<MultiChoiceBallot>(ballot)?;
 
     let mut output = vec![0; 32];
 
-    if let Ok(_) = public_key.verify(
-        &signature,
-        Some(MessageRandomizer::new(msg_randomizer)),
-        msg,
-    ) {
+    if in_election(&election_id, &context)
+        && verify_multi_choice(
+            &ballot,
+            &public_key,
+            candidate_count.try_into()?,
+            min.try_into()?,
+            max.try_into()?,
+            &context,
+        )
+    {
         output[31] = 1;
     }
 
-    output
+    Ok(output)
 }
 
-/// Decrypts the final election results.
+/// Single-choice or multi-choice ballot added by `add_votes_batch`.
+enum BatchBallot {
+    MultiChoice(MultiChoiceBallot),
+    Legacy(LegacyBallot),
+}
+
+/// Decodes a multi-choice or legacy single-choice ballot together with its election ID,
+/// mapping the malformed ones and those of another format version to `None`.
+fn decode_batch_ballot(ballot: &[u8]) -> Option<(B256, BatchBallot)> {
+    if let Ok((election_id, ballot)) = open(MULTI_CHOICE_BALLOT, ballot) {
+        let ballot = from_bytes::<MultiChoiceBallot>(ballot).ok()?;
+        return Some((election_id, BatchBallot::MultiChoice(ballot)));
+    }
+
+    let (election_id, ballot) = open(LEGACY_SINGLE_CHOICE_BALLOT, ballot).ok()?;
+    let ballot = from_bytes::<LegacyBallot>(ballot).ok()?;
+
+    Some((election_id, BatchBallot::Legacy(ballot)))
+}
+
+/// Adds many encrypted votes to the current accumulator at once.
+///
+/// The accumulator is decoded and encoded once for the whole batch, and the ballots are added
+/// by the backend written in its envelope, as in `add_votes`. BFV accumulators are rejected.
+/// With `verify`, the ballots are checked as in `verify_votes_batch` and the invalid ones are
+/// left out; otherwise `min`, `max`, `contexts` and `public_key` are ignored and only the
+/// ballots that cannot be decoded or do not match the accumulator are left out. Ballots of
+/// another election are always left out.
+///
+/// `ElGamal` accumulators also take single-choice ballots of the first format, migrated as
+/// `LEGACY_SINGLE_CHOICE_BALLOT`s, which are checked as in `verify_legacy_vote` and only pass
+/// verification with a `min` and `max` of 1. The other backends only verify single-choice
+/// ballots, so `verify` with other bounds is an error.
 ///
 /// # Arguments
 ///
-/// * `secret_key` - Secret key of the election authority.
-/// * `raw_result` - The final accumulator containing aggregated votes.
-/// * `max_count` - Upper bound for the discrete log lookup table. Must be at least
-///   as large as the highest expected vote count per candidate.
+/// * `data` - ABI encoded sequence of `(accumulator, ballots, verify, min, max, contexts,
+///   public_key)`, where `contexts[i]` is the context of `ballots[i]`. Single-choice ballots
+///   have a `min` and `max` of 1.
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing a vector of vote counts per option.
-pub fn decrypt_result(
-    secret_key: &Vec<u8>,
-    raw_result: &Vec<u8>,
-    max_count: u64,
-) -> Result<Vec<u64>, Box<dyn Error>> {
-    let result = from_bytes::<Vec<Ciphertext<Ristretto>>>(raw_result.as_slice())?;
+/// Returns the ABI encoded sequence of `(accumulator, rejected)`, where `rejected` holds the
+/// indexes of the ballots that were left out.
+#[allow(clippy::ptr_arg)]
+pub fn add_votes_batch(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballots, verify, min, max, contexts, public_key) =
+        <(Bytes, Vec<Bytes>, bool, U256, U256, Vec<Bytes>, Bytes)>::abi_decode_sequence(data)?;
 
-    let sk = match SecretKey::<Ristretto>::from_bytes(&secret_key) {
-        Some(res) => res,
-        None => return Err(Box::from("Unexpected error")),
-    };
+    if verify && contexts.len() != ballots.len() {
+        return Err(Error::InvalidInput(
+            "Ballots and contexts do not match".into(),
+        ));
+    }
+
+    if scheme_of(&acc)? != RISTRETTO_ELGAMAL {
+        if verify && (min, max) != (U256::from(1), U256::from(1)) {
+            return Err(Error::InvalidInput(
+                "Only single-choice ballots are verified with this backend".into(),
+            ));
+        }
 
-    let lookup_table = DiscreteLogTable::new(0..=max_count);
+        let checks = verify.then_some((contexts.as_slice(), public_key.as_ref()));
+        let (acc, rejected) = backend::add_vote_batch(&acc, &ballots, checks)?;
+
+        return Ok((Bytes::from(acc), rejected).abi_encode_sequence());
+    }
 
-    Ok(result
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballots = ballots
         .iter()
-        .map(|choice| sk.decrypt(*choice, &lookup_table).unwrap())
-        .collect::<Vec<u64>>())
+        .map(|ballot| decode_batch_ballot(ballot))
+        .collect::<Vec<_>>();
+
+    let valid = if verify {
+        let (min, max): (usize, usize) = (min.try_into()?, max.try_into()?);
+        let receiver = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+        let options_count = acc.len();
+
+        ballots
+            .par_iter()
+            .zip(&contexts)
+            .map(|(ballot, context)| {
+                ballot.as_ref().is_some_and(|(ballot_election, ballot)| {
+                    in_election(ballot_election, context)
+                        && match ballot {
+                            BatchBallot::MultiChoice(ballot) => verify_multi_choice(
+                                ballot,
+                                &receiver,
+                                options_count,
+                                min,
+                                max,
+                                context,
+                            ),
+                            BatchBallot::Legacy(ballot) => {
+                                (min, max) == (1, 1)
+                                    && options_count > 0
+                                    && ballot
+                                        .verify(&ChoiceParams::single(
+                                            receiver.clone(),
+                                            options_count,
+                                        ))
+                                        .is_ok()
+                            }
+                        }
+                })
+            })
+            .collect()
+    } else {
+        vec![true; ballots.len()]
+    };
+
+    let mut rejected = Vec::new();
+
+    for (index, (ballot, valid)) in ballots.iter().zip(valid).enumerate() {
+        let added = match ballot {
+            Some((ballot_election, ballot)) if valid && *ballot_election == election_id => {
+                match ballot {
+                    BatchBallot::MultiChoice((choices, _, _)) => {
+                        accumulate(&mut acc, choices.iter().map(|(choice, _)| *choice))
+                    }
+                    BatchBallot::Legacy(ballot) => {
+                        accumulate(&mut acc, ballot.choices_unchecked().iter().copied())
+                    }
+                }
+                .is_ok()
+            }
+            _ => false,
+        };
+
+        if !added {
+            rejected.push(U256::from(index));
+        }
+    }
+
+    let added = (ballots.len() - rejected.len()) as u64;
+    let acc = encode_acc(&election_id, count, added, &acc)?;
+
+    Ok((Bytes::from(acc), rejected).abi_encode_sequence())
+}
+
+/// Adds an encrypted multi-choice vote to the current accumulator.
+///
+/// The accumulator has the same format as for single-choice votes, so the results are
+/// decrypted in the same way.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_multi_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballot = open_ballot(MULTI_CHOICE_BALLOT, &ballot, &election_id)?;
+    let (choices, _, _) = from_bytes::<MultiChoiceBallot>(ballot)?;
+
+    accumulate(&mut acc, choices.iter().map(|(choice, _)| *choice))?;
+
+    encode_acc(&election_id, count, 1, &acc)
+}
+
+/// Verifies a single-choice ballot of the first format, migrated with
+/// `envelope::migrate_ballot` as a `LEGACY_SINGLE_CHOICE_BALLOT`.
+///
+/// Its proofs commit to no ballot context, so only the election ID of its envelope ties it to
+/// an election. It is meant to finish the tallies of elections run before ballots were bound
+/// to their context, and new ballots must be cast with `encrypt_vote`.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, election_id, public_key, ballot)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_legacy_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, election_id, public_key, ballot) =
+        <(U256, B256, Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let candidate_count: usize = candidate_count.try_into()?;
+    if candidate_count == 0 {
+        return Err(Error::InvalidInput("Invalid number of options".into()));
+    }
+
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let (ballot_election, ballot) = open(LEGACY_SINGLE_CHOICE_BALLOT, &ballot)?;
+    let ballot = from_bytes::<LegacyBallot>(ballot)?;
+
+    let mut output = vec![0; 32];
+
+    if ballot_election == election_id
+        && ballot
+            .verify(&ChoiceParams::single(public_key, candidate_count))
+            .is_ok()
+    {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Adds a single-choice ballot of the first format to the current accumulator.
+///
+/// The ballot is not verified here, which is the job of `verify_legacy_vote`, and counts
+/// as a single-choice vote in the same accumulator format.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_legacy_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballot = open_ballot(LEGACY_SINGLE_CHOICE_BALLOT, &ballot, &election_id)?;
+    let ballot = from_bytes::<LegacyBallot>(ballot)?;
+
+    accumulate(&mut acc, ballot.choices_unchecked().iter().copied())?;
+
+    encode_acc(&election_id, count, 1, &acc)
+}
+
+/// Adds the ciphertexts of a ballot to the matching accumulator entries.
+pub(crate) fn accumulate(
+    acc: &mut [Ciphertext<Ristretto>],
+    ciphertexts: impl ExactSizeIterator<Item = Ciphertext<Ristretto>>,
+) -> Result<(), Error> {
+    if ciphertexts.len() != acc.len() {
+        return Err(Error::InvalidInput(
+            "Ballot does not match the accumulator".into(),
+        ));
+    }
+
+    for (entry, ciphertext) in acc.iter_mut().zip(ciphertexts) {
+        *entry += ciphertext;
+    }
+
+    Ok(())
+}
+
+/// Prepares the range of values `0..=max`.
+fn prepare_range(max: u64) -> PreparedRange<Ristretto> {
+    RangeDecomposition::optimal(max.saturating_add(1)).into()
+}
+
+/// Encrypts a value in `range` together with its range proof, keeping its randomness for
+/// further proofs.
+fn encrypt_range<R: CryptoRng + RngCore>(
+    value: u64,
+    range: &PreparedRange<Ristretto>,
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+    rng: &mut R,
+) -> Result<(CiphertextWithValue<Ristretto>, ProvenRange), Error> {
+    let ciphertext = CiphertextWithValue::new(value, receiver, rng);
+
+    prove_range(ciphertext, range, receiver, context, rng)
+}
+
+/// Proves that an encrypted value is in `range`.
+fn prove_range<R: CryptoRng + RngCore>(
+    ciphertext: CiphertextWithValue<Ristretto, u64>,
+    range: &PreparedRange<Ristretto>,
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+    rng: &mut R,
+) -> Result<(CiphertextWithValue<Ristretto>, ProvenRange), Error> {
+    let proof = RangeProof::from_ciphertext(
+        receiver,
+        range,
+        &ciphertext,
+        &mut bound_transcript(SCORE_RANGE_LABEL, context),
+        rng,
+    );
+    let proven = (*ciphertext.inner(), serde_json::to_vec(&proof)?);
+
+    Ok((ciphertext.generalize(), proven))
+}
+
+/// Checks the range proof of an encrypted value.
+fn verify_range(
+    (ciphertext, proof): &ProvenRange,
+    range: &PreparedRange<Ristretto>,
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+) -> bool {
+    serde_json::from_slice::<RangeProof<Ristretto>>(proof).is_ok_and(|proof| {
+        proof
+            .verify(
+                receiver,
+                range,
+                *ciphertext,
+                &mut bound_transcript(SCORE_RANGE_LABEL, context),
+            )
+            .is_ok()
+    })
+}
+
+/// Encrypts a flag together with the proof that it is in `0..=1`, keeping its ciphertext for
+/// the sum-of-squares proofs of elastic-elgamal.
+pub(crate) fn encrypt_ranged_flag<R: CryptoRng + RngCore>(
+    flag: bool,
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+    rng: &mut R,
+) -> Result<(CiphertextWithValue<Ristretto>, ProvenRange), Error> {
+    encrypt_range(u64::from(flag), &prepare_range(1), receiver, context, rng)
+}
+
+/// Checks the proofs that encrypted flags are in `0..=1`.
+pub(crate) fn verify_ranged_flags(
+    flags: &[ProvenRange],
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+) -> bool {
+    let range = prepare_range(1);
+
+    flags
+        .iter()
+        .all(|flag| verify_range(flag, &range, receiver, context))
+}
+
+/// Encrypts a vote that gives every candidate a score in `0..=max`, e.g. 0 to 5 stars.
+///
+/// Every score comes with a range proof. With a `budget`, the ballot also proves that the
+/// scores add up to at most `budget`, which gives cumulative voting. To do so, every score
+/// is split into `max` unary flags that are 0 or 1, and the total of all the flags is range
+/// proven against the budget, so the ballot grows linearly with `max`.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `scores` - Score of every candidate.
+/// * `max` - Highest score a candidate can get.
+/// * `budget` - Optional upper bound for the sum of the scores.
+/// * `context` - Ballot context from `ballot_context`.
+///
+/// # Returns
+///
+/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_score_vote(
+    pub_key_bytes: &Vec<u8>,
+    scores: &Vec<u64>,
+    max: u64,
+    budget: Option<u64>,
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    if scores.is_empty() || max == 0 || budget == Some(0) {
+        return Err(Error::InvalidInput("Invalid score bounds".into()));
+    }
+    if scores.iter().any(|&score| score > max) {
+        return Err(Error::InvalidInput("Score out of bounds".into()));
+    }
+
+    let election_id = context_election(context)?;
+
+    let rng = &mut thread_rng();
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+    let range = prepare_range(max);
+
+    let mut held = Vec::with_capacity(scores.len());
+    let mut proven = Vec::with_capacity(scores.len());
+
+    for &score in scores {
+        let (ciphertext, score) = encrypt_range(score, &range, &receiver, context, rng)?;
+        held.push(ciphertext);
+        proven.push(score);
+    }
+
+    let budget_proof = match budget {
+        Some(budget) => {
+            let total = scores.iter().sum::<u64>();
+            if total > budget {
+                return Err(Error::InvalidInput("Scores exceed the budget".into()));
+            }
+
+            let flags = scores
+                .iter()
+                .flat_map(|&score| (0..max).map(move |k| k < score))
+                .map(|flag| encrypt_ranged_flag(flag, &receiver, context, rng))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let score_proofs = flags
+                .chunks(max as usize)
+                .zip(&held)
+                .map(|(score_flags, score)| {
+                    SumOfSquaresProof::new(
+                        score_flags.iter().map(|(flag, _)| flag),
+                        score,
+                        &receiver,
+                        &mut bound_transcript(SCORE_FLAGS_LABEL, context),
+                        rng,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let (total, proven_total) =
+                encrypt_range(total, &prepare_range(budget), &receiver, context, rng)?;
+            let total_proof = SumOfSquaresProof::new(
+                flags.iter().map(|(flag, _)| flag),
+                &total,
+                &receiver,
+                &mut bound_transcript(SCORE_BUDGET_LABEL, context),
+                rng,
+            );
+
+            let flags = flags.into_iter().map(|(_, flag)| flag).collect::<Vec<_>>();
+
+            Some((flags, score_proofs, proven_total, total_proof))
+        }
+        None => None,
+    };
+
+    Ok(seal(
+        SCORE_BALLOT,
+        &election_id,
+        &to_allocvec(&(proven, budget_proof))?,
+    ))
+}
+
+/// Checks the budget proof of a score ballot.
+fn verify_budget(
+    scores: &[ProvenRange],
+    budget_proof: &BudgetProof,
+    receiver: &PublicKey<Ristretto>,
+    max: u64,
+    budget: u64,
+    context: &[u8],
+) -> bool {
+    let (flags, score_proofs, total, total_proof) = budget_proof;
+
+    if (scores.len() as u64).checked_mul(max) != Some(flags.len() as u64)
+        || score_proofs.len() != scores.len()
+    {
+        return false;
+    }
+
+    if !verify_ranged_flags(flags, receiver, context) {
+        return false;
+    }
+
+    let rows = flags.chunks(max as usize).zip(scores).zip(score_proofs);
+
+    for ((score_flags, (score, _)), proof) in rows {
+        if proof
+            .verify(
+                score_flags.iter().map(|(ciphertext, _)| ciphertext),
+                score,
+                receiver,
+                &mut bound_transcript(SCORE_FLAGS_LABEL, context),
+            )
+            .is_err()
+        {
+            return false;
+        }
+    }
+
+    verify_range(total, &prepare_range(budget), receiver, context)
+        && total_proof
+            .verify(
+                flags.iter().map(|(ciphertext, _)| ciphertext),
+                &total.0,
+                receiver,
+                &mut bound_transcript(SCORE_BUDGET_LABEL, context),
+            )
+            .is_ok()
+}
+
+/// Checks every proof of a score ballot. A `budget` of 0 means that the ballot has none.
+pub(crate) fn verify_score(
+    ballot: &ScoreBallot,
+    receiver: &PublicKey<Ristretto>,
+    options_count: usize,
+    max: u64,
+    budget: u64,
+    context: &[u8],
+) -> bool {
+    let (scores, budget_proof) = ballot;
+
+    if scores.len() != options_count || options_count == 0 || max == 0 {
+        return false;
+    }
+
+    let range = prepare_range(max);
+    if !scores
+        .iter()
+        .all(|score| verify_range(score, &range, receiver, context))
+    {
+        return false;
+    }
+
+    match (budget, budget_proof) {
+        (0, None) => true,
+        (0, Some(_)) | (_, None) => false,
+        (budget, Some(proof)) => verify_budget(scores, proof, receiver, max, budget, context),
+    }
+}
+
+/// Verifies the validity of an encrypted score vote using Zero-Knowledge Proofs.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, max, budget, context, public_key, ballot)`,
+///   where a `budget` of 0 means that the scores are not limited by a budget.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_score_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, max, budget, context, public_key, ballot) =
+        <(U256, U256, U256, Bytes, Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let (election_id, ballot) = open(SCORE_BALLOT, &ballot)?;
+    let ballot = from_bytes::<ScoreBallot>(ballot)?;
+
+    let mut output = vec![0; 32];
+
+    if in_election(&election_id, &context)
+        && verify_score(
+            &ballot,
+            &public_key,
+            candidate_count.try_into()?,
+            max.try_into()?,
+            budget.try_into()?,
+            &context,
+        )
+    {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Adds an encrypted score vote to the current accumulator.
+///
+/// The accumulator has the same format as for single-choice votes, so the total scores are
+/// decrypted with `decrypt_result`, passing the highest score as `max_score`.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_score_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballot = open_ballot(SCORE_BALLOT, &ballot, &election_id)?;
+    let (scores, _) = from_bytes::<ScoreBallot>(ballot)?;
+
+    accumulate(&mut acc, scores.iter().map(|(score, _)| *score))?;
+
+    encode_acc(&election_id, count, 1, &acc)
+}
+
+/// Encodes the unblinded signature of a voter token together with its message randomizer.
+///
+/// The blind signature mode is written as the scheme of the envelope, so the verifier
+/// checks the token in the mode it was issued in.
+///
+/// # Arguments
+///
+/// * `signature` - Unblinded signature of the token.
+/// * `msg_randomizer` - 32-byte message randomizer the token was blinded with, empty in the
+///   deterministic mode.
+/// * `election_id` - 32-byte identifier of the election the token was issued for.
+/// * `scheme` - Blind signature mode of the election, `RSA_BLIND_PSS` or
+///   `RSA_BLIND_PSS_DETERMINISTIC`.
+///
+/// # Returns
+///
+/// Returns the encoded token as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encode_token(
+    signature: &Vec<u8>,
+    msg_randomizer: &Vec<u8>,
+    election_id: &Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, Error> {
+    let token = token(scheme, signature, msg_randomizer)?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+
+    Ok(seal_with(
+        scheme,
+        TOKEN,
+        &election_id,
+        &to_allocvec(&token)?,
+    ))
+}
+
+/// Encodes the unblinded signature of a voter token together with the ID of its signing key.
+///
+/// Unlike `encode_token`, the verifier resolves the key from its ID in a key registry, so
+/// the public key is not sent along with every vote.
+///
+/// # Arguments
+///
+/// * `signature` - Unblinded signature of the token.
+/// * `msg_randomizer` - 32-byte message randomizer the token was blinded with, empty in the
+///   deterministic mode.
+/// * `key_id` - 32-byte ID of the signing key, from `blind_signatures::key_id`.
+/// * `election_id` - 32-byte identifier of the election the token was issued for.
+/// * `scheme` - Blind signature mode of the election.
+///
+/// # Returns
+///
+/// Returns the encoded token as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encode_keyed_token(
+    signature: &Vec<u8>,
+    msg_randomizer: &Vec<u8>,
+    key_id: &Vec<u8>,
+    election_id: &Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, Error> {
+    let key_id = <[u8; 32]>::try_from(key_id.as_slice())?;
+    let token = token(scheme, signature, msg_randomizer)?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+
+    Ok(seal_with(
+        scheme,
+        KEYED_TOKEN,
+        &election_id,
+        &to_allocvec(&(key_id, token))?,
+    ))
+}
+
+/// Verifies a blinded signature against a message.
+///
+/// The token is checked in the blind signature mode of the election, and a token written in
+/// the other mode is rejected.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(public_key_pem, signature, msg, scheme)`, where
+///   `signature` is a token from `encode_token` and `scheme` is the blind signature mode of
+///   the election, `RSA_BLIND_PSS` or `RSA_BLIND_PSS_DETERMINISTIC`.
+///
+/// # Returns
+///
+/// Returns `abi.encode(valid, nullifier)`: a 32-byte word whose last byte is 1 if valid, 0
+/// otherwise, followed by the 32-byte nullifier of the token, all zeros if invalid.
+#[allow(clippy::ptr_arg)]
+pub fn verify(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (public_key_pem, signature, msg, scheme) =
+        <(String, Bytes, Bytes, U256)>::abi_decode_sequence(data)?;
+
+    let scheme = scheme.try_into()?;
+    let (_, token) = open_with(scheme, TOKEN, &signature)?;
+    let token = from_bytes::<Token>(token)?;
+
+    let public_key = BlindPublic::from_pem(&public_key_pem)?;
+
+    if verify_token(&public_key, scheme, &token, &msg)? {
+        return Ok(valid_token(&nullifier(&msg.to_vec(), &token.0)));
+    }
+
+    Ok(vec![0; 64])
+}
+
+/// Encodes the output of a valid token, `abi.encode(true, nullifier)`.
+fn valid_token(nullifier: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; 64];
+    output[31] = 1;
+    output[32..].copy_from_slice(nullifier);
+
+    output
+}
+
+/// Verifies a partially blind token against a message and the public metadata it was
+/// issued with.
+///
+/// The token must belong to `election_id`, and is rejected once `timestamp` is past its
+/// expiry. The signature is checked with the key derived from the master public key for
+/// `token_metadata(election_id, expiry)`, so a token issued for another election or expiry
+/// does not verify.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(public_key_pem, signature, msg, election_id, expiry,
+///   timestamp)`, where `signature` is a token from `encode_token` and `timestamp` is the
+///   current time, e.g. `block.timestamp`.
+///
+/// # Returns
+///
+/// Returns `abi.encode(valid, nullifier)`, as `verify` does.
+#[allow(clippy::ptr_arg)]
+pub fn verify_partially_blind(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (public_key_pem, signature, msg, election_id, expiry, timestamp) =
+        <(String, Bytes, Bytes, B256, U256, U256)>::abi_decode_sequence(data)?;
+
+    let (token_election, token) = open(TOKEN, &signature)?;
+    let (signature_raw, msg_randomizer) = from_bytes::<Token>(token)?;
+
+    let public_key = PartiallyBlindPublic::from_pem(&public_key_pem)?;
+    let expiry: u64 = expiry.try_into()?;
+
+    if token_election == election_id
+        && timestamp <= U256::from(expiry)
+        && verify_with_metadata(
+            &public_key,
+            signature_raw.clone(),
+            msg_randomizer,
+            &msg,
+            &token_metadata(&election_id.to_vec(), expiry)?,
+        )?
+    {
+        return Ok(valid_token(&nullifier(&msg.to_vec(), &signature_raw)));
+    }
+
+    Ok(vec![0; 64])
+}
+
+/// Verifies a keyed token against a message, with the signing key its ID resolves to.
+///
+/// Tokens of unknown or retired keys, or presented outside the validity window of their key,
+/// are rejected, as are tokens written in another blind signature mode than the election's.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(registry, token, msg, timestamp, scheme)`, where
+///   `registry` is the key registry of the election, `token` is from `encode_keyed_token`,
+///   `timestamp` is the current time, e.g. `block.timestamp`, and `scheme` is the blind
+///   signature mode of the election.
+///
+/// # Returns
+///
+/// Returns `abi.encode(valid, nullifier)`, as `verify` does.
+#[allow(clippy::ptr_arg)]
+pub fn verify_by_key_id(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (registry, token, msg, timestamp, scheme) =
+        <(Bytes, Bytes, Bytes, U256, U256)>::abi_decode_sequence(data)?;
+
+    let scheme = scheme.try_into()?;
+    let (_, token) = open_with(scheme, KEYED_TOKEN, &token)?;
+    let (key_id, token) = from_bytes::<KeyedToken>(token)?;
+
+    if verify_signature_by_key_id(
+        &registry.to_vec(),
+        &key_id.to_vec(),
+        token.0.clone(),
+        &token_randomizer(scheme, &token),
+        &msg.to_vec(),
+        timestamp.try_into()?,
+        scheme,
+    )? {
+        return Ok(valid_token(&nullifier(&msg.to_vec(), &token.0)));
+    }
+
+    Ok(vec![0; 64])
+}
+
+/// Verifies that at least `k` distinct registrars of an election vouch for a voter.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(public_keys_pem, k, bundle, msg, election_id,
+///   scheme)`, where `public_keys_pem` lists the registrar keys of the election, `bundle` is
+///   from `encode_eligibility_bundle` and `scheme` is the blind signature mode of the
+///   election.
+///
+/// # Returns
+///
+/// Returns `abi.encode(valid, nullifier)`, as `verify` does. The nullifier is
+/// `eligibility_nullifier(msg)`, the same whichever registrars signed.
+#[allow(clippy::ptr_arg)]
+pub fn verify_eligibility(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (public_keys_pem, k, bundle, msg, election_id, scheme) =
+        <(Vec<String>, U256, Bytes, Bytes, B256, U256)>::abi_decode_sequence(data)?;
+
+    let public_keys = public_keys_pem
+        .iter()
+        .map(|pem| BlindPublic::from_pem(pem))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if is_eligible(
+        &public_keys,
+        k.try_into()?,
+        &bundle,
+        &msg,
+        &election_id,
+        scheme.try_into()?,
+    )? {
+        return Ok(valid_token(&eligibility_nullifier(&msg.to_vec())));
+    }
+
+    Ok(vec![0; 64])
+}
+
+/// Decrypts the final election results.
+///
+/// No count can exceed the number of ballots in the accumulator times `max_score`, which
+/// bounds the baby-step giant-step search, so tallies in the millions decrypt in well under
+/// a second per candidate. Accumulators of another backend are decrypted by it, see
+/// `backend::Backend`.
+///
+/// # Arguments
+///
+/// * `secret_key` - Secret key of the election authority.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+/// * `max_score` - Highest value a single ballot adds to an entry: 1 for single-choice,
+///   multi-choice and ranked ballots, the highest score for score ballots.
+/// * `baby_steps` - Baby steps the discrete log solver stores, see
+///   `DiscreteLog::with_baby_steps`. `None` picks about `√(ballots * max_score)`, up to
+///   `MAX_BABY_STEPS`.
+///
+/// # Returns
+///
+/// Returns a `Result` containing a vector of vote counts per option.
+#[allow(clippy::ptr_arg)]
+pub fn decrypt_result(
+    secret_key: &Vec<u8>,
+    raw_result: &Vec<u8>,
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, Error> {
+    backend::decrypt_result(secret_key, raw_result, max_score, baby_steps)
+}
+
+/// Recovers the counts of decrypted accumulator entries, solving the entries in parallel.
+///
+/// No entry can exceed `ballots * max_score`, which bounds the discrete logs.
+pub(crate) fn solve_counts(
+    elements: &[RistrettoPoint],
+    ballots: u64,
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, Error> {
+    let bound = ballots
+        .checked_mul(max_score)
+        .ok_or_else(|| Error::Arithmetic("Tally bound overflows".into()))?;
+    let solver = match baby_steps {
+        Some(baby_steps) => DiscreteLog::with_baby_steps(bound, baby_steps),
+        None => DiscreteLog::new(bound),
+    };
+
+    elements
+        .par_iter()
+        .map(|element| {
+            solver.solve(element).ok_or_else(|| {
+                Error::Arithmetic("Count exceeds the ballots of the accumulator".into())
+            })
+        })
+        .collect()
+}
+
+/// Transcript label shared by tally decryption proofs and their verification.
+const TALLY_TRANSCRIPT_LABEL: &[u8] = b"tally_decryption";
+
+/// Decrypts the final election results and proves the decryption is correct.
+///
+/// For every candidate, a Chaum-Pedersen proof shows that the published count is the
+/// decryption of the accumulator under the election public key, without revealing
+/// the secret key.
+///
+/// # Arguments
+///
+/// * `secret_key` - Secret key of the election authority.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+/// * `max_score` - Highest value a single ballot adds to an entry: 1 for single-choice,
+///   multi-choice and ranked ballots, the highest score for score ballots.
+/// * `baby_steps` - Baby steps of the discrete log solver, as in `decrypt_result`.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the vote counts per option and the serialized proof.
+#[allow(clippy::ptr_arg)]
+pub fn decrypt_result_with_proof(
+    secret_key: &Vec<u8>,
+    raw_result: &Vec<u8>,
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<(Vec<u64>, Vec<u8>), Error> {
+    let rng = &mut thread_rng();
+    let (_, (ballots, result)) = decode_acc(raw_result)?;
+
+    let keys = SecretKey::<Ristretto>::from_bytes(secret_key)
+        .map(Keypair::from)
+        .ok_or_else(|| Error::Key("Invalid secret key".into()))?;
+
+    let mut elements = Vec::with_capacity(result.len());
+    let mut proof = Vec::with_capacity(result.len());
+
+    for choice in result {
+        let mut transcript = Transcript::new(TALLY_TRANSCRIPT_LABEL);
+        let (decryption, choice_proof) =
+            VerifiableDecryption::new(choice, &keys, &mut transcript, rng);
+
+        elements.push(decryption.decrypt_to_element(choice));
+        proof.push((decryption, choice_proof));
+    }
+
+    let counts = solve_counts(&elements, ballots, max_score, baby_steps)?;
+
+    Ok((counts, to_allocvec(&proof)?))
+}
+
+/// Verifies that the published counts are the correct decryption of the accumulator.
+///
+/// # Arguments
+///
+/// * `public_key` - Public key of the election authority.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+/// * `counts` - Published vote counts per option.
+/// * `proof` - Proof returned by `decrypt_result_with_proof`.
+///
+/// # Returns
+///
+/// Returns `true` if the proof is valid for every option, `false` otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_tally_proof(
+    public_key: &Vec<u8>,
+    raw_result: &Vec<u8>,
+    counts: &Vec<u64>,
+    proof: &Vec<u8>,
+) -> Result<bool, Error> {
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key)?;
+    let (_, (_, result)) = decode_acc(raw_result)?;
+    let proof = from_bytes::<Vec<ProvenDecryption>>(proof)?;
+
+    if result.len() != counts.len() || result.len() != proof.len() {
+        return Ok(false);
+    }
+
+    for ((choice, count), (decryption, choice_proof)) in result.iter().zip(counts).zip(proof) {
+        let mut transcript = Transcript::new(TALLY_TRANSCRIPT_LABEL);
+
+        let decryption =
+            match decryption.verify(*choice, &public_key, &choice_proof, &mut transcript) {
+                Ok(res) => res,
+                Err(_) => return Ok(false),
+            };
+
+        let expected = Ristretto::vartime_mul_generator(&(*count).into());
+
+        if decryption.decrypt_to_element(*choice) != expected {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Verifies a published tally against the accumulator.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(public_key, accumulator, counts, proof)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise. Inputs that fail
+/// to decode return an error instead.
+#[allow(clippy::ptr_arg)]
+pub fn verify_tally(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (public_key, acc, counts, proof) =
+        <(Bytes, Bytes, Vec<U256>, Bytes)>::abi_decode_sequence(data)?;
+
+    let mut output = vec![0; 32];
+
+    let counts = match counts
+        .iter()
+        .map(|count| count.try_into())
+        .collect::<Result<Vec<u64>, _>>()
+    {
+        Ok(res) => res,
+        Err(_) => return Ok(output),
+    };
+
+    if verify_tally_proof(
+        &public_key.to_vec(),
+        &acc.to_vec(),
+        &counts,
+        &proof.to_vec(),
+    )? {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Computes a trustee's partial decryption of the final accumulator.
+///
+/// Each candidate gets a decryption share together with a Chaum-Pedersen proof that it
+/// was computed with the trustee's key share, so the secret key is never reconstructed.
+///
+/// # Arguments
+///
+/// * `key_share` - Key share of the trustee, as returned by `dkg::insert_secret_shares`.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+///
+/// # Returns
+///
+/// Returns the serialized partial decryption as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn decrypt_share(key_share: &Vec<u8>, raw_result: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (_, (_, result)) = decode_acc(raw_result)?;
+
+    partial_decryption(key_share, &result)
+}
+
+/// Decrypts every ciphertext with a trustee's key share, proving each decryption.
+pub(crate) fn partial_decryption(
+    key_share: &[u8],
+    ciphertexts: &[Ciphertext<Ristretto>],
+) -> Result<Vec<u8>, Error> {
+    let rng = &mut thread_rng();
+    let participant = from_bytes::<ActiveParticipant<Ristretto>>(key_share)?;
+
+    let shares = ciphertexts
+        .iter()
+        .map(|ciphertext| participant.decrypt_share(*ciphertext, rng))
+        .collect::<Vec<_>>();
+
+    Ok(to_allocvec(&shares)?)
+}
+
+/// Checks a partial decryption against the accumulator and returns the verified shares.
+fn verify_share(
+    key_set: &PublicKeySet<Ristretto>,
+    result: &[Ciphertext<Ristretto>],
+    index: usize,
+    share: &[u8],
+) -> Option<Vec<VerifiableDecryption<Ristretto>>> {
+    if index >= key_set.params().shares {
+        return None;
+    }
+
+    let share = from_bytes::<Vec<ProvenDecryption>>(share).ok()?;
+
+    if share.len() != result.len() {
+        return None;
+    }
+
+    share
+        .into_iter()
+        .zip(result)
+        .map(|((candidate, proof), choice)| {
+            key_set.verify_share(candidate, *choice, index, &proof).ok()
+        })
+        .collect()
+}
+
+/// Verifies a trustee's partial decryption of the final accumulator.
+///
+/// # Arguments
+///
+/// * `key_set` - Public key set of the election, as returned by `dkg::derive_key_set`.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+/// * `index` - 0-based index of the trustee that produced the share.
+/// * `share` - Partial decryption returned by `decrypt_share`.
+///
+/// # Returns
+///
+/// Returns `true` if every proof in the share is valid, `false` otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_decryption_share(
+    key_set: &Vec<u8>,
+    raw_result: &Vec<u8>,
+    index: usize,
+    share: &Vec<u8>,
+) -> Result<bool, Error> {
+    let key_set = from_bytes::<PublicKeySet<Ristretto>>(key_set)?;
+    let (_, (_, result)) = decode_acc(raw_result)?;
+
+    Ok(verify_share(&key_set, &result, index, share).is_some())
+}
+
+/// Combines the partial decryptions of `k` trustees into the final election results.
+///
+/// Every share is verified before it is used. If any of them is invalid the whole
+/// operation fails, naming the offending trustee.
+///
+/// # Arguments
+///
+/// * `key_set` - Public key set of the election, as returned by `dkg::derive_key_set`.
+/// * `raw_result` - The final accumulator containing aggregated votes.
+/// * `shares` - Pairs of `(trustee_index, share)` as returned by `decrypt_share`.
+/// * `max_score` - Highest value a single ballot adds to an entry: 1 for single-choice,
+///   multi-choice and ranked ballots, the highest score for score ballots.
+/// * `baby_steps` - Baby steps of the discrete log solver, as in `decrypt_result`.
+///
+/// # Returns
+///
+/// Returns a `Result` containing a vector of vote counts per option.
+#[allow(clippy::ptr_arg)]
+pub fn combine_decryption_shares(
+    key_set: &Vec<u8>,
+    raw_result: &Vec<u8>,
+    shares: &Vec<(usize, Vec<u8>)>,
+    max_score: u64,
+    baby_steps: Option<u64>,
+) -> Result<Vec<u64>, Error> {
+    let key_set = from_bytes::<PublicKeySet<Ristretto>>(key_set)?;
+    let (_, (ballots, result)) = decode_acc(raw_result)?;
+
+    let elements = combine_shares(&key_set, &result, shares)?;
+
+    solve_counts(&elements, ballots, max_score, baby_steps)
+}
+
+/// Verifies the partial decryptions of `result` and combines them into the decrypted group
+/// elements.
+pub(crate) fn combine_shares(
+    key_set: &PublicKeySet<Ristretto>,
+    result: &[Ciphertext<Ristretto>],
+    shares: &[(usize, Vec<u8>)],
+) -> Result<Vec<RistrettoPoint>, Error> {
+    let mut verified = Vec::with_capacity(shares.len());
+
+    for (index, share) in shares {
+        if verified.iter().any(|(i, _)| i == index) {
+            return Err(Error::InvalidInput(format!(
+                "Duplicate decryption share from trustee {}",
+                index
+            )));
+        }
+
+        match verify_share(key_set, result, *index, share) {
+            Some(decryptions) => verified.push((*index, decryptions)),
+            None => {
+                return Err(Error::Proof(format!(
+                    "Invalid decryption share from trustee {}",
+                    index
+                )));
+            }
+        }
+    }
+
+    if verified.len() < key_set.params().threshold {
+        return Err(Error::InvalidInput("Not enough decryption shares".into()));
+    }
+
+    result
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| {
+            let decryption = key_set
+                .params()
+                .combine_shares(verified.iter().map(|(index, d)| (*index, d[i])))
+                .ok_or_else(|| Error::InvalidInput("Not enough decryption shares".into()))?;
+
+            Ok(decryption.decrypt_to_element(*choice))
+        })
+        .collect()
 }
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use cupcake::integer_arith::ArithUtils;
+use cupcake::integer_arith::scalar::Scalar;
+use cupcake::randutils::{sample_gaussian_poly, sample_ternary_poly, sample_uniform_poly};
+use cupcake::rqpoly::{FiniteRingElt, RqPoly, RqPolyContext};
+use cupcake::traits::{
+    AdditiveHomomorphicScheme, FastPolyMultiply, NTT, PKEncryption, Serializable,
+};
+use cupcake::{FV, FVCiphertext};
+use postcard::{from_bytes, to_allocvec};
+
+use crate::Error;
+use crate::backend::Backend;
+use crate::envelope::BFV;
+
+/// Degree of the ring `Z_q[x] / (x^N + 1)` of `cupcake::default`.
+const N: usize = 2048;
+
+/// Ciphertext modulus of `cupcake::default`, a 54-bit prime.
+const Q: u64 = 18014398492704769;
+
+/// Plaintext modulus, which bounds the counts of a tally.
+const T: u32 = 1 << 20;
+
+/// Length of a polynomial encoded by Cupcake: its NTT flag and 8 bytes per coefficient.
+const POLY_LEN: usize = 1 + 8 * N;
+
+/// Ciphertext of Cupcake together with the number of options it encrypts a count for.
+type Encryption = (usize, FVCiphertext<Scalar>);
+
+/// Fan-Vercauteren lattice encryption from Cupcake, with every count of a ballot packed in
+/// a coefficient of a single ciphertext.
+///
+/// Ballots are encrypted and added by Cupcake. Its secret keys cannot be encoded, so key
+/// pairs are sampled with its samplers the way `KeyGeneration::generate_keypair` does, and
+/// tallies are decrypted with 128-bit arithmetic, since the rounding of Cupcake overflows
+/// for plaintext moduli above `2^10` and counts need more.
+///
+/// BFV ballots carry no proof of validity, nor any binding to their context: a voter could
+/// encrypt several votes, or a negative one. `verify` therefore refuses every ballot until
+/// such a proof exists, and elections that verify their ballots must use `ElGamal`.
+///
+/// Cupcake draws its randomness from rand 0.5, which has no entropy source under
+/// wasm-bindgen, so the backend is not built for `wasm32` and its scheme is unsupported there.
+pub struct Bfv;
+
+/// Parameters of `cupcake::default` with the plaintext modulus `T`, which is above the
+/// `2^10` that `cupcake::default_with_plaintext_mod` accepts.
+fn scheme() -> FV<Scalar> {
+    FV::new_with_ptxt_mod(N, &Scalar::new_modulus(T as u64), &Scalar::new_modulus(Q))
+}
+
+fn context() -> Arc<RqPolyContext<Scalar>> {
+    Arc::new(RqPolyContext::new(N, &Scalar::new_modulus(Q)))
+}
+
+/// Checks that a polynomial has `N` coefficients modulo `Q` and the expected form.
+fn check_poly(poly: &RqPoly<Scalar>, is_ntt_form: bool) -> bool {
+    poly.is_ntt_form == is_ntt_form
+        && poly.coeffs.len() == N
+        && poly.coeffs.iter().all(|coeff| coeff.rep() < Q)
+}
+
+/// Decodes a pair of polynomials encoded by Cupcake, both in the given form.
+fn decode_pair(bytes: &[u8], is_ntt_form: bool) -> Option<FVCiphertext<Scalar>> {
+    if bytes.len() != 2 * POLY_LEN {
+        return None;
+    }
+
+    let pair = scheme().from_bytes(&bytes.to_vec());
+
+    (check_poly(&pair.0, is_ntt_form) && check_poly(&pair.1, is_ntt_form)).then_some(pair)
+}
+
+/// Decodes a public key `(a, a * s + e)`, which Cupcake keeps in NTT form.
+fn decode_public_key(public_key: &[u8]) -> Result<FVCiphertext<Scalar>, Error> {
+    decode_pair(public_key, true).ok_or_else(|| Error::Key("Invalid BFV public key".into()))
+}
+
+/// Decodes a ternary secret key in NTT form.
+fn decode_secret_key(secret_key: &[u8]) -> Result<RqPoly<Scalar>, Error> {
+    let invalid = || Error::Key("Invalid BFV secret key".into());
+
+    if secret_key.len() != POLY_LEN {
+        return Err(invalid());
+    }
+
+    let decoded = RqPoly::<Scalar>::from_bytes(&secret_key.to_vec());
+    if !check_poly(&decoded, true) {
+        return Err(invalid());
+    }
+
+    let mut secret_key = RqPoly::new(context());
+    secret_key.coeffs = decoded.coeffs;
+    secret_key.is_ntt_form = true;
+
+    Ok(secret_key)
+}
+
+fn encode_encryption((options_count, ciphertext): &Encryption) -> Result<Vec<u8>, Error> {
+    Ok(to_allocvec(&(
+        *options_count as u64,
+        ciphertext.to_bytes(),
+    ))?)
+}
+
+fn decode_encryption(encryption: &[u8]) -> Result<Encryption, Error> {
+    let (options_count, ciphertext) = from_bytes::<(u64, Vec<u8>)>(encryption)?;
+
+    match decode_pair(&ciphertext, false) {
+        Some(ciphertext) if options_count <= N as u64 => Ok((options_count as usize, ciphertext)),
+        _ => Err(Error::Decode("Malformed BFV ciphertext".into())),
+    }
+}
+
+impl Backend for Bfv {
+    const SCHEME: u8 = BFV;
+
+    type Ballot = Encryption;
+    type Tally = Encryption;
+
+    fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let scheme = scheme();
+        let context = context();
+
+        let mut secret_key = sample_ternary_poly(context.clone());
+        secret_key.forward_transform();
+
+        let mut a = sample_uniform_poly(context.clone());
+        let mut b = a.multiply_fast(&secret_key);
+        b.add_inplace(&sample_gaussian_poly(context, scheme.stdev));
+        a.forward_transform();
+        b.forward_transform();
+
+        Ok(((a, b).to_bytes(), secret_key.to_bytes()))
+    }
+
+    fn encrypt(
+        public_key: &[u8],
+        choice: usize,
+        options_count: usize,
+        _context: &[u8],
+    ) -> Result<Self::Ballot, Error> {
+        if options_count == 0 || options_count > N {
+            return Err(Error::InvalidInput("Invalid number of options".into()));
+        }
+        if choice >= options_count {
+            return Err(Error::InvalidInput("Choice out of bounds".into()));
+        }
+
+        let public_key = decode_public_key(public_key)?;
+        let plaintext = (0..options_count)
+            .map(|option| Scalar::from(u64::from(option == choice)))
+            .collect::<Vec<_>>();
+
+        Ok((options_count, scheme().encrypt(&plaintext, &public_key)))
+    }
+
+    fn verify(
+        _public_key: &[u8],
+        _ballot: &Self::Ballot,
+        _options_count: usize,
+        _context: &[u8],
+    ) -> Result<bool, Error> {
+        Err(Error::Proof(
+            "BFV ballots have no validity proof to verify".into(),
+        ))
+    }
+
+    fn zero(options_count: usize) -> Result<Self::Tally, Error> {
+        if options_count == 0 || options_count > N {
+            return Err(Error::InvalidInput("Invalid number of options".into()));
+        }
+
+        let context = context();
+
+        Ok((
+            options_count,
+            (RqPoly::new(context.clone()), RqPoly::new(context)),
+        ))
+    }
+
+    fn add(tally: &mut Self::Tally, ballot: &Self::Ballot) -> Result<(), Error> {
+        if tally.0 != ballot.0 {
+            return Err(Error::InvalidInput(
+                "Ballot does not match the accumulator".into(),
+            ));
+        }
+
+        scheme().add_inplace(&mut tally.1, &ballot.1);
+
+        Ok(())
+    }
+
+    fn options_count(tally: &Self::Tally) -> usize {
+        tally.0
+    }
+
+    fn decrypt(
+        secret_key: &[u8],
+        tally: &Self::Tally,
+        ballots: u64,
+        max_score: u64,
+        _baby_steps: Option<u64>,
+    ) -> Result<Vec<u64>, Error> {
+        let bound = ballots
+            .checked_mul(max_score)
+            .filter(|&bound| bound < T as u64)
+            .ok_or_else(|| Error::Arithmetic("Tally exceeds the plaintext modulus".into()))?;
+
+        let secret_key = decode_secret_key(secret_key)?;
+        let (options_count, (c0, c1)) = tally;
+        let mut noisy = c1.clone();
+        noisy.sub_inplace(&c0.multiply_fast(&secret_key));
+
+        noisy
+            .coeffs
+            .iter()
+            .take(*options_count)
+            .map(|coeff| {
+                // Scale down by q / t, rounding away the noise
+                let count = ((coeff.rep() as u128 * T as u128 + Q as u128 / 2) / Q as u128) as u64
+                    % T as u64;
+
+                (count <= bound).then_some(count).ok_or_else(|| {
+                    Error::Arithmetic("Count exceeds the ballots of the accumulator".into())
+                })
+            })
+            .collect()
+    }
+
+    fn encode_ballot(ballot: &Self::Ballot) -> Result<Vec<u8>, Error> {
+        encode_encryption(ballot)
+    }
+
+    fn decode_ballot(ballot: &[u8]) -> Result<Self::Ballot, Error> {
+        decode_encryption(ballot)
+    }
+
+    fn encode_tally(tally: &Self::Tally) -> Result<Vec<u8>, Error> {
+        encode_encryption(tally)
+    }
+
+    fn decode_tally(tally: &[u8]) -> Result<Self::Tally, Error> {
+        decode_encryption(tally)
+    }
+}
@@ -1,8 +1,8 @@
-use alloy_primitives::{Bytes, U256};
+use alloy_primitives::{B256, Bytes, U256};
 use alloy_sol_types::SolValue;
-use primitives::ballots::{
-    add_votes, decrypt_result, encrypt_vote, generate_acc, generate_elgamal_keypair, verify_vote,
-};
+use primitives::backend::{encrypt_backend_vote, generate_backend_acc, generate_backend_keypair};
+use primitives::ballots::{add_votes, add_votes_batch, ballot_context, decrypt_result, verify_vote};
+use primitives::envelope::{RISTRETTO_ELGAMAL, SECP256K1_ELGAMAL};
 use rand_legacy::rngs::StdRng;
 use rand_legacy::Rng;
 use rand_legacy::SeedableRng;
@@ -15,6 +15,7 @@ use std::time::Instant;
 struct Config {
     ballots: Vec<usize>,
     candidates: Vec<usize>,
+    schemes: Vec<u8>,
     runs: usize,
     output: String,
     seed: u64,
@@ -25,6 +26,7 @@ impl Default for Config {
         Self {
             ballots: vec![100, 500, 1000, 2000],
             candidates: vec![3, 5, 10],
+            schemes: vec![RISTRETTO_ELGAMAL],
             runs: 5,
             output: "benchmark_results.csv".into(),
             seed: 42,
@@ -38,6 +40,22 @@ fn parse_csv_usize(s: &str) -> Vec<usize> {
         .collect()
 }
 
+fn parse_scheme(s: &str) -> u8 {
+    match s {
+        "ristretto" => RISTRETTO_ELGAMAL,
+        "secp256k1" => SECP256K1_ELGAMAL,
+        other => panic!("Unknown scheme: {}", other),
+    }
+}
+
+fn scheme_name(scheme: u8) -> &'static str {
+    match scheme {
+        RISTRETTO_ELGAMAL => "ristretto",
+        SECP256K1_ELGAMAL => "secp256k1",
+        _ => "unknown",
+    }
+}
+
 fn parse_args() -> Config {
     let args: Vec<String> = std::env::args().collect();
     let mut config = Config::default();
@@ -52,6 +70,13 @@ fn parse_args() -> Config {
                 i += 1;
                 config.candidates = parse_csv_usize(&args[i]);
             }
+            "--schemes" => {
+                i += 1;
+                config.schemes = args[i]
+                    .split(',')
+                    .map(|v| parse_scheme(v.trim()))
+                    .collect();
+            }
             "--runs" => {
                 i += 1;
                 config.runs = args[i].parse().expect("invalid runs");
@@ -135,12 +160,20 @@ struct RunResult {
     correct: bool,
 }
 
-fn run_benchmark(ballots: usize, candidates: usize, zkp_enabled: bool, seed: u64) -> RunResult {
-    let (pk, sk) = generate_elgamal_keypair();
-    let encoded_count = U256::from(candidates).abi_encode();
-    let mut acc = generate_acc(&encoded_count);
+fn run_benchmark(
+    scheme: u8,
+    ballots: usize,
+    candidates: usize,
+    zkp_enabled: bool,
+    seed: u64,
+) -> RunResult {
+    let (pk, sk) = generate_backend_keypair(scheme).expect("keygen failed");
+    let encoded_count =
+        (U256::from(candidates), B256::ZERO, U256::from(scheme)).abi_encode_sequence();
+    let mut acc = generate_backend_acc(&encoded_count).unwrap();
     let mut rng = StdRng::seed_from_u64(seed);
     let mut expected = vec![0u64; candidates];
+    let context = ballot_context(&vec![0; 32], &vec![0; 20], &vec![]).expect("invalid context");
 
     let votes: Vec<usize> = (0..ballots)
         .map(|_| {
@@ -154,7 +187,9 @@ fn run_benchmark(ballots: usize, candidates: usize, zkp_enabled: bool, seed: u64
     let (encrypted, encryption) = measure(|| {
         votes
             .iter()
-            .map(|&c| encrypt_vote(&pk, c, candidates).expect("encrypt failed"))
+            .map(|&c| {
+                encrypt_backend_vote(scheme, &pk, c, candidates, &context).expect("encrypt failed")
+            })
             .collect::<Vec<Vec<u8>>>()
     });
 
@@ -164,11 +199,13 @@ fn run_benchmark(ballots: usize, candidates: usize, zkp_enabled: bool, seed: u64
             for ballot in &encrypted {
                 let input = (
                     U256::from(candidates),
+                    Bytes::from(context.clone()),
                     Bytes::from(pk.clone()),
                     Bytes::from(ballot.clone()),
+                    U256::from(scheme),
                 )
                     .abi_encode_sequence();
-                verify_vote(&input);
+                verify_vote(&input).unwrap();
             }
         });
         Some(m)
@@ -176,18 +213,36 @@ fn run_benchmark(ballots: usize, candidates: usize, zkp_enabled: bool, seed: u64
         None
     };
 
-    // Phase 3: Homomorphic addition
+    // Phase 3: Homomorphic addition, batched for Ristretto and one ballot at a time otherwise
     let (_, addition) = measure(|| {
-        for ballot in &encrypted {
-            let input =
-                (Bytes::from(acc.clone()), Bytes::from(ballot.clone())).abi_encode_sequence();
-            acc = add_votes(&input);
+        if scheme != RISTRETTO_ELGAMAL {
+            for ballot in &encrypted {
+                let input = (Bytes::from(acc.clone()), Bytes::from(ballot.clone()))
+                    .abi_encode_sequence();
+                acc = add_votes(&input).unwrap();
+            }
+            return;
         }
+
+        let input = (
+            Bytes::from(acc.clone()),
+            encrypted.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+            false,
+            U256::from(1),
+            U256::from(1),
+            Vec::<Bytes>::new(),
+            Bytes::new(),
+        )
+            .abi_encode_sequence();
+        let output = add_votes_batch(&input).unwrap();
+        let (new_acc, rejected) = <(Bytes, Vec<U256>)>::abi_decode_sequence(&output).unwrap();
+        assert!(rejected.is_empty(), "ballots rejected: {:?}", rejected);
+        acc = new_acc.to_vec();
     });
 
     // Phase 4: Decryption
     let (tallies, decryption) = measure(|| {
-        decrypt_result(&sk, &acc, ballots as u64).expect("decrypt failed")
+        decrypt_result(&sk, &acc, 1, None).expect("decrypt failed")
     });
 
     let correct = tallies.iter().zip(expected.iter()).all(|(a, b)| *a == *b);
@@ -217,7 +272,7 @@ fn stddev(values: &[f64]) -> f64 {
 fn write_csv_header(file: &mut fs::File) {
     writeln!(
         file,
-        "ballots,candidates,zkp_enabled,phase,runs,mean_wall_s,stddev_wall_s,mean_cpu_user_s,stddev_cpu_user_s,mean_rss_delta_kb,stddev_rss_delta_kb"
+        "scheme,ballots,candidates,zkp_enabled,phase,runs,mean_wall_s,stddev_wall_s,mean_cpu_user_s,stddev_cpu_user_s,mean_rss_delta_kb,stddev_rss_delta_kb"
     )
     .expect("failed to write header");
     file.flush().expect("failed to flush");
@@ -225,11 +280,11 @@ fn write_csv_header(file: &mut fs::File) {
 
 fn write_csv_row(
     file: &mut fs::File,
+    scheme: u8,
     ballots: usize,
     candidates: usize,
     zkp_enabled: bool,
     phase: &str,
-    runs: usize,
     measurements: &[&PhaseMeasurement],
 ) {
     let walls: Vec<f64> = measurements.iter().map(|m| m.wall_secs).collect();
@@ -237,12 +292,13 @@ fn write_csv_row(
     let rsss: Vec<f64> = measurements.iter().map(|m| m.rss_delta_kb as f64).collect();
 
     let line = format!(
-        "{},{},{},{},{},{:.6},{:.6},{:.6},{:.6},{:.1},{:.1}",
+        "{},{},{},{},{},{},{:.6},{:.6},{:.6},{:.6},{:.1},{:.1}",
+        scheme_name(scheme),
         ballots,
         candidates,
         zkp_enabled,
         phase,
-        runs,
+        measurements.len(),
         mean(&walls),
         stddev(&walls),
         mean(&cpus),
@@ -268,60 +324,63 @@ fn main() {
         .expect("failed to open output file");
     write_csv_header(&mut file);
 
-    for &ballots in &config.ballots {
-        for &candidates in &config.candidates {
-            for zkp_enabled in [false, true] {
-                println!(
-                    "\n=== ballots={} candidates={} zkp={} ({} runs) ===",
-                    ballots, candidates, zkp_enabled, config.runs
-                );
-
-                let mut results: Vec<RunResult> = Vec::with_capacity(config.runs);
-                for run in 0..config.runs {
-                    let seed = config.seed.wrapping_add(run as u64);
-                    println!("  run {}/{}", run + 1, config.runs);
-                    let r = run_benchmark(ballots, candidates, zkp_enabled, seed);
-                    if !r.correct {
-                        eprintln!("  WARNING: tally mismatch on run {}", run + 1);
+    for &scheme in &config.schemes {
+        for &ballots in &config.ballots {
+            for &candidates in &config.candidates {
+                for zkp_enabled in [false, true] {
+                    println!(
+                        "\n=== scheme={} ballots={} candidates={} zkp={} ({} runs) ===",
+                        scheme_name(scheme), ballots, candidates, zkp_enabled, config.runs
+                    );
+
+                    let mut results: Vec<RunResult> = Vec::with_capacity(config.runs);
+                    for run in 0..config.runs {
+                        let seed = config.seed.wrapping_add(run as u64);
+                        println!("  run {}/{}", run + 1, config.runs);
+                        let r = run_benchmark(scheme, ballots, candidates, zkp_enabled, seed);
+                        if !r.correct {
+                            eprintln!("  WARNING: tally mismatch on run {}", run + 1);
+                        }
+                        results.push(r);
                     }
-                    results.push(r);
-                }
 
-                // Write stats per phase
-                let phases: Vec<(&str, Vec<&PhaseMeasurement>)> = vec![
-                    ("encryption", results.iter().map(|r| &r.encryption).collect()),
-                    ("addition", results.iter().map(|r| &r.addition).collect()),
-                    ("decryption", results.iter().map(|r| &r.decryption).collect()),
-                ];
-
-                for (phase, measurements) in &phases {
-                    write_csv_row(
-                        &mut file,
-                        ballots,
-                        candidates,
-                        zkp_enabled,
-                        phase,
-                        config.runs,
-                        measurements,
-                    );
-                }
+                    // Write stats per phase
+                    let phases: Vec<(&str, Vec<&PhaseMeasurement>)> = vec![
+                        ("encryption", results.iter().map(|r| &r.encryption).collect()),
+                        ("addition", results.iter().map(|r| &r.addition).collect()),
+                        ("decryption", results.iter().map(|r| &r.decryption).collect()),
+                    ];
+
+                    for (phase, measurements) in &phases {
+                        write_csv_row(
+                            &mut file,
+                            scheme,
+                            ballots,
+                            candidates,
+                            zkp_enabled,
+                            phase,
+                            measurements,
+                        );
+                    }
 
-                // ZKP phase only when enabled
-                if zkp_enabled {
-                    let zkp_measurements: Vec<&PhaseMeasurement> =
-                        results.iter().filter_map(|r| r.zkp.as_ref()).collect();
-                    write_csv_row(
-                        &mut file,
-                        ballots,
-                        candidates,
-                        zkp_enabled,
-                        "zkp_verification",
-                        config.runs,
-                        &zkp_measurements,
-                    );
+                    // ZKP phase only when enabled
+                    if zkp_enabled {
+                        let zkp_measurements: Vec<&PhaseMeasurement> =
+                            results.iter().filter_map(|r| r.zkp.as_ref()).collect();
+                        write_csv_row(
+                            &mut file,
+                            scheme,
+                            ballots,
+                            candidates,
+                            zkp_enabled,
+                            "zkp_verification",
+                            &zkp_measurements,
+                        );
+                    }
                 }
             }
         }
+
     }
 
     println!("\nResults written to {}", config.output);
@@ -1,8 +1,49 @@
+use alloy_primitives::{B256, Bytes, U256, keccak256};
+use alloy_sol_types::SolValue;
+use blind_rsa_signatures::pbrsa::{
+    PartiallyBlindKeyPair, PartiallyBlindPublicKey, PartiallyBlindSecretKey,
+};
 use blind_rsa_signatures::{
-    BlindMessage, BlindSignature, BlindingResult, DefaultRng, Deterministic, KeyPair, PSS,
-    PublicKey, Secret, SecretKey, Sha384, Signature,
+    BlindMessage, BlindSignature, BlindingResult, DefaultRng, Deterministic, KeyPair,
+    MessagePrepare, MessageRandomizer, PSS, PublicKey, Randomized, Secret, SecretKey, Sha384,
+    Signature,
+};
+use merlin::Transcript;
+use num_bigint_dig::prime::probably_prime;
+use num_bigint_dig::{BigInt, BigUint, ExtendedGcd, ModInverse, RandBigInt, Sign};
+use postcard::{from_bytes, to_allocvec};
+use rand_legacy::thread_rng;
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePublicKey};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+use crate::Error;
+use crate::ballots::Token;
+use crate::envelope::{
+    ELIGIBILITY_BUNDLE, RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC, open_with, seal_with,
 };
-use std::error::Error;
+
+/// RSABSSA-SHA384-PSS-Randomized public key, which also verifies deterministic signatures
+/// through `verify_token`.
+pub(crate) type BlindPublic = PublicKey<Sha384, PSS, Randomized>;
+
+/// RSAPBSSA-SHA384-PSS-Randomized keys, the partially blind variant recommended by the
+/// CFRG draft.
+pub(crate) type PartiallyBlindPublic = PartiallyBlindPublicKey<Sha384, PSS, Randomized>;
+type PartiallyBlindSecret = PartiallyBlindSecretKey<Sha384, PSS, Randomized>;
+
+/// Domain separator of token nullifiers.
+const NULLIFIER_LABEL: &[u8] = b"token_nullifier";
+
+/// Domain separator of eligibility nullifiers.
+const ELIGIBILITY_LABEL: &[u8] = b"eligibility_nullifier";
+
+/// Entry of a key registry, `(key_id, public_key, not_before, not_after, retired)`.
+type RegistryEntry = ([u8; 32], Vec<u8>, u64, u64, bool);
+
+/// Eligibility bundle: the index of the issuing registrar, in the list of registrars of the
+/// election, and the token it issued.
+type EligibilityBundle = Vec<(u16, Token)>;
 
 /// Structure to hold exported RSA key pair.
 pub struct ExportedKeyPair {
@@ -17,7 +58,7 @@ pub struct ExportedKeyPair {
 /// # Returns
 ///
 /// Returns an `ExportedKeyPair` with DER-encoded keys.
-pub fn generate_rsa_keypair() -> Result<ExportedKeyPair, Box<dyn Error>> {
+pub fn generate_rsa_keypair() -> Result<ExportedKeyPair, Error> {
     let keypair = KeyPair::<Sha384, PSS, Deterministic>::generate(&mut DefaultRng, 2048)?;
 
     Ok(ExportedKeyPair {
@@ -27,17 +68,23 @@ pub fn generate_rsa_keypair() -> Result<ExportedKeyPair, Box<dyn Error>> {
 }
 
 /// Deserializes a public key from DER format.
+#[allow(clippy::ptr_arg)]
 pub fn deserialize_pub(
     public_key: &Vec<u8>,
-) -> Result<PublicKey<Sha384, PSS, Deterministic>, blind_rsa_signatures::Error> {
-    PublicKey::<Sha384, PSS, Deterministic>::from_der(public_key)
+) -> Result<PublicKey<Sha384, PSS, Deterministic>, Error> {
+    Ok(PublicKey::<Sha384, PSS, Deterministic>::from_der(
+        public_key,
+    )?)
 }
 
 /// Deserializes a private key from DER format.
+#[allow(clippy::ptr_arg)]
 pub fn deserialize_priv(
     private_key: &Vec<u8>,
-) -> Result<SecretKey<Sha384, PSS, Deterministic>, blind_rsa_signatures::Error> {
-    SecretKey::<Sha384, PSS, Deterministic>::from_der(private_key)
+) -> Result<SecretKey<Sha384, PSS, Deterministic>, Error> {
+    Ok(SecretKey::<Sha384, PSS, Deterministic>::from_der(
+        private_key,
+    )?)
 }
 
 /// Structure to hold the result of blinding a message.
@@ -47,29 +94,84 @@ pub struct ExportedBlindingResult {
     pub msg_randomizer: Vec<u8>,
 }
 
+fn unsupported_mode(scheme: u8) -> Error {
+    Error::InvalidInput(format!("Unsupported blind signature mode {}", scheme))
+}
+
+/// Checks the message randomizer of a signature made in the mode of `scheme`: 32 bytes for
+/// RSABSSA-SHA384-PSS-Randomized, none for deterministic signatures.
+fn message_randomizer(
+    scheme: u8,
+    msg_randomizer: &[u8],
+) -> Result<Option<MessageRandomizer>, Error> {
+    match scheme {
+        RSA_BLIND_PSS => Ok(Some(MessageRandomizer::new(<[u8; 32]>::try_from(
+            msg_randomizer,
+        )?))),
+        RSA_BLIND_PSS_DETERMINISTIC if msg_randomizer.is_empty() => Ok(None),
+        RSA_BLIND_PSS_DETERMINISTIC => Err(Error::InvalidInput(
+            "Deterministic signatures have no message randomizer".into(),
+        )),
+        _ => Err(unsupported_mode(scheme)),
+    }
+}
+
+fn blind<M: MessagePrepare>(public_key: &[u8], msg: &[u8]) -> Result<BlindingResult, Error> {
+    Ok(PublicKey::<Sha384, PSS, M>::from_der(public_key)?.blind(&mut DefaultRng, msg)?)
+}
+
+fn finalize<M: MessagePrepare>(
+    public_key: &[u8],
+    blind_signature: &BlindSignature,
+    blinding_result: &BlindingResult,
+    msg: &[u8],
+) -> Result<Signature, Error> {
+    Ok(PublicKey::<Sha384, PSS, M>::from_der(public_key)?.finalize(
+        blind_signature,
+        blinding_result,
+        msg,
+    )?)
+}
+
 /// Creates a blind signature request.
 ///
 /// # Arguments
 ///
 /// * `public_key` - DER-encoded public key.
 /// * `msg` - Message to be blinded.
+/// * `scheme` - Blind signature mode of the election, `RSA_BLIND_PSS` or
+///   `RSA_BLIND_PSS_DETERMINISTIC`.
 ///
 /// # Returns
 ///
-/// Returns a tuple containing the blinded message and the secret (blinding factor).
+/// Returns the blinded message, the secret (blinding factor) and the message randomizer,
+/// empty in the deterministic mode.
+#[allow(clippy::ptr_arg)]
 pub fn create_request(
     public_key: &Vec<u8>,
     msg: &Vec<u8>,
-) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
-    let public_key = deserialize_pub(&public_key)?;
-
-    let res = public_key.blind(&mut DefaultRng, msg)?;
+    scheme: u8,
+) -> Result<ExportedBlindingResult, Error> {
+    let res = match scheme {
+        RSA_BLIND_PSS => blind::<Randomized>(public_key, msg)?,
+        RSA_BLIND_PSS_DETERMINISTIC => blind::<Deterministic>(public_key, msg)?,
+        _ => return Err(unsupported_mode(scheme)),
+    };
 
-    Ok((res.blind_message.0, res.secret.0))
+    Ok(ExportedBlindingResult {
+        blind_message: res.blind_message.0,
+        secret: res.secret.0,
+        msg_randomizer: res
+            .msg_randomizer
+            .map(|randomizer| randomizer.0.to_vec())
+            .unwrap_or_default(),
+    })
 }
 
 /// Signs a blinded message.
 ///
+/// Signing is the same in both blind signature modes.
+///
 /// # Arguments
 ///
 /// * `private_key` - DER-encoded private key.
@@ -78,10 +180,10 @@ pub fn create_request(
 /// # Returns
 ///
 /// Returns the blinded signature as `Vec<u8>`.
-pub fn sign(private_key: &Vec<u8>, blind_message: &Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
-    let private_key = deserialize_priv(&private_key)?;
+pub fn sign(private_key: &Vec<u8>, blind_message: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let private_key = deserialize_priv(private_key)?;
 
-    Ok(private_key.blind_sign(&blind_message)?.0)
+    Ok(private_key.blind_sign(blind_message)?.0)
 }
 
 /// Unblinds a signature.
@@ -91,30 +193,38 @@ pub fn sign(private_key: &Vec<u8>, blind_message: &Vec<u8>) -> Result<Vec<u8>, B
 /// * `public_key` - DER-encoded public key.
 /// * `msg` - Original message.
 /// * `secret` - Blinding factor.
+/// * `msg_randomizer` - Message randomizer of the request, empty in the deterministic mode.
 /// * `blind_sig` - Blinded signature.
+/// * `scheme` - Blind signature mode the request was created in.
 ///
 /// # Returns
 ///
 /// Returns the unblinded signature as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
 pub fn unblind(
     public_key: &Vec<u8>,
     msg: &Vec<u8>,
     secret: Vec<u8>,
+    msg_randomizer: &Vec<u8>,
     blind_sig: Vec<u8>,
-) -> Result<Vec<u8>, Box<dyn Error>> {
-    let public_key = deserialize_pub(&public_key)?;
-
+    scheme: u8,
+) -> Result<Vec<u8>, Error> {
     let blinding_result = BlindingResult {
         blind_message: BlindMessage(msg.clone()),
         secret: Secret(secret),
-        msg_randomizer: None,
+        msg_randomizer: message_randomizer(scheme, msg_randomizer)?,
     };
 
     let blind_signature = BlindSignature::new(blind_sig);
 
-    Ok(public_key
-        .finalize(&blind_signature, &blinding_result, &msg)?
-        .0)
+    let signature = match scheme {
+        RSA_BLIND_PSS => {
+            finalize::<Randomized>(public_key, &blind_signature, &blinding_result, msg)?
+        }
+        _ => finalize::<Deterministic>(public_key, &blind_signature, &blinding_result, msg)?,
+    };
+
+    Ok(signature.0)
 }
 
 /// Verifies a blind signature.
@@ -123,22 +233,1060 @@ pub fn unblind(
 ///
 /// * `public_key` - DER-encoded public key.
 /// * `signature_bytes` - The unblinded signature.
+/// * `msg_randomizer` - Message randomizer of the request, empty in the deterministic mode.
 /// * `msg` - The original message.
+/// * `scheme` - Blind signature mode the signature was issued in.
 ///
 /// # Returns
 ///
 /// Returns `true` if valid, `false` otherwise.
+#[allow(clippy::ptr_arg)]
 pub fn verify(
     public_key: &Vec<u8>,
     signature_bytes: Vec<u8>,
+    msg_randomizer: &Vec<u8>,
     msg: &Vec<u8>,
-) -> Result<bool, Box<dyn Error>> {
-    let public_key = deserialize_pub(&public_key)?;
+    scheme: u8,
+) -> Result<bool, Error> {
+    let public_key = BlindPublic::from_der(public_key)?;
 
+    verify_in_mode(&public_key, scheme, signature_bytes, msg_randomizer, msg)
+}
+
+fn verify_in_mode(
+    public_key: &BlindPublic,
+    scheme: u8,
+    signature_bytes: Vec<u8>,
+    msg_randomizer: &[u8],
+    msg: &[u8],
+) -> Result<bool, Error> {
     let signature = Signature::new(signature_bytes);
 
-    match public_key.verify(&signature, None, msg) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
+    let valid = match message_randomizer(scheme, msg_randomizer)? {
+        Some(msg_randomizer) => public_key.verify(&signature, Some(msg_randomizer), msg),
+        None => PublicKey::<Sha384, PSS, Deterministic>::new(public_key.as_ref().clone())
+            .verify(&signature, None, msg),
+    };
+
+    Ok(valid.is_ok())
+}
+
+/// Builds the `(signature, msg_randomizer)` payload of a token issued in the mode of
+/// `scheme`. Deterministic tokens have no randomizer and store zeros instead.
+pub(crate) fn token(scheme: u8, signature: &[u8], msg_randomizer: &[u8]) -> Result<Token, Error> {
+    let msg_randomizer = message_randomizer(scheme, msg_randomizer)?;
+
+    Ok((
+        signature.to_vec(),
+        msg_randomizer
+            .map(|randomizer| randomizer.0)
+            .unwrap_or_default(),
+    ))
+}
+
+/// Reads the message randomizer of a token issued in the mode of `scheme`, empty for
+/// deterministic tokens.
+pub(crate) fn token_randomizer(scheme: u8, (_, msg_randomizer): &Token) -> Vec<u8> {
+    match scheme {
+        RSA_BLIND_PSS_DETERMINISTIC => Vec::new(),
+        _ => msg_randomizer.to_vec(),
+    }
+}
+
+/// Verifies a token issued in the mode of `scheme`.
+pub(crate) fn verify_token(
+    public_key: &BlindPublic,
+    scheme: u8,
+    token: &Token,
+    msg: &[u8],
+) -> Result<bool, Error> {
+    verify_in_mode(
+        public_key,
+        scheme,
+        token.0.clone(),
+        &token_randomizer(scheme, token),
+        msg,
+    )
+}
+
+/// Derives the nullifier of a token from its unblinded message and signature.
+///
+/// The nullifier is `keccak256(abi.encode(keccak256("token_nullifier"), msg, signature))`.
+/// It does not depend on how the token is encoded, so a contract that stores the nullifiers
+/// of spent tokens rejects the same token presented twice.
+///
+/// # Arguments
+///
+/// * `msg` - The original message.
+/// * `signature` - The unblinded signature.
+///
+/// # Returns
+///
+/// Returns the 32-byte nullifier.
+#[allow(clippy::ptr_arg)]
+pub fn nullifier(msg: &Vec<u8>, signature: &Vec<u8>) -> Vec<u8> {
+    let encoded = (
+        keccak256(NULLIFIER_LABEL),
+        Bytes::from(msg.clone()),
+        Bytes::from(signature.clone()),
+    )
+        .abi_encode_sequence();
+
+    keccak256(encoded).to_vec()
+}
+
+/// Computes the ID of a signing key, the `keccak256` hash of its SPKI DER encoding.
+///
+/// # Arguments
+///
+/// * `public_key` - DER-encoded public key.
+///
+/// # Returns
+///
+/// Returns the 32-byte key ID as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn key_id(public_key: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    // Re-encoding makes the ID independent of how the key was written
+    let public_key = RsaPublicKey::from_public_key_der(public_key)?;
+
+    Ok(keccak256(public_key.to_public_key_der()?.as_bytes()).to_vec())
+}
+
+fn decode_registry(registry: &[u8]) -> Result<Vec<RegistryEntry>, Error> {
+    if registry.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(from_bytes::<Vec<RegistryEntry>>(registry)?)
+}
+
+/// Adds a signing key to a key registry.
+///
+/// Tokens signed with the key are accepted from `not_before` to `not_after`, both included,
+/// unless the key is retired earlier.
+///
+/// # Arguments
+///
+/// * `registry` - Encoded registry, or an empty vector for a new one.
+/// * `public_key` - DER-encoded public key.
+/// * `not_before` - Unix timestamp from which the key is valid.
+/// * `not_after` - Unix timestamp after which the key is no longer valid.
+///
+/// # Returns
+///
+/// Returns the updated registry as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn register_key(
+    registry: &Vec<u8>,
+    public_key: &Vec<u8>,
+    not_before: u64,
+    not_after: u64,
+) -> Result<Vec<u8>, Error> {
+    if not_before > not_after {
+        return Err(Error::InvalidInput(
+            "The validity window ends before it starts".into(),
+        ));
+    }
+
+    let mut entries = decode_registry(registry)?;
+    let id = <[u8; 32]>::try_from(key_id(public_key)?.as_slice())?;
+
+    if entries.iter().any(|(entry_id, ..)| *entry_id == id) {
+        return Err(Error::InvalidInput("Key is already registered".into()));
+    }
+
+    entries.push((id, public_key.clone(), not_before, not_after, false));
+
+    Ok(to_allocvec(&entries)?)
+}
+
+/// Retires a signing key, so that its tokens are rejected from now on.
+///
+/// # Arguments
+///
+/// * `registry` - Encoded registry.
+/// * `key_id` - 32-byte ID of the key to retire.
+///
+/// # Returns
+///
+/// Returns the updated registry as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn retire_key(registry: &Vec<u8>, key_id: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let mut entries = decode_registry(registry)?;
+
+    let (.., retired) = entries
+        .iter_mut()
+        .find(|(entry_id, ..)| entry_id.as_slice() == key_id.as_slice())
+        .ok_or(Error::Key("Unknown key ID".into()))?;
+    *retired = true;
+
+    Ok(to_allocvec(&entries)?)
+}
+
+/// Finds the public key of a key ID that is active at `timestamp`.
+fn active_key(entries: &[RegistryEntry], key_id: &[u8], timestamp: u64) -> Option<Vec<u8>> {
+    entries
+        .iter()
+        .find(|(entry_id, ..)| entry_id.as_slice() == key_id)
+        .filter(|(_, _, not_before, not_after, retired)| {
+            !retired && (*not_before..=*not_after).contains(&timestamp)
+        })
+        .map(|(_, public_key, ..)| public_key.clone())
+}
+
+/// Resolves a key ID to its public key.
+///
+/// # Arguments
+///
+/// * `registry` - Encoded registry.
+/// * `key_id` - 32-byte key ID.
+/// * `timestamp` - Unix timestamp the key must be valid at.
+///
+/// # Returns
+///
+/// Returns the DER-encoded public key, or an `Error::Key` if the key is unknown, retired or
+/// outside its validity window.
+#[allow(clippy::ptr_arg)]
+pub fn resolve_key(registry: &Vec<u8>, key_id: &Vec<u8>, timestamp: u64) -> Result<Vec<u8>, Error> {
+    active_key(&decode_registry(registry)?, key_id, timestamp)
+        .ok_or(Error::Key("Unknown, retired or expired key ID".into()))
+}
+
+/// Verifies a blind signature with the key a registry holds for `key_id`.
+///
+/// # Arguments
+///
+/// * `registry` - Encoded registry.
+/// * `key_id` - 32-byte ID of the signing key.
+/// * `signature_bytes` - The unblinded signature.
+/// * `msg_randomizer` - Message randomizer of the request, empty in the deterministic mode.
+/// * `msg` - The original message.
+/// * `timestamp` - Unix timestamp the key must be valid at.
+/// * `scheme` - Blind signature mode the signature was issued in.
+///
+/// # Returns
+///
+/// Returns `true` if valid, `false` otherwise, including when the key is unknown, retired
+/// or outside its validity window.
+#[allow(clippy::ptr_arg)]
+pub fn verify_by_key_id(
+    registry: &Vec<u8>,
+    key_id: &Vec<u8>,
+    signature_bytes: Vec<u8>,
+    msg_randomizer: &Vec<u8>,
+    msg: &Vec<u8>,
+    timestamp: u64,
+    scheme: u8,
+) -> Result<bool, Error> {
+    match active_key(&decode_registry(registry)?, key_id, timestamp) {
+        Some(public_key) => verify(&public_key, signature_bytes, msg_randomizer, msg, scheme),
+        None => Ok(false),
+    }
+}
+
+/// Bundles the tokens several registrars issued for the same voter message.
+///
+/// Each registrar signs the same hidden message with its own key, through `create_request`,
+/// `sign` and `unblind`.
+///
+/// # Arguments
+///
+/// * `registrars` - Index of the registrar of every signature, in the list of registrar keys
+///   of the election.
+/// * `signatures` - The unblinded signatures.
+/// * `msg_randomizers` - Message randomizer of every request, empty in the deterministic
+///   mode.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `scheme` - Blind signature mode of the election.
+///
+/// # Returns
+///
+/// Returns the encoded bundle as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encode_eligibility_bundle(
+    registrars: &Vec<usize>,
+    signatures: &Vec<Vec<u8>>,
+    msg_randomizers: &Vec<Vec<u8>>,
+    election_id: &Vec<u8>,
+    scheme: u8,
+) -> Result<Vec<u8>, Error> {
+    if registrars.len() != signatures.len() || msg_randomizers.len() != signatures.len() {
+        return Err(Error::InvalidInput(
+            "Expected one registrar and message randomizer per signature".into(),
+        ));
+    }
+
+    let election_id = B256::try_from(election_id.as_slice())?;
+    let bundle = registrars
+        .iter()
+        .zip(signatures.iter().zip(msg_randomizers))
+        .map(|(registrar, (signature, msg_randomizer))| {
+            let registrar =
+                u16::try_from(*registrar).map_err(|e| Error::InvalidInput(e.to_string()))?;
+
+            Ok((registrar, token(scheme, signature, msg_randomizer)?))
+        })
+        .collect::<Result<EligibilityBundle, Error>>()?;
+
+    Ok(seal_with(
+        scheme,
+        ELIGIBILITY_BUNDLE,
+        &election_id,
+        &to_allocvec(&bundle)?,
+    ))
+}
+
+/// Derives the nullifier of a voter vouched for by an eligibility bundle.
+///
+/// The nullifier is `keccak256(abi.encode(keccak256("eligibility_nullifier"), msg))`, so a
+/// voter has a single nullifier whichever registrars signed its message.
+///
+/// # Arguments
+///
+/// * `msg` - The hidden voter message.
+///
+/// # Returns
+///
+/// Returns the 32-byte nullifier.
+#[allow(clippy::ptr_arg)]
+pub fn eligibility_nullifier(msg: &Vec<u8>) -> Vec<u8> {
+    let encoded = (keccak256(ELIGIBILITY_LABEL), Bytes::from(msg.clone())).abi_encode_sequence();
+
+    keccak256(encoded).to_vec()
+}
+
+/// Verifies that at least `k` distinct registrars vouch for a voter message.
+///
+/// # Arguments
+///
+/// * `public_keys` - DER-encoded public keys of the registrars of the election.
+/// * `k` - The number of registrars that must vouch for the voter.
+/// * `bundle` - Bundle from `encode_eligibility_bundle`.
+/// * `msg` - The hidden voter message.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `scheme` - Blind signature mode of the election. Bundles written in the other mode are
+///   rejected.
+///
+/// # Returns
+///
+/// Returns `true` if valid, `false` otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_eligibility_bundle(
+    public_keys: &Vec<Vec<u8>>,
+    k: usize,
+    bundle: &Vec<u8>,
+    msg: &Vec<u8>,
+    election_id: &Vec<u8>,
+    scheme: u8,
+) -> Result<bool, Error> {
+    let public_keys = public_keys
+        .iter()
+        .map(|public_key| BlindPublic::from_der(public_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+
+    is_eligible(&public_keys, k, bundle, msg, &election_id, scheme)
+}
+
+/// Counts the distinct registrars with a valid signature in a bundle, and compares them to
+/// `k`. Signatures of unknown registrars are ignored, and a key listed twice counts once.
+///
+/// The bundle must be written in `scheme`, the blind signature mode of the election, rather
+/// than the one its envelope claims, which the voter controls.
+pub(crate) fn is_eligible(
+    public_keys: &[BlindPublic],
+    k: usize,
+    bundle: &[u8],
+    msg: &[u8],
+    election_id: &B256,
+    scheme: u8,
+) -> Result<bool, Error> {
+    if k == 0 || k > public_keys.len() {
+        return Err(Error::InvalidInput("Invalid threshold parameters".into()));
+    }
+
+    let (bundle_election, bundle) = open_with(scheme, ELIGIBILITY_BUNDLE, bundle)?;
+    let bundle = from_bytes::<EligibilityBundle>(bundle)?;
+
+    if bundle_election != *election_id {
+        return Ok(false);
+    }
+
+    let mut vouching: Vec<Vec<u8>> = Vec::with_capacity(k);
+    for (registrar, token) in bundle {
+        let Some(public_key) = public_keys.get(registrar as usize) else {
+            continue;
+        };
+        let key = public_key.to_der()?;
+
+        if !vouching.contains(&key) && verify_token(public_key, scheme, &token, msg)? {
+            vouching.push(key);
+        }
+    }
+
+    Ok(vouching.len() >= k)
+}
+
+/// Encodes the public metadata of a partially blind token, `abi.encode(election_id, expiry)`.
+///
+/// # Arguments
+///
+/// * `election_id` - 32-byte identifier of the election the token is issued for.
+/// * `expiry` - Unix timestamp after which the token is no longer valid.
+///
+/// # Returns
+///
+/// Returns the encoded metadata as `Result<Vec<u8>, Error>`.
+pub fn token_metadata(election_id: &Vec<u8>, expiry: u64) -> Result<Vec<u8>, Error> {
+    let election_id = B256::try_from(election_id.as_slice())?;
+
+    Ok((election_id, U256::from(expiry)).abi_encode_sequence())
+}
+
+/// Generates a new RSA key pair for partially blind signatures.
+///
+/// The modulus is the product of two 1024-bit safe primes, so that a key can be derived
+/// for every metadata value. Finding them takes much longer than a regular key pair.
+///
+/// # Returns
+///
+/// Returns an `ExportedKeyPair` with DER-encoded master keys.
+pub fn generate_partially_blind_keypair() -> Result<ExportedKeyPair, Error> {
+    let keypair =
+        PartiallyBlindKeyPair::<Sha384, PSS, Randomized>::generate(&mut DefaultRng, 2048)?;
+
+    Ok(ExportedKeyPair {
+        public: keypair.pk.to_der()?,
+        private: keypair.sk.to_der()?,
+    })
+}
+
+/// Derives the public key of the metadata of a token from a DER-encoded master public key.
+fn derive_public_key(public_key: &[u8], metadata: &[u8]) -> Result<PartiallyBlindPublic, Error> {
+    Ok(PartiallyBlindPublic::from_der(public_key)?.derive_public_key_for_metadata(metadata)?)
+}
+
+/// Creates a partially blind signature request.
+///
+/// Unlike `create_request`, the election ID and expiry are not hidden in the message but
+/// bound to the signature as public metadata, so the signer knows which election it signs
+/// for and a token is only valid for that election and until its expiry.
+///
+/// # Arguments
+///
+/// * `public_key` - DER-encoded master public key.
+/// * `msg` - Message to be blinded.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `expiry` - Unix timestamp after which the token is no longer valid.
+///
+/// # Returns
+///
+/// Returns the blinded message, the secret (blinding factor) and the message randomizer.
+#[allow(clippy::ptr_arg)]
+pub fn create_partially_blind_request(
+    public_key: &Vec<u8>,
+    msg: &Vec<u8>,
+    election_id: &Vec<u8>,
+    expiry: u64,
+) -> Result<ExportedBlindingResult, Error> {
+    let metadata = token_metadata(election_id, expiry)?;
+    let public_key = derive_public_key(public_key, &metadata)?;
+
+    let res = public_key.blind(&mut DefaultRng, msg, Some(&metadata))?;
+
+    Ok(ExportedBlindingResult {
+        blind_message: res.blind_message.0,
+        secret: res.secret.0,
+        msg_randomizer: res
+            .msg_randomizer
+            .map(|randomizer| randomizer.0.to_vec())
+            .unwrap_or_default(),
+    })
+}
+
+/// Signs a partially blind message with the key derived for its metadata.
+///
+/// # Arguments
+///
+/// * `private_key` - DER-encoded master private key.
+/// * `blind_message` - The blinded message to sign.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `expiry` - Unix timestamp after which the token is no longer valid.
+///
+/// # Returns
+///
+/// Returns the blinded signature as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn sign_partially_blind(
+    private_key: &Vec<u8>,
+    blind_message: &Vec<u8>,
+    election_id: &Vec<u8>,
+    expiry: u64,
+) -> Result<Vec<u8>, Error> {
+    let metadata = token_metadata(election_id, expiry)?;
+    let secret_key = PartiallyBlindSecret::from_der(private_key)?;
+
+    let keypair = PartiallyBlindKeyPair {
+        pk: secret_key.public_key()?,
+        sk: secret_key,
+    };
+
+    Ok(keypair
+        .derive_secret_key_for_metadata(&metadata)?
+        .blind_sign(blind_message)?
+        .0)
+}
+
+/// Unblinds a partially blind signature.
+///
+/// # Arguments
+///
+/// * `public_key` - DER-encoded master public key.
+/// * `msg` - Original message.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `expiry` - Unix timestamp after which the token is no longer valid.
+/// * `secret` - Blinding factor.
+/// * `msg_randomizer` - 32-byte message randomizer of the request.
+/// * `blind_sig` - Blinded signature.
+///
+/// # Returns
+///
+/// Returns the unblinded signature as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn unblind_partially_blind(
+    public_key: &Vec<u8>,
+    msg: &Vec<u8>,
+    election_id: &Vec<u8>,
+    expiry: u64,
+    secret: Vec<u8>,
+    msg_randomizer: &Vec<u8>,
+    blind_sig: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let metadata = token_metadata(election_id, expiry)?;
+    let public_key = derive_public_key(public_key, &metadata)?;
+
+    let blinding_result = BlindingResult {
+        blind_message: BlindMessage(msg.clone()),
+        secret: Secret(secret),
+        msg_randomizer: Some(MessageRandomizer::new(<[u8; 32]>::try_from(
+            msg_randomizer.as_slice(),
+        )?)),
+    };
+
+    Ok(public_key
+        .finalize(
+            &BlindSignature::new(blind_sig),
+            &blinding_result,
+            msg,
+            Some(&metadata),
+        )?
+        .0)
+}
+
+/// Verifies a partially blind signature against a message and its metadata.
+///
+/// # Arguments
+///
+/// * `public_key` - DER-encoded master public key.
+/// * `signature_bytes` - The unblinded signature.
+/// * `msg_randomizer` - 32-byte message randomizer of the request.
+/// * `msg` - The original message.
+/// * `election_id` - 32-byte identifier of the election.
+/// * `expiry` - Unix timestamp after which the token is no longer valid.
+///
+/// # Returns
+///
+/// Returns `true` if valid, `false` otherwise. The expiry is not compared to the current
+/// time.
+#[allow(clippy::ptr_arg)]
+pub fn verify_partially_blind(
+    public_key: &Vec<u8>,
+    signature_bytes: Vec<u8>,
+    msg_randomizer: &Vec<u8>,
+    msg: &Vec<u8>,
+    election_id: &Vec<u8>,
+    expiry: u64,
+) -> Result<bool, Error> {
+    let metadata = token_metadata(election_id, expiry)?;
+    let public_key = PartiallyBlindPublic::from_der(public_key)?;
+    let msg_randomizer = <[u8; 32]>::try_from(msg_randomizer.as_slice())?;
+
+    verify_with_metadata(&public_key, signature_bytes, msg_randomizer, msg, &metadata)
+}
+
+/// Verifies a partially blind signature with the key derived from a master public key for
+/// `metadata`.
+pub(crate) fn verify_with_metadata(
+    public_key: &PartiallyBlindPublic,
+    signature_bytes: Vec<u8>,
+    msg_randomizer: [u8; 32],
+    msg: &[u8],
+    metadata: &[u8],
+) -> Result<bool, Error> {
+    let public_key = public_key.derive_public_key_for_metadata(metadata)?;
+
+    Ok(public_key
+        .verify(
+            &Signature::new(signature_bytes),
+            Some(MessageRandomizer::new(msg_randomizer)),
+            msg,
+            Some(metadata),
+        )
+        .is_ok())
+}
+
+/// Transcript label of the proofs of partial signatures.
+const PARTIAL_LABEL: &[u8] = b"threshold_partial";
+
+/// Length of the challenge of a partial signature proof.
+const CHALLENGE_LEN: usize = 32;
+
+/// Shoup threshold key set, `(modulus, exponent, threshold, v, verification_keys)`.
+///
+/// `v` generates the squares modulo the modulus and the verification key of registrar `i`
+/// is `v^s_i`, where `s_i` is its share of the private exponent.
+type ThresholdKeySet = (Vec<u8>, Vec<u8>, usize, Vec<u8>, Vec<Vec<u8>>);
+
+/// Key share of a registrar, `(index, share)`. Indices start at 1.
+type ThresholdKeyShare = (usize, Vec<u8>);
+
+/// Structure to hold an RSA key dealt among registrars.
+pub struct ExportedThresholdKey {
+    /// DER-encoded public key, as used by `create_request`, `unblind` and `verify`.
+    pub public: Vec<u8>,
+    /// Public parameters needed to produce, check and combine partial signatures.
+    pub key_set: Vec<u8>,
+    /// Key share of every registrar.
+    pub shares: Vec<Vec<u8>>,
+}
+
+/// Decoded threshold key set.
+struct KeySet {
+    modulus: BigUint,
+    exponent: BigUint,
+    threshold: usize,
+    v: BigUint,
+    verification_keys: Vec<BigUint>,
+}
+
+/// Decoded partial signature, `(index, partial, challenge, response)`.
+type Partial = (usize, BigUint, BigUint, BigUint);
+
+impl KeySet {
+    fn decode(key_set: &[u8]) -> Result<Self, Error> {
+        let (modulus, exponent, threshold, v, verification_keys) =
+            from_bytes::<ThresholdKeySet>(key_set)?;
+
+        Ok(KeySet {
+            modulus: BigUint::from_bytes_be(&modulus),
+            exponent: BigUint::from_bytes_be(&exponent),
+            threshold,
+            v: BigUint::from_bytes_be(&v),
+            verification_keys: verification_keys
+                .iter()
+                .map(|key| BigUint::from_bytes_be(key))
+                .collect(),
+        })
+    }
+
+    /// Length in bytes of the modulus, and of signatures.
+    fn len(&self) -> usize {
+        self.modulus.bits().div_ceil(8)
+    }
+
+    /// Length of the response of a partial signature proof, which exceeds the modulus by
+    /// the bits of the challenge and of the statistical hiding of the share.
+    fn response_len(&self) -> usize {
+        self.len() + 2 * CHALLENGE_LEN + 1
+    }
+
+    /// Length of an encoded partial signature, `index | partial | challenge | response`.
+    fn partial_len(&self) -> usize {
+        2 + self.len() + CHALLENGE_LEN + self.response_len()
+    }
+
+    fn delta(&self) -> BigUint {
+        delta(self.verification_keys.len())
+    }
+
+    fn verification_key(&self, index: usize) -> Option<&BigUint> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.verification_keys.get(i))
+    }
+
+    /// Parses a blinded message as an element of the multiplicative group.
+    fn element(&self, blind_message: &[u8]) -> Result<BigUint, Error> {
+        let element = BigUint::from_bytes_be(blind_message);
+
+        if element == BigUint::from(0u32) || element >= self.modulus {
+            return Err(Error::InvalidInput(
+                "The blinded message is out of range".into(),
+            ));
+        }
+
+        Ok(element)
+    }
+
+    /// Fiat-Shamir challenge of the proof that `log_v(key) == log_base(squared)`.
+    fn challenge(
+        &self,
+        base: &BigUint,
+        key: &BigUint,
+        squared: &BigUint,
+        commitments: [&BigUint; 2],
+    ) -> BigUint {
+        let mut transcript = Transcript::new(PARTIAL_LABEL);
+        transcript.append_message(b"N", &self.modulus.to_bytes_be());
+        transcript.append_message(b"v", &self.v.to_bytes_be());
+        transcript.append_message(b"x", &base.to_bytes_be());
+        transcript.append_message(b"vi", &key.to_bytes_be());
+        transcript.append_message(b"xi", &squared.to_bytes_be());
+
+        for commitment in commitments {
+            transcript.append_message(b"A", &commitment.to_bytes_be());
+        }
+
+        let mut challenge = [0u8; CHALLENGE_LEN];
+        transcript.challenge_bytes(b"c", &mut challenge);
+
+        BigUint::from_bytes_be(&challenge)
+    }
+
+    fn encode_partial(&self, (index, partial, challenge, response): &Partial) -> Vec<u8> {
+        let mut encoded = (*index as u16).to_be_bytes().to_vec();
+        encoded.extend(to_fixed(partial, self.len()));
+        encoded.extend(to_fixed(challenge, CHALLENGE_LEN));
+        encoded.extend(to_fixed(response, self.response_len()));
+
+        encoded
+    }
+
+    fn decode_partial(&self, partial: &[u8]) -> Result<Partial, Error> {
+        if partial.len() != self.partial_len() {
+            return Err(Error::Decode("Invalid partial signature length".into()));
+        }
+
+        let (index, rest) = partial.split_at(2);
+        let (value, rest) = rest.split_at(self.len());
+        let (challenge, response) = rest.split_at(CHALLENGE_LEN);
+
+        Ok((
+            u16::from_be_bytes([index[0], index[1]]) as usize,
+            BigUint::from_bytes_be(value),
+            BigUint::from_bytes_be(challenge),
+            BigUint::from_bytes_be(response),
+        ))
+    }
+
+    /// Checks the proof that a partial signature is `x^(2 * delta * s_i)`.
+    fn check_partial(&self, x: &BigUint, (index, partial, challenge, response): &Partial) -> bool {
+        let Some(key) = self.verification_key(*index) else {
+            return false;
+        };
+        if *partial == BigUint::from(0u32) || *partial >= self.modulus {
+            return false;
+        }
+
+        let base = x.modpow(&(self.delta() * 4u32), &self.modulus);
+        let squared = partial * partial % &self.modulus;
+
+        let negated = BigInt::from_biguint(Sign::Minus, challenge.clone());
+        let (Some(key_power), Some(squared_power)) = (
+            pow_signed(key, &negated, &self.modulus),
+            pow_signed(&squared, &negated, &self.modulus),
+        ) else {
+            return false;
+        };
+
+        let key_commitment = self.v.modpow(response, &self.modulus) * key_power % &self.modulus;
+        let base_commitment = base.modpow(response, &self.modulus) * squared_power % &self.modulus;
+
+        *challenge == self.challenge(&base, key, &squared, [&key_commitment, &base_commitment])
+    }
+}
+
+/// `n!`, which clears the denominators of the Lagrange coefficients of `n` registrars.
+fn delta(n: usize) -> BigUint {
+    (1..=n).fold(BigUint::from(1u32), |acc, i| acc * i)
+}
+
+/// Writes a non-negative integer as `len` big-endian bytes.
+fn to_fixed(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut fixed = vec![0; len.saturating_sub(bytes.len())];
+    fixed.extend(bytes);
+
+    fixed
+}
+
+/// Computes `base^exponent` modulo `modulus` for a signed exponent, or `None` if the
+/// exponent is negative and `base` is not invertible.
+fn pow_signed(base: &BigUint, exponent: &BigInt, modulus: &BigUint) -> Option<BigUint> {
+    let (base, exponent) = match exponent.sign() {
+        Sign::Minus => (
+            base.clone().mod_inverse(modulus)?.to_biguint()?,
+            (-exponent).to_biguint()?,
+        ),
+        _ => (base.clone(), exponent.to_biguint()?),
+    };
+
+    Some(base.modpow(&exponent, modulus))
+}
+
+/// Deals an RSA private key into `n` shares, any `k` of which can blindly sign.
+///
+/// Follows Shoup's "Practical Threshold Signatures": the private exponent is shared with a
+/// polynomial of degree `k - 1` modulo `p'q'`, where `p = 2p' + 1` and `q = 2q' + 1`, so the
+/// modulus must be a product of safe primes, e.g. a key from
+/// `generate_partially_blind_keypair`. The dealer should discard the private key afterwards.
+///
+/// # Arguments
+///
+/// * `private_key` - DER-encoded private key.
+/// * `k` - The number of registrars needed to sign.
+/// * `n` - The total number of registrars.
+///
+/// # Returns
+///
+/// Returns the public key, the key set and one key share per registrar.
+#[allow(clippy::ptr_arg)]
+pub fn deal_threshold_key(
+    private_key: &Vec<u8>,
+    k: usize,
+    n: usize,
+) -> Result<ExportedThresholdKey, Error> {
+    if k == 0 || k > n || n > u16::MAX as usize {
+        return Err(Error::InvalidInput("Invalid threshold parameters".into()));
+    }
+
+    let secret_key = RsaPrivateKey::from_pkcs8_der(private_key)?;
+    let [p, q] = secret_key.primes() else {
+        return Err(Error::Key("Expected a two-prime modulus".into()));
+    };
+
+    let p_prime: BigUint = (p - 1u32) >> 1;
+    let q_prime: BigUint = (q - 1u32) >> 1;
+    if !probably_prime(&p_prime, 20) || !probably_prime(&q_prime, 20) {
+        return Err(Error::Key(
+            "The modulus is not a product of safe primes".into(),
+        ));
+    }
+
+    let modulus = secret_key.n();
+    let exponent = secret_key.e();
+    let order = p_prime * q_prime;
+
+    let (gcd, _, _) =
+        BigInt::from_biguint(Sign::Plus, delta(n) * delta(n) * 4u32).extended_gcd(exponent);
+    if gcd != BigInt::from(1u32) {
+        return Err(Error::InvalidInput(
+            "The public exponent must be coprime with the number of registrars".into(),
+        ));
     }
+
+    let private_exponent = exponent
+        .clone()
+        .mod_inverse(&order)
+        .and_then(|d| d.to_biguint())
+        .ok_or(Error::Key("The public exponent is not invertible".into()))?;
+
+    let mut rng = thread_rng();
+    let coefficients: Vec<BigUint> = std::iter::once(private_exponent)
+        .chain((1..k).map(|_| rng.gen_biguint_below(&order)))
+        .collect();
+
+    let shares: Vec<BigUint> = (1..=n)
+        .map(|i| {
+            coefficients
+                .iter()
+                .rev()
+                .fold(BigUint::from(0u32), |acc, coefficient| {
+                    (acc * i + coefficient) % &order
+                })
+        })
+        .collect();
+
+    let root = rng.gen_biguint_below(modulus);
+    let v = &root * &root % modulus;
+
+    let verification_keys: Vec<Vec<u8>> = shares
+        .iter()
+        .map(|share| v.modpow(share, modulus).to_bytes_be())
+        .collect();
+
+    Ok(ExportedThresholdKey {
+        public: secret_key
+            .to_public_key()
+            .to_public_key_der()?
+            .as_bytes()
+            .to_vec(),
+        key_set: to_allocvec(&(
+            modulus.to_bytes_be(),
+            exponent.to_bytes_be(),
+            k,
+            v.to_bytes_be(),
+            verification_keys,
+        ))?,
+        shares: shares
+            .iter()
+            .enumerate()
+            .map(|(i, share)| to_allocvec(&(i + 1, share.to_bytes_be())))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Produces the partial blind signature of a registrar, with a proof that it is correct.
+///
+/// The partial signature is `x^(2 * n! * s_i)`, where `x` is the blinded message and `s_i`
+/// the share of the registrar. The proof shows it uses the same share as the verification
+/// key of the registrar, without revealing it.
+///
+/// # Arguments
+///
+/// * `key_set` - Key set from `deal_threshold_key`.
+/// * `key_share` - Key share of the registrar.
+/// * `blind_message` - The blinded message to sign, as from `create_request`.
+///
+/// # Returns
+///
+/// Returns the encoded partial signature as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn sign_threshold_partial(
+    key_set: &Vec<u8>,
+    key_share: &Vec<u8>,
+    blind_message: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let key_set = KeySet::decode(key_set)?;
+    let (index, share) = from_bytes::<ThresholdKeyShare>(key_share)?;
+    let key = key_set
+        .verification_key(index)
+        .ok_or(Error::Key("Unknown key share index".into()))?;
+
+    let x = key_set.element(blind_message)?;
+    let share = BigUint::from_bytes_be(&share);
+    let modulus = &key_set.modulus;
+
+    let partial = x.modpow(&(key_set.delta() * &share * 2u32), modulus);
+    let base = x.modpow(&(key_set.delta() * 4u32), modulus);
+    let squared = &partial * &partial % modulus;
+
+    let nonce = thread_rng().gen_biguint(modulus.bits() + 2 * CHALLENGE_LEN * 8);
+    let challenge = key_set.challenge(
+        &base,
+        key,
+        &squared,
+        [
+            &key_set.v.modpow(&nonce, modulus),
+            &base.modpow(&nonce, modulus),
+        ],
+    );
+    let response = share * &challenge + nonce;
+
+    Ok(key_set.encode_partial(&(index, partial, challenge, response)))
+}
+
+/// Verifies the proof of a partial blind signature.
+///
+/// # Arguments
+///
+/// * `key_set` - Key set from `deal_threshold_key`.
+/// * `blind_message` - The blinded message.
+/// * `partial` - Partial signature from `sign_threshold_partial`.
+///
+/// # Returns
+///
+/// Returns `true` if the partial signature is correct, `false` otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_threshold_partial(
+    key_set: &Vec<u8>,
+    blind_message: &Vec<u8>,
+    partial: &Vec<u8>,
+) -> Result<bool, Error> {
+    let key_set = KeySet::decode(key_set)?;
+    let x = key_set.element(blind_message)?;
+
+    Ok(key_set.check_partial(&x, &key_set.decode_partial(partial)?))
+}
+
+/// Combines partial blind signatures into a standard blind signature.
+///
+/// Partial signatures that cannot be decoded, or have an invalid proof or a repeated index,
+/// are skipped, and the first `k` valid ones are combined. The result is the same as `sign`
+/// with the dealt private key, so `unblind` and `verify` accept it unchanged.
+///
+/// # Arguments
+///
+/// * `key_set` - Key set from `deal_threshold_key`.
+/// * `blind_message` - The blinded message.
+/// * `partials` - Partial signatures from `sign_threshold_partial`.
+///
+/// # Returns
+///
+/// Returns the blinded signature as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn combine_threshold_partials(
+    key_set: &Vec<u8>,
+    blind_message: &Vec<u8>,
+    partials: &Vec<Vec<u8>>,
+) -> Result<Vec<u8>, Error> {
+    let key_set = KeySet::decode(key_set)?;
+    let x = key_set.element(blind_message)?;
+    let modulus = &key_set.modulus;
+
+    let mut selected: Vec<(usize, BigUint)> = Vec::with_capacity(key_set.threshold);
+    for partial in partials {
+        let Ok(partial) = key_set.decode_partial(partial) else {
+            continue;
+        };
+
+        if selected.len() < key_set.threshold
+            && selected.iter().all(|(index, _)| *index != partial.0)
+            && key_set.check_partial(&x, &partial)
+        {
+            selected.push((partial.0, partial.1));
+        }
+    }
+
+    if selected.len() < key_set.threshold {
+        return Err(Error::Proof("Not enough valid partial signatures".into()));
+    }
+
+    // w = prod(x_j^(2 * lambda_j)) = x^(4 * delta^2 * d)
+    let delta = BigInt::from_biguint(Sign::Plus, key_set.delta());
+    let mut w = BigUint::from(1u32);
+    for (j, partial) in &selected {
+        let (numerator, denominator) = selected.iter().filter(|(other, _)| other != j).fold(
+            (delta.clone(), BigInt::from(1u32)),
+            |(numerator, denominator), (other, _)| {
+                (
+                    numerator * BigInt::from(*other as u64),
+                    denominator * (BigInt::from(*other as u64) - BigInt::from(*j as u64)),
+                )
+            },
+        );
+        let lambda = numerator / denominator * 2u32;
+
+        let power = pow_signed(partial, &lambda, modulus).ok_or(Error::Arithmetic(
+            "Partial signature is not invertible".into(),
+        ))?;
+        w = w * power % modulus;
+    }
+
+    // With a * 4 * delta^2 + b * e = 1, (w^a * x^b)^e = x
+    let (_, a, b) = (&delta * &delta * 4u32).extended_gcd(&key_set.exponent);
+    let signature = pow_signed(&w, &a, modulus)
+        .zip(pow_signed(&x, &b, modulus))
+        .map(|(w, x)| w * x % modulus)
+        .ok_or(Error::Arithmetic(
+            "Blinded message is not invertible".into(),
+        ))?;
+
+    if signature.modpow(&key_set.exponent, modulus) != x {
+        return Err(Error::Proof(
+            "The combined signature does not verify".into(),
+        ));
+    }
+
+    Ok(to_fixed(&signature, key_set.len()))
 }
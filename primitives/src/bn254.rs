@@ -0,0 +1,388 @@
+use alloy_primitives::{B256, U256, keccak256};
+use alloy_sol_types::{SolValue, sol};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective};
+use ark_ec::{AffineRepr, CurveGroup, PrimeGroup};
+use ark_ff::{BigInt, PrimeField, UniformRand, Zero};
+use rand_legacy::{RngCore, thread_rng};
+use rayon::prelude::*;
+
+use crate::Error;
+use crate::backend::Backend;
+use crate::discrete_log::{self, DiscreteLog};
+use crate::envelope::BN254_ELGAMAL;
+
+/// Solidity contract that verifies the ballots of `Bn254` with the `ecAdd` and `ecMul`
+/// precompiles, as `verify_vote` does.
+pub const VERIFIER_CONTRACT: &str = include_str!("../contracts/Bn254BallotVerifier.sol");
+
+/// Transcript labels shared by ballot proofs and the verifier contract.
+const FLAG_LABEL: &[u8] = b"ballot_flag";
+const SUM_LABEL: &[u8] = b"multi_choice_sum";
+
+sol! {
+    /// Encrypted flag `(r, c) = ([ρ]G, [b]G + [ρ]H)` with the proof that `b` is 0 or 1,
+    /// `proof = [c0, c1, s0, s1]`.
+    struct Bn254Flag {
+        uint256[2] r;
+        uint256[2] c;
+        uint256[4] proof;
+    }
+
+    /// Single-choice ballot: one flag per option and the proof `sumProof = [c, s]` that
+    /// the flags add up to 1.
+    struct Bn254Ballot {
+        Bn254Flag[] flags;
+        uint256[2] sumProof;
+    }
+
+    /// Entry of an accumulator.
+    struct Bn254Ciphertext {
+        uint256[2] r;
+        uint256[2] c;
+    }
+}
+
+/// Point of the BN254 G1 group, with the arithmetic of `ark-bn254`.
+type Point = G1Projective;
+
+/// Reads a point written as the EVM precompiles do, checking that it lies on the curve.
+///
+/// The point at infinity is `(0, 0)`. G1 has a cofactor of 1, so every point on the curve
+/// is in the group.
+fn from_words(words: &[U256; 2]) -> Option<Point> {
+    if words.iter().all(U256::is_zero) {
+        return Some(Point::zero());
+    }
+
+    let x = Fq::from_bigint(BigInt::new(words[0].into_limbs()))?;
+    let y = Fq::from_bigint(BigInt::new(words[1].into_limbs()))?;
+    let point = G1Affine::new_unchecked(x, y);
+
+    point.is_on_curve().then(|| point.into_group())
+}
+
+fn to_words(point: Point) -> [U256; 2] {
+    point.into_affine().xy().map_or([U256::ZERO; 2], |(x, y)| {
+        [
+            U256::from_limbs(x.into_bigint().0),
+            U256::from_limbs(y.into_bigint().0),
+        ]
+    })
+}
+
+/// Reads a scalar of a proof, which must be reduced.
+fn scalar(word: U256) -> Option<Fr> {
+    Fr::from_bigint(BigInt::new(word.into_limbs()))
+}
+
+fn scalar_to_word(scalar: Fr) -> U256 {
+    U256::from_limbs(scalar.into_bigint().0)
+}
+
+/// Reads a point of a ballot or accumulator.
+fn point(words: &[U256; 2]) -> Result<Point, Error> {
+    from_words(words).ok_or_else(|| Error::Decode("Point is not on the BN254 curve".into()))
+}
+
+/// Hashes the words of a proof transcript to a challenge, as
+/// `uint256(keccak256(abi.encode(label, contextHash, ...))) % R` in the verifier contract.
+fn challenge(label: &[u8], context: &[u8], points: &[Point]) -> Fr {
+    let mut words = vec![
+        U256::from_be_bytes(keccak256(label).0),
+        U256::from_be_bytes(keccak256(context).0),
+    ];
+    words.extend(points.iter().flat_map(|point| to_words(*point)));
+
+    let bytes = words
+        .iter()
+        .flat_map(|word| word.to_be_bytes::<32>())
+        .collect::<Vec<_>>();
+
+    Fr::from_be_bytes_mod_order(keccak256(bytes).as_slice())
+}
+
+fn decode_public_key(public_key: &[u8]) -> Result<Point, Error> {
+    let words = <[U256; 2]>::abi_decode(public_key)
+        .map_err(|_| Error::Key("Invalid BN254 public key".into()))?;
+
+    from_words(&words)
+        .filter(|point| !point.is_zero())
+        .ok_or_else(|| Error::Key("Invalid BN254 public key".into()))
+}
+
+fn decode_secret_key(secret_key: &[u8]) -> Result<Fr, Error> {
+    B256::try_from(secret_key)
+        .ok()
+        .and_then(|bytes| scalar(U256::from_be_bytes(bytes.0)))
+        .filter(|secret_key| !secret_key.is_zero())
+        .ok_or_else(|| Error::Key("Invalid BN254 secret key".into()))
+}
+
+/// Encrypts a flag and proves that it is 0 or 1 with a disjunctive Chaum-Pedersen proof.
+fn encrypt_flag(
+    flag: bool,
+    receiver: Point,
+    context: &[u8],
+    rng: &mut impl RngCore,
+) -> (Bn254Flag, Fr) {
+    let generator = Point::generator();
+    let randomness = Fr::rand(rng);
+    let r = generator * randomness;
+    let mut c = receiver * randomness;
+    if flag {
+        c += generator;
+    }
+
+    // The branch of the other value is simulated from a chosen challenge and response
+    let real = flag as usize;
+    let mut challenges = [Fr::zero(); 2];
+    let mut responses = [Fr::zero(); 2];
+    let mut commitments = [Point::zero(); 4];
+
+    let fake = 1 - real;
+    challenges[fake] = Fr::rand(rng);
+    responses[fake] = Fr::rand(rng);
+    let shifted = if fake == 1 { c - generator } else { c };
+    commitments[2 * fake] = generator * responses[fake] - r * challenges[fake];
+    commitments[2 * fake + 1] = receiver * responses[fake] - shifted * challenges[fake];
+
+    let nonce = Fr::rand(rng);
+    commitments[2 * real] = generator * nonce;
+    commitments[2 * real + 1] = receiver * nonce;
+
+    let mut transcript = vec![receiver, r, c];
+    transcript.extend(commitments);
+    let challenge = challenge(FLAG_LABEL, context, &transcript);
+
+    challenges[real] = challenge - challenges[fake];
+    responses[real] = nonce + challenges[real] * randomness;
+
+    let flag = Bn254Flag {
+        r: to_words(r),
+        c: to_words(c),
+        proof: [challenges[0], challenges[1], responses[0], responses[1]].map(scalar_to_word),
+    };
+
+    (flag, randomness)
+}
+
+/// Checks the proof that an encrypted flag is 0 or 1, as `verifyFlag` in the contract.
+fn verify_flag(flag: &Bn254Flag, receiver: Point, context: &[u8]) -> bool {
+    let (Some(r), Some(c)) = (from_words(&flag.r), from_words(&flag.c)) else {
+        return false;
+    };
+    let [Some(c0), Some(c1), Some(s0), Some(s1)] = flag.proof.map(scalar) else {
+        return false;
+    };
+
+    let generator = Point::generator();
+    let transcript = [
+        receiver,
+        r,
+        c,
+        generator * s0 - r * c0,
+        receiver * s0 - c * c0,
+        generator * s1 - r * c1,
+        receiver * s1 - (c - generator) * c1,
+    ];
+
+    c0 + c1 == challenge(FLAG_LABEL, context, &transcript)
+}
+
+impl discrete_log::Group for Point {
+    type Encoding = G1Affine;
+
+    fn identity() -> Self {
+        Point::zero()
+    }
+
+    fn generator() -> Self {
+        <Point as PrimeGroup>::generator()
+    }
+
+    fn encode_batch(representatives: &[Self]) -> Vec<Self::Encoding> {
+        Point::normalize_batch(representatives)
+    }
+}
+
+/// Exponential ElGamal over the BN254 G1 group, with proofs a Solidity contract can check
+/// with the `ecAdd` and `ecMul` precompiles of any EVM chain.
+///
+/// Ballots and accumulators are ABI encoded, so the contract reads the payload of the
+/// envelope as its `Bn254Ballot` struct. See `VERIFIER_CONTRACT`.
+pub struct Bn254;
+
+impl Backend for Bn254 {
+    const SCHEME: u8 = BN254_ELGAMAL;
+
+    type Ballot = Bn254Ballot;
+    type Tally = Vec<Bn254Ciphertext>;
+
+    fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let rng = &mut thread_rng();
+
+        let mut secret_key = Fr::zero();
+        while secret_key.is_zero() {
+            secret_key = Fr::rand(rng);
+        }
+
+        Ok((
+            to_words(Point::generator() * secret_key).abi_encode(),
+            scalar_to_word(secret_key).to_be_bytes::<32>().to_vec(),
+        ))
+    }
+
+    fn encrypt(
+        public_key: &[u8],
+        choice: usize,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<Self::Ballot, Error> {
+        if options_count == 0 {
+            return Err(Error::InvalidInput("Invalid number of options".into()));
+        }
+        if choice >= options_count {
+            return Err(Error::InvalidInput("Choice out of bounds".into()));
+        }
+
+        let rng = &mut thread_rng();
+        let receiver = decode_public_key(public_key)?;
+
+        let (flags, randomness): (Vec<_>, Vec<_>) = (0..options_count)
+            .map(|option| encrypt_flag(option == choice, receiver, context, rng))
+            .unzip();
+
+        // The flags add up to an encryption of 1 with the sum of their randomness
+        let generator = Point::generator();
+        let sum_r = randomness.iter().sum::<Fr>();
+        let total_r = generator * sum_r;
+        let total_c = receiver * sum_r;
+
+        let nonce = Fr::rand(rng);
+        let transcript = [
+            receiver,
+            total_r,
+            total_c,
+            generator * nonce,
+            receiver * nonce,
+        ];
+        let challenge = challenge(SUM_LABEL, context, &transcript);
+        let response = nonce + challenge * sum_r;
+
+        Ok(Bn254Ballot {
+            flags,
+            sumProof: [challenge, response].map(scalar_to_word),
+        })
+    }
+
+    fn verify(
+        public_key: &[u8],
+        ballot: &Self::Ballot,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<bool, Error> {
+        let receiver = decode_public_key(public_key)?;
+
+        if ballot.flags.len() != options_count
+            || !ballot
+                .flags
+                .iter()
+                .all(|flag| verify_flag(flag, receiver, context))
+        {
+            return Ok(false);
+        }
+
+        let generator = Point::generator();
+        let (mut total_r, mut total_c) = (Point::zero(), -generator);
+        for flag in &ballot.flags {
+            total_r += point(&flag.r)?;
+            total_c += point(&flag.c)?;
+        }
+
+        let [Some(challenge_sum), Some(response)] = ballot.sumProof.map(scalar) else {
+            return Ok(false);
+        };
+
+        let transcript = [
+            receiver,
+            total_r,
+            total_c,
+            generator * response - total_r * challenge_sum,
+            receiver * response - total_c * challenge_sum,
+        ];
+
+        Ok(challenge_sum == challenge(SUM_LABEL, context, &transcript))
+    }
+
+    fn zero(options_count: usize) -> Result<Self::Tally, Error> {
+        let zero = Bn254Ciphertext {
+            r: to_words(Point::zero()),
+            c: to_words(Point::zero()),
+        };
+
+        Ok(vec![zero; options_count])
+    }
+
+    fn add(tally: &mut Self::Tally, ballot: &Self::Ballot) -> Result<(), Error> {
+        if ballot.flags.len() != tally.len() {
+            return Err(Error::InvalidInput(
+                "Ballot does not match the accumulator".into(),
+            ));
+        }
+
+        for (entry, flag) in tally.iter_mut().zip(&ballot.flags) {
+            entry.r = to_words(point(&entry.r)? + point(&flag.r)?);
+            entry.c = to_words(point(&entry.c)? + point(&flag.c)?);
+        }
+
+        Ok(())
+    }
+
+    fn options_count(tally: &Self::Tally) -> usize {
+        tally.len()
+    }
+
+    fn decrypt(
+        secret_key: &[u8],
+        tally: &Self::Tally,
+        ballots: u64,
+        max_score: u64,
+        baby_steps: Option<u64>,
+    ) -> Result<Vec<u64>, Error> {
+        let bound = ballots
+            .checked_mul(max_score)
+            .ok_or_else(|| Error::Arithmetic("Tally bound overflows".into()))?;
+        let secret_key = decode_secret_key(secret_key)?;
+        let solver = match baby_steps {
+            Some(baby_steps) => DiscreteLog::with_baby_steps(bound, baby_steps),
+            None => DiscreteLog::new(bound),
+        };
+
+        tally
+            .par_iter()
+            .map(|entry| {
+                let message = point(&entry.c)? - point(&entry.r)? * secret_key;
+
+                solver.solve(&message).ok_or_else(|| {
+                    Error::Arithmetic("Count exceeds the ballots of the accumulator".into())
+                })
+            })
+            .collect()
+    }
+
+    fn encode_ballot(ballot: &Self::Ballot) -> Result<Vec<u8>, Error> {
+        Ok(ballot.abi_encode())
+    }
+
+    fn decode_ballot(ballot: &[u8]) -> Result<Self::Ballot, Error> {
+        Ok(<Bn254Ballot as SolValue>::abi_decode(ballot)?)
+    }
+
+    fn encode_tally(tally: &Self::Tally) -> Result<Vec<u8>, Error> {
+        Ok(tally.abi_encode())
+    }
+
+    fn decode_tally(tally: &[u8]) -> Result<Self::Tally, Error> {
+        Ok(Vec::<Bn254Ciphertext>::abi_decode(tally)?)
+    }
+}
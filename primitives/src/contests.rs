@@ -0,0 +1,355 @@
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, PublicKey};
+use postcard::{from_bytes, to_allocvec};
+
+use crate::Error;
+use crate::ballots::{
+    MultiChoiceBallot, ScoreBallot, accumulate, context_election, decode_acc, empty_acc,
+    encode_acc, encrypt_multi_vote, encrypt_score_vote, encrypt_vote, in_election, open_ballot,
+    verify_multi_choice, verify_score,
+};
+use crate::envelope::{
+    CONTEST_BALLOT, MULTI_CHOICE_BALLOT, RANKED_BALLOT, SCORE_BALLOT, open, seal,
+};
+use crate::ranked_ballots::{RankedBallot, encrypt_ranked_vote, preference_matrix, verify_ranked};
+
+/// Contest kinds of a ballot manifest.
+pub const SINGLE_CHOICE: u64 = 0;
+pub const MULTI_CHOICE: u64 = 1;
+pub const SCORE: u64 = 2;
+pub const RANKED: u64 = 3;
+
+/// Manifest entry as encoded in ABI: `(kind, options_count, param_a, param_b)`.
+type ManifestEntry = (U256, U256, U256, U256);
+
+/// Choice rule of a contest.
+enum Rule {
+    Single,
+    Multi { min: usize, max: usize },
+    Score { max: u64, budget: Option<u64> },
+    Ranked,
+}
+
+/// Contest described by the ballot manifest.
+struct Contest {
+    options_count: usize,
+    rule: Rule,
+}
+
+impl Contest {
+    /// Envelope kind of the ballots of the contest.
+    fn ballot_kind(&self) -> u8 {
+        match self.rule {
+            Rule::Single | Rule::Multi { .. } => MULTI_CHOICE_BALLOT,
+            Rule::Score { .. } => SCORE_BALLOT,
+            Rule::Ranked => RANKED_BALLOT,
+        }
+    }
+
+    /// Number of accumulator entries used by the contest, if it fits in a `usize`.
+    fn acc_len(&self) -> Option<usize> {
+        match self.rule {
+            Rule::Ranked => self.options_count.checked_mul(self.options_count),
+            _ => Some(self.options_count),
+        }
+    }
+}
+
+/// Decodes and checks a ballot manifest.
+///
+/// Every entry is `(kind, options_count, param_a, param_b)`, where the parameters are
+/// `(min, max)` for multi-choice contests, `(max, budget)` for score contests, with a
+/// budget of 0 meaning none, and unused otherwise.
+fn parse_manifest(manifest: &[u8]) -> Result<Vec<Contest>, Error> {
+    let entries = <Vec<ManifestEntry>>::abi_decode(manifest)?;
+
+    if entries.is_empty() {
+        return Err(Error::InvalidInput(
+            "Manifest must contain at least one contest".into(),
+        ));
+    }
+
+    entries
+        .into_iter()
+        .map(|(kind, options_count, a, b)| {
+            let options_count = options_count.try_into()?;
+
+            let rule = match kind.try_into()? {
+                SINGLE_CHOICE => Rule::Single,
+                MULTI_CHOICE => Rule::Multi {
+                    min: a.try_into()?,
+                    max: b.try_into()?,
+                },
+                SCORE => Rule::Score {
+                    max: a.try_into()?,
+                    budget: Some(b.try_into()?).filter(|&budget| budget > 0),
+                },
+                RANKED => Rule::Ranked,
+                _ => return Err(Error::InvalidInput("Unknown contest kind".into())),
+            };
+
+            Ok(Contest {
+                options_count,
+                rule,
+            })
+        })
+        .collect()
+}
+
+/// Offset of every contest in the accumulator, followed by the accumulator length.
+fn offsets(contests: &[Contest]) -> Result<Vec<usize>, Error> {
+    let mut offset = 0_usize;
+
+    std::iter::once(Ok(0))
+        .chain(contests.iter().map(|contest| {
+            offset = contest
+                .acc_len()
+                .and_then(|len| offset.checked_add(len))
+                .ok_or_else(|| Error::Arithmetic("Manifest too large".into()))?;
+            Ok(offset)
+        }))
+        .collect()
+}
+
+/// Generates an accumulator for every contest of a manifest initialized to zero.
+///
+/// The accumulators of the contests are concatenated in manifest order, so the whole
+/// accumulator is decrypted at once and then split with `split_contest_results`.
+///
+/// # Arguments
+///
+/// * `manifest` - ABI encoded ballot manifest.
+/// * `election_id` - 32-byte identifier of the election.
+///
+/// # Returns
+///
+/// Returns the initialized accumulator as a `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn generate_contest_acc(manifest: &Vec<u8>, election_id: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let contests = parse_manifest(manifest)?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+
+    empty_acc(&election_id, offsets(&contests)?[contests.len()])
+}
+
+/// Encrypts the selections of every contest of a manifest into a single ballot.
+///
+/// Each contest is encrypted with the ballot format of its kind and carries its own
+/// validity proofs.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `manifest` - ABI encoded ballot manifest.
+/// * `selections` - Selection for every contest: the chosen option for single-choice
+///   contests, the chosen options for multi-choice contests, the score of every option for
+///   score contests and the options from most to least preferred for ranked contests.
+/// * `context` - Ballot context from `ballots::ballot_context`, shared by all the contests.
+///
+/// # Returns
+///
+/// Returns the encrypted ballot as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_contest_votes(
+    pub_key_bytes: &Vec<u8>,
+    manifest: &Vec<u8>,
+    selections: &Vec<Vec<u64>>,
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let contests = parse_manifest(manifest)?;
+    let election_id = context_election(context)?;
+
+    if selections.len() != contests.len() {
+        return Err(Error::InvalidInput(
+            "Expected one selection per contest".into(),
+        ));
+    }
+
+    let ballots = contests
+        .iter()
+        .zip(selections)
+        .map(|(contest, selection)| {
+            let n = contest.options_count;
+            let indexes = selection
+                .iter()
+                .map(|&i| usize::try_from(i))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| Error::InvalidInput("Selection index out of range".into()))?;
+
+            match contest.rule {
+                Rule::Single => match indexes[..] {
+                    [choice] if choice < n => encrypt_vote(pub_key_bytes, choice, n, context),
+                    _ => Err(Error::InvalidInput("Expected a single valid choice".into())),
+                },
+                Rule::Multi { min, max } => {
+                    encrypt_multi_vote(pub_key_bytes, &indexes, n, min, max, context)
+                }
+                Rule::Score { max, budget } => {
+                    if selection.len() != n {
+                        return Err(Error::InvalidInput(
+                            "Expected a score for every option".into(),
+                        ));
+                    }
+
+                    encrypt_score_vote(pub_key_bytes, selection, max, budget, context)
+                }
+                Rule::Ranked => encrypt_ranked_vote(pub_key_bytes, &indexes, n, context),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(seal(CONTEST_BALLOT, &election_id, &to_allocvec(&ballots)?))
+}
+
+/// Checks the proofs of one contest of a ballot of the election `election_id`.
+fn verify_contest(
+    contest: &Contest,
+    ballot: &[u8],
+    election_id: &B256,
+    receiver: &PublicKey<Ristretto>,
+    context: &[u8],
+) -> bool {
+    let n = contest.options_count;
+    let Ok(ballot) = open_ballot(contest.ballot_kind(), ballot, election_id) else {
+        return false;
+    };
+
+    match contest.rule {
+        Rule::Single => from_bytes::<MultiChoiceBallot>(ballot)
+            .is_ok_and(|ballot| verify_multi_choice(&ballot, receiver, n, 1, 1, context)),
+        Rule::Multi { min, max } => from_bytes::<MultiChoiceBallot>(ballot)
+            .is_ok_and(|ballot| verify_multi_choice(&ballot, receiver, n, min, max, context)),
+        Rule::Score { max, budget } => from_bytes::<ScoreBallot>(ballot).is_ok_and(|ballot| {
+            verify_score(&ballot, receiver, n, max, budget.unwrap_or(0), context)
+        }),
+        Rule::Ranked => from_bytes::<RankedBallot>(ballot)
+            .is_ok_and(|ballot| verify_ranked(&ballot, receiver, n, context)),
+    }
+}
+
+/// Verifies the validity of every contest of an encrypted ballot using Zero-Knowledge Proofs.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(manifest, context, public_key, ballot)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_contest_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (manifest, context, public_key, ballot) =
+        <(Bytes, Bytes, Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let contests = parse_manifest(manifest.as_ref())?;
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let (election_id, ballot) = open(CONTEST_BALLOT, &ballot)?;
+    let ballots = from_bytes::<Vec<Vec<u8>>>(ballot)?;
+
+    let mut output = vec![0; 32];
+
+    if in_election(&election_id, &context)
+        && ballots.len() == contests.len()
+        && contests.iter().zip(&ballots).all(|(contest, ballot)| {
+            verify_contest(contest, ballot, &election_id, &public_key, &context)
+        })
+    {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Extracts the ciphertexts one contest of a ballot adds to the accumulator of the election
+/// `election_id`.
+fn contest_ciphertexts(
+    contest: &Contest,
+    ballot: &[u8],
+    election_id: &B256,
+) -> Result<Vec<Ciphertext<Ristretto>>, Error> {
+    let ballot = open_ballot(contest.ballot_kind(), ballot, election_id)?;
+
+    Ok(match contest.rule {
+        Rule::Single | Rule::Multi { .. } => {
+            let (choices, _, _) = from_bytes::<MultiChoiceBallot>(ballot)?;
+            choices.into_iter().map(|(choice, _)| choice).collect()
+        }
+        Rule::Score { .. } => {
+            let (scores, _) = from_bytes::<ScoreBallot>(ballot)?;
+            scores.into_iter().map(|(score, _)| score).collect()
+        }
+        Rule::Ranked => {
+            preference_matrix(&from_bytes::<RankedBallot>(ballot)?, contest.options_count)?
+        }
+    })
+}
+
+/// Adds every contest of an encrypted ballot to the accumulator.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(manifest, accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_contest_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (manifest, acc, ballot) = <(Bytes, Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let contests = parse_manifest(manifest.as_ref())?;
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballot = open_ballot(CONTEST_BALLOT, &ballot, &election_id)?;
+    let ballots = from_bytes::<Vec<Vec<u8>>>(ballot)?;
+
+    let offsets = offsets(&contests)?;
+
+    if acc.len() != offsets[contests.len()] || ballots.len() != contests.len() {
+        return Err(Error::InvalidInput(
+            "Ballot does not match the manifest".into(),
+        ));
+    }
+
+    for ((contest, ballot), range) in contests.iter().zip(&ballots).zip(offsets.windows(2)) {
+        accumulate(
+            &mut acc[range[0]..range[1]],
+            contest_ciphertexts(contest, ballot, &election_id)?.into_iter(),
+        )?;
+    }
+
+    encode_acc(&election_id, count, 1, &acc)
+}
+
+/// Splits the decrypted accumulator into the results of every contest.
+///
+/// # Arguments
+///
+/// * `manifest` - ABI encoded ballot manifest.
+/// * `results` - Decrypted accumulator, e.g. from `decrypt_result` with a `max_score` of at
+///   least the highest score of the manifest.
+///
+/// # Returns
+///
+/// Returns the results of every contest in manifest order. Ranked contests yield their
+/// pairwise preference matrix.
+#[allow(clippy::ptr_arg)]
+pub fn split_contest_results(
+    manifest: &Vec<u8>,
+    results: &Vec<u64>,
+) -> Result<Vec<Vec<u64>>, Error> {
+    let contests = parse_manifest(manifest)?;
+    let offsets = offsets(&contests)?;
+
+    if results.len() != offsets[contests.len()] {
+        return Err(Error::InvalidInput(
+            "Results do not match the manifest".into(),
+        ));
+    }
+
+    Ok(offsets
+        .windows(2)
+        .map(|range| results[range[0]..range[1]].to_vec())
+        .collect())
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Neg};
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+
+/// Largest number of baby steps a `DiscreteLog` stores, about 100 MB of table.
+///
+/// Past `MAX_BABY_STEPS²` the solver trades time for memory: every giant step covers at
+/// most this many values.
+pub const MAX_BABY_STEPS: u64 = 1 << 20;
+
+/// Number of elements encoded together.
+const BATCH_SIZE: u64 = 1024;
+
+/// Group that `DiscreteLog` solves discrete logs in.
+///
+/// The solver walks over representatives of the elements rather than the elements
+/// themselves, which lets a group whose batch encoding transforms its input undo that
+/// transform, and stores the encodings of the baby steps in its table.
+pub trait Group: Copy + Send + Sync + Add<Output = Self> + Neg<Output = Self> {
+    /// Encoding the table of baby steps is keyed by, unique per element.
+    type Encoding: Eq + Hash + Send + Sync;
+
+    /// Neutral element.
+    fn identity() -> Self;
+
+    /// Generator `G` that discrete logs are taken to.
+    fn generator() -> Self;
+
+    /// Maps an element to its representative. Must be a homomorphism.
+    fn represent(element: &Self) -> Self {
+        *element
+    }
+
+    /// Encodes the elements of a batch of representatives.
+    fn encode_batch(representatives: &[Self]) -> Vec<Self::Encoding>;
+}
+
+/// Inverse of 2 modulo the group order.
+fn half() -> Scalar {
+    Scalar::from(2_u64).invert()
+}
+
+/// Compressing an element costs a field inversion. Batch compression shares one inversion
+/// between many elements but doubles them, so the solver walks over halved elements.
+impl Group for RistrettoPoint {
+    type Encoding = CompressedRistretto;
+
+    fn identity() -> Self {
+        <Self as Identity>::identity()
+    }
+
+    fn generator() -> Self {
+        RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn represent(element: &Self) -> Self {
+        element * half()
+    }
+
+    fn encode_batch(representatives: &[Self]) -> Vec<Self::Encoding> {
+        RistrettoPoint::double_and_compress_batch(representatives)
+    }
+}
+
+/// Baby-step giant-step solver for discrete logs in `0..=bound`, over the Ristretto group
+/// unless another `Group` is given.
+///
+/// The solver stores `[i]G` for the `m` baby steps `i < m`, then walks down from the
+/// target element in giant steps of `[m]G` until it lands in the table. A lookup costs at
+/// most `bound / m + 1` giant steps, so `m = √bound` balances memory and time. Both phases
+/// encode elements in batches of the group.
+pub struct DiscreteLog<G: Group = RistrettoPoint> {
+    bound: u64,
+    step: u64,
+    baby_steps: HashMap<G::Encoding, u64>,
+    /// Representative of `-[m]G`, the giant step.
+    giant_step: G,
+}
+
+/// Encodes the `count` elements `point, point + step, ...`, then moves `point` past them.
+fn encode_walk<G: Group>(point: &mut G, step: G, count: u64) -> Vec<G::Encoding> {
+    let points = (0..count)
+        .map(|_| {
+            let current = *point;
+            *point = current + step;
+            current
+        })
+        .collect::<Vec<_>>();
+
+    G::encode_batch(&points)
+}
+
+impl<G: Group> DiscreteLog<G> {
+    /// Creates a solver for `0..=bound` with about `√bound` baby steps.
+    pub fn new(bound: u64) -> Self {
+        let baby_steps = (bound.isqrt() + 1).min(MAX_BABY_STEPS);
+        Self::with_baby_steps(bound, baby_steps)
+    }
+
+    /// Creates a solver for `0..=bound` that stores `baby_steps` elements.
+    ///
+    /// More baby steps make lookups faster at the cost of memory. `baby_steps` is clamped
+    /// to `1..=min(bound + 1, MAX_BABY_STEPS)`, so the table never outgrows that of `new`.
+    pub fn with_baby_steps(bound: u64, baby_steps: u64) -> Self {
+        let step = baby_steps.clamp(1, bound.saturating_add(1).min(MAX_BABY_STEPS));
+        let generator = G::represent(&G::generator());
+
+        let mut table = HashMap::new();
+        let mut point = G::identity();
+
+        for start in (0..step).step_by(BATCH_SIZE as usize) {
+            let count = (step - start).min(BATCH_SIZE);
+            let encoded = encode_walk(&mut point, generator, count);
+            table.extend(encoded.into_iter().zip(start..));
+        }
+
+        DiscreteLog {
+            bound,
+            step,
+            baby_steps: table,
+            giant_step: -point,
+        }
+    }
+
+    /// Finds `x` in `0..=bound` such that `element = [x]G`.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` if there is no such `x`.
+    pub fn solve(&self, element: &G) -> Option<u64> {
+        let giant_steps = self.bound / self.step + 1;
+        let mut point = G::represent(element);
+
+        for start in (0..giant_steps).step_by(BATCH_SIZE as usize) {
+            let count = (giant_steps - start).min(BATCH_SIZE);
+            let encoded = encode_walk(&mut point, self.giant_step, count);
+
+            for (giant_step, candidate) in (start..).zip(&encoded) {
+                if let Some(baby_step) = self.baby_steps.get(candidate) {
+                    let value = giant_step * self.step + baby_step;
+                    return (value <= self.bound).then_some(value);
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,323 @@
+use elastic_elgamal::dkg::{
+    ParticipantCollectingCommitments, ParticipantCollectingPolynomials,
+    ParticipantExchangingSecrets, PublicInfo,
+};
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::sharing::{ActiveParticipant, Params, PublicKeySet};
+use elastic_elgamal::{PublicKey, SecretKey};
+use postcard::{from_bytes, to_allocvec};
+use rand_legacy::thread_rng;
+
+use crate::Error;
+
+/// Builds the threshold parameters, rejecting values that would make `Params::new` panic.
+fn params(k: usize, n: usize) -> Result<Params, Error> {
+    if n == 0 || k == 0 || k > n {
+        return Err(Error::InvalidInput("Invalid threshold parameters".into()));
+    }
+
+    Ok(Params::new(n, k))
+}
+
+/// Starts the distributed key generation ceremony for one trustee.
+///
+/// Each trustee samples its own secret polynomial, so the election secret key is never
+/// assembled in a single place.
+///
+/// # Arguments
+///
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `index` - 0-based index of this trustee.
+///
+/// # Returns
+///
+/// Returns the serialized trustee state for the commitment phase.
+pub fn generate_participant(k: usize, n: usize, index: usize) -> Result<Vec<u8>, Error> {
+    let params = params(k, n)?;
+
+    if index >= n {
+        return Err(Error::InvalidInput(
+            "Participant index out of bounds".into(),
+        ));
+    }
+
+    let participant =
+        ParticipantCollectingCommitments::<Ristretto>::new(params, index, &mut thread_rng());
+
+    Ok(to_allocvec(&participant)?)
+}
+
+/// Returns the commitment a trustee publishes during the first phase.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `generate_participant`.
+///
+/// # Returns
+///
+/// Returns the 32-byte commitment to the trustee's public key share.
+#[allow(clippy::ptr_arg)]
+pub fn get_commitment(state: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let participant = from_bytes::<ParticipantCollectingCommitments<Ristretto>>(state)?;
+
+    Ok(participant.commitment().to_vec())
+}
+
+/// Inserts the commitments published by every trustee and moves to the polynomial phase.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `generate_participant`.
+/// * `commitments` - Commitments of all `n` trustees ordered by index. The entry of the
+///   calling trustee is ignored.
+///
+/// # Returns
+///
+/// Returns the serialized trustee state for the polynomial phase.
+#[allow(clippy::ptr_arg)]
+pub fn insert_commitments(state: &Vec<u8>, commitments: &Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    let mut participant = from_bytes::<ParticipantCollectingCommitments<Ristretto>>(state)?;
+
+    if commitments.len() != participant.params().shares {
+        return Err(Error::InvalidInput(
+            "Unexpected number of commitments".into(),
+        ));
+    }
+
+    for (i, commitment) in commitments.iter().enumerate() {
+        if i != participant.index() {
+            participant.insert_commitment(i, commitment.as_slice().try_into()?);
+        }
+    }
+
+    Ok(to_allocvec(&participant.finish_commitment_phase())?)
+}
+
+/// Returns the public information a trustee publishes during the second phase.
+///
+/// It contains the public polynomial, a proof of possession of its secret coefficients and
+/// the opening of the commitment from the first phase.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `insert_commitments`.
+///
+/// # Returns
+///
+/// Returns the serialized public information.
+#[allow(clippy::ptr_arg)]
+pub fn get_public_info(state: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let participant = from_bytes::<ParticipantCollectingPolynomials<Ristretto>>(state)?;
+
+    Ok(to_allocvec(&participant.public_info())?)
+}
+
+/// Inserts the public information published by every trustee and moves to the share
+/// exchange phase.
+///
+/// Every entry is checked against the commitment received in the first phase.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `insert_commitments`.
+/// * `infos` - Public information of all `n` trustees ordered by index. The entry of the
+///   calling trustee is ignored.
+///
+/// # Returns
+///
+/// Returns the serialized trustee state for the share exchange phase.
+#[allow(clippy::ptr_arg)]
+pub fn insert_public_infos(state: &Vec<u8>, infos: &Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    let mut participant = from_bytes::<ParticipantCollectingPolynomials<Ristretto>>(state)?;
+
+    if infos.len() != participant.params().shares {
+        return Err(Error::InvalidInput(
+            "Unexpected number of public infos".into(),
+        ));
+    }
+
+    for (i, info) in infos.iter().enumerate() {
+        if i != participant.index() {
+            participant.insert_public_polynomial(i, from_bytes::<PublicInfo<Ristretto>>(info)?)?;
+        }
+    }
+
+    Ok(to_allocvec(&participant.finish_polynomials_phase())?)
+}
+
+/// Returns the secret share a trustee sends to another trustee.
+///
+/// The share must be delivered over a confidential channel, e.g. encrypted with
+/// `ecc::ecc_encrypt` under the recipient's key.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `insert_public_infos`.
+/// * `participant_index` - 0-based index of the receiving trustee.
+///
+/// # Returns
+///
+/// Returns the secret share scalar as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn get_secret_share(state: &Vec<u8>, participant_index: usize) -> Result<Vec<u8>, Error> {
+    let participant = from_bytes::<ParticipantExchangingSecrets<Ristretto>>(state)?;
+
+    if participant_index >= participant.params().shares {
+        return Err(Error::InvalidInput(
+            "Participant index out of bounds".into(),
+        ));
+    }
+
+    let share = participant.secret_share_for_participant(participant_index);
+
+    Ok(Vec::from(share.expose_scalar().as_bytes()))
+}
+
+/// Inserts the secret shares received from every trustee and completes the ceremony.
+///
+/// Every share is checked against the sender's public polynomial.
+///
+/// # Arguments
+///
+/// * `state` - Trustee state returned by `insert_public_infos`.
+/// * `shares` - Secret shares addressed to this trustee by all `n` trustees, ordered by
+///   sender index. The entry of the calling trustee is ignored.
+///
+/// # Returns
+///
+/// Returns the serialized key share of the trustee.
+#[allow(clippy::ptr_arg)]
+pub fn insert_secret_shares(state: &Vec<u8>, shares: &Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    let mut participant = from_bytes::<ParticipantExchangingSecrets<Ristretto>>(state)?;
+
+    if shares.len() != participant.params().shares {
+        return Err(Error::InvalidInput(
+            "Unexpected number of secret shares".into(),
+        ));
+    }
+
+    for (i, share) in shares.iter().enumerate() {
+        if i != participant.index() {
+            let share = SecretKey::<Ristretto>::from_bytes(share)
+                .ok_or_else(|| Error::Key("Invalid secret share".into()))?;
+
+            participant.insert_secret_share(i, share)?;
+        }
+    }
+
+    Ok(to_allocvec(&participant.complete()?)?)
+}
+
+/// Returns the joint election public key held by a trustee after the ceremony.
+///
+/// # Arguments
+///
+/// * `key_share` - Key share returned by `insert_secret_shares`.
+///
+/// # Returns
+///
+/// Returns the joint public key as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn get_shared_public_key(key_share: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let participant = from_bytes::<ActiveParticipant<Ristretto>>(key_share)?;
+
+    Ok(participant.key_set().shared_key().as_bytes().to_vec())
+}
+
+/// Returns the public key set held by a trustee after the ceremony.
+///
+/// The key set holds the joint public key and the public key share of every trustee,
+/// and is needed to verify partial decryptions.
+///
+/// # Arguments
+///
+/// * `key_share` - Key share returned by `insert_secret_shares`.
+///
+/// # Returns
+///
+/// Returns the serialized public key set.
+#[allow(clippy::ptr_arg)]
+pub fn get_key_set(key_share: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let participant = from_bytes::<ActiveParticipant<Ristretto>>(key_share)?;
+
+    Ok(to_allocvec(participant.key_set())?)
+}
+
+/// Rebuilds the public key set from the public information of every trustee.
+fn combine_public_infos(
+    k: usize,
+    n: usize,
+    infos: &Vec<Vec<u8>>,
+) -> Result<PublicKeySet<Ristretto>, Error> {
+    let params = params(k, n)?;
+
+    if infos.len() != n {
+        return Err(Error::InvalidInput(
+            "Unexpected number of public infos".into(),
+        ));
+    }
+
+    let mut participant_keys: Option<Vec<PublicKey<Ristretto>>> = None;
+
+    for info in infos {
+        let info = from_bytes::<PublicInfo<Ristretto>>(info)?;
+        let key_set =
+            PublicKeySet::<Ristretto>::new(params, info.polynomial, &info.proof_of_possession)?;
+
+        participant_keys = match participant_keys {
+            Some(keys) => Some(
+                keys.into_iter()
+                    .zip(key_set.participant_keys())
+                    .map(|(acc, key)| acc + key.clone())
+                    .collect(),
+            ),
+            None => Some(key_set.participant_keys().to_vec()),
+        };
+    }
+
+    match participant_keys {
+        Some(keys) => Ok(PublicKeySet::from_participants(params, keys)?),
+        None => Err(Error::InvalidInput("No public infos".into())),
+    }
+}
+
+/// Derives the public key set from the published trustee information.
+///
+/// This lets any observer verify partial decryptions without trusting a key set
+/// published by the trustees. Each proof of possession is verified, but the commitments
+/// of the first phase are only checked by the trustees themselves.
+///
+/// # Arguments
+///
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `infos` - Public information of all `n` trustees ordered by index.
+///
+/// # Returns
+///
+/// Returns the serialized public key set.
+pub fn derive_key_set(k: usize, n: usize, infos: &Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    Ok(to_allocvec(&combine_public_infos(k, n, infos)?)?)
+}
+
+/// Derives the joint election public key from the published trustee information.
+///
+/// This lets any observer compute the key without taking part in the ceremony. Each
+/// proof of possession is verified, but the commitments of the first phase are only
+/// checked by the trustees themselves.
+///
+/// # Arguments
+///
+/// * `k` - The threshold number of trustees required to decrypt.
+/// * `n` - The total number of trustees.
+/// * `infos` - Public information of all `n` trustees ordered by index.
+///
+/// # Returns
+///
+/// Returns the joint public key as `Vec<u8>`.
+pub fn derive_public_key(k: usize, n: usize, infos: &Vec<Vec<u8>>) -> Result<Vec<u8>, Error> {
+    let key_set = combine_public_infos(k, n, infos)?;
+
+    Ok(key_set.shared_key().as_bytes().to_vec())
+}
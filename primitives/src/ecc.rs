@@ -1,3 +1,5 @@
+use crate::Error;
+
 /// Encrypts a message using ECIES (Elliptic Curve Integrated Encryption Scheme).
 ///
 /// # Arguments
@@ -7,12 +9,10 @@
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing the encrypted message as a `Vec<u8>` on success,
-/// or a `String` error message on failure.
-pub fn ecc_encrypt(pk: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, String> {
-    ecies::encrypt(&pk, &msg)
-        .map(Into::into)
-        .map_err(|e| e.to_string())
+/// Returns a `Result` containing the encrypted message as a `Vec<u8>` on success.
+#[allow(clippy::ptr_arg)]
+pub fn ecc_encrypt(pk: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    ecies::encrypt(pk, msg).map_err(|e| Error::Key(e.to_string()))
 }
 
 /// Decrypts a message using ECIES (Elliptic Curve Integrated Encryption Scheme).
@@ -24,10 +24,8 @@ pub fn ecc_encrypt(pk: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, String> {
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing the decrypted message as a `Vec<u8>` on success,
-/// or a `String` error message on failure.
-pub fn ecc_decrypt(sk: &Vec<u8>, encrypted: &Vec<u8>) -> Result<Vec<u8>, String> {
-    ecies::decrypt(&sk, &encrypted)
-        .map(Into::into)
-        .map_err(|e| e.to_string())
+/// Returns a `Result` containing the decrypted message as a `Vec<u8>` on success.
+#[allow(clippy::ptr_arg)]
+pub fn ecc_decrypt(sk: &Vec<u8>, encrypted: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    ecies::decrypt(sk, encrypted).map_err(|e| Error::Decode(e.to_string()))
 }
@@ -0,0 +1,241 @@
+use alloy_primitives::B256;
+use elastic_elgamal::Ciphertext;
+use elastic_elgamal::group::Ristretto;
+use postcard::from_bytes;
+
+use crate::Error;
+use crate::ballots::{LegacyBallot, Token, encode_acc, encode_token};
+
+/// Magic bytes every envelope starts with.
+pub const MAGIC: [u8; 4] = *b"VOTE";
+
+/// Format version written by this library.
+///
+/// It changes whenever the payload of any kind is encoded differently, so an upgrade can
+/// never silently reinterpret data stored on-chain.
+pub const VERSION: u8 = 1;
+
+/// Schemes a payload is encrypted or signed with.
+pub const RISTRETTO_ELGAMAL: u8 = 1;
+pub const BFV: u8 = 3;
+pub const BN254_ELGAMAL: u8 = 4;
+pub const SECP256K1_ELGAMAL: u8 = 5;
+
+/// Blind signature modes an election issues its tokens in. RSABSSA-SHA384-PSS-Randomized
+/// is the recommended one, and the only one partially blind tokens support.
+pub const RSA_BLIND_PSS: u8 = 2;
+pub const RSA_BLIND_PSS_DETERMINISTIC: u8 = 6;
+
+/// Kinds of payload an envelope holds.
+pub const MULTI_CHOICE_BALLOT: u8 = 1;
+pub const SCORE_BALLOT: u8 = 2;
+pub const RANKED_BALLOT: u8 = 3;
+pub const CONTEST_BALLOT: u8 = 4;
+pub const ACCUMULATOR: u8 = 5;
+pub const TOKEN: u8 = 6;
+pub const ELIGIBILITY_BUNDLE: u8 = 7;
+pub const KEYED_TOKEN: u8 = 8;
+pub const LEGACY_SINGLE_CHOICE_BALLOT: u8 = 9;
+
+/// Length of the header: magic bytes, version, scheme, kind and 32-byte election ID.
+pub const HEADER_LEN: usize = MAGIC.len() + 3 + 32;
+
+/// Scheme every kind of payload is written with, unless the election picked another backend.
+fn scheme(kind: u8) -> u8 {
+    match kind {
+        TOKEN | ELIGIBILITY_BUNDLE | KEYED_TOKEN => RSA_BLIND_PSS,
+        _ => RISTRETTO_ELGAMAL,
+    }
+}
+
+/// Name of a kind of payload, for error messages.
+fn kind_name(kind: u8) -> &'static str {
+    match kind {
+        MULTI_CHOICE_BALLOT => "multi-choice ballot",
+        SCORE_BALLOT => "score ballot",
+        RANKED_BALLOT => "ranked ballot",
+        CONTEST_BALLOT => "contest ballot",
+        ACCUMULATOR => "accumulator",
+        TOKEN => "token",
+        ELIGIBILITY_BUNDLE => "eligibility bundle",
+        KEYED_TOKEN => "keyed token",
+        LEGACY_SINGLE_CHOICE_BALLOT => "legacy single-choice ballot",
+        _ => "unknown payload",
+    }
+}
+
+/// Wraps an encoded payload in an envelope of the current version.
+pub(crate) fn seal(kind: u8, election_id: &B256, payload: &[u8]) -> Vec<u8> {
+    seal_with(scheme(kind), kind, election_id, payload)
+}
+
+/// Wraps a payload encrypted or signed with `scheme` in an envelope of the current version.
+pub(crate) fn seal_with(scheme: u8, kind: u8, election_id: &B256, payload: &[u8]) -> Vec<u8> {
+    let mut blob = Vec::with_capacity(HEADER_LEN + payload.len());
+
+    blob.extend_from_slice(&MAGIC);
+    blob.extend_from_slice(&[VERSION, scheme, kind]);
+    blob.extend_from_slice(election_id.as_slice());
+    blob.extend_from_slice(payload);
+
+    blob
+}
+
+/// Splits the header of an envelope of any version.
+fn header(blob: &[u8]) -> Result<(u8, u8, u8, B256), Error> {
+    if blob.len() < HEADER_LEN || blob[..MAGIC.len()] != MAGIC {
+        return Err(Error::Version(
+            "Blob has no envelope, it must be migrated first".into(),
+        ));
+    }
+
+    let election_id = B256::from_slice(&blob[MAGIC.len() + 3..HEADER_LEN]);
+
+    Ok((blob[4], blob[5], blob[6], election_id))
+}
+
+/// Opens an envelope of the current version that holds a payload of `kind`.
+///
+/// # Returns
+///
+/// Returns the election ID and the encoded payload, or an `Error::Version` if the blob has
+/// no envelope or was written in another version.
+pub(crate) fn open(kind: u8, blob: &[u8]) -> Result<(B256, &[u8]), Error> {
+    open_with(scheme(kind), kind, blob)
+}
+
+/// Opens an envelope of the current version that holds a payload of `kind` written with
+/// `scheme`.
+pub(crate) fn open_with(scheme: u8, kind: u8, blob: &[u8]) -> Result<(B256, &[u8]), Error> {
+    let (version, found_scheme, found_kind, election_id) = header(blob)?;
+
+    if version != VERSION {
+        return Err(Error::Version(format!(
+            "Unsupported format version {}, expected {}",
+            version, VERSION
+        )));
+    }
+    if found_kind != kind {
+        return Err(Error::InvalidInput(format!(
+            "Expected a {}, found a {}",
+            kind_name(kind),
+            kind_name(found_kind)
+        )));
+    }
+    if found_scheme != scheme {
+        return Err(Error::InvalidInput(format!(
+            "Unsupported scheme {} for a {}",
+            found_scheme,
+            kind_name(kind)
+        )));
+    }
+
+    Ok((election_id, &blob[HEADER_LEN..]))
+}
+
+/// Reads the scheme of an envelope, which selects the backend of ballots and accumulators.
+pub(crate) fn scheme_of(blob: &[u8]) -> Result<u8, Error> {
+    header(blob).map(|(_, scheme, _, _)| scheme)
+}
+
+/// Reads the header of a ballot, accumulator or token of any version.
+///
+/// # Arguments
+///
+/// * `blob` - Ballot, accumulator or token.
+///
+/// # Returns
+///
+/// Returns `(version, scheme, kind, election_id)`, or an `Error::Version` if the blob has no
+/// envelope, e.g. because it was written before envelopes were introduced.
+#[allow(clippy::ptr_arg)]
+pub fn inspect(blob: &Vec<u8>) -> Result<(u8, u8, u8, Vec<u8>), Error> {
+    let (version, scheme, kind, election_id) = header(blob)?;
+
+    Ok((version, scheme, kind, election_id.to_vec()))
+}
+
+/// Fails if a blob to migrate already has an envelope.
+fn check_unversioned(blob: &[u8]) -> Result<(), Error> {
+    if header(blob).is_ok() {
+        return Err(Error::InvalidInput("Blob already has an envelope".into()));
+    }
+
+    Ok(())
+}
+
+/// Wraps a single-choice ballot of the first format, written before envelopes were
+/// introduced.
+///
+/// Those ballots, an `EncryptedChoice` of elastic-elgamal with no context, carry proofs that
+/// cannot be re-encoded as those of a `MULTI_CHOICE_BALLOT`. They are wrapped unchanged as a
+/// `LEGACY_SINGLE_CHOICE_BALLOT` instead, which is verified with `ballots::verify_legacy_vote`
+/// and added with `ballots::add_legacy_votes`.
+///
+/// # Arguments
+///
+/// * `ballot` - Bare postcard ballot.
+/// * `election_id` - 32-byte identifier of the election the ballot was cast in.
+///
+/// # Returns
+///
+/// Returns the ballot in the current format as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn migrate_ballot(ballot: &Vec<u8>, election_id: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let election_id = B256::try_from(election_id.as_slice())?;
+    check_unversioned(ballot)?;
+    from_bytes::<LegacyBallot>(ballot)?;
+
+    Ok(seal(LEGACY_SINGLE_CHOICE_BALLOT, &election_id, ballot))
+}
+
+/// Wraps an accumulator written before envelopes were introduced.
+///
+/// Those accumulators are a bare list of ciphertexts, which does not record how many ballots
+/// were summed into it, so the number must be given as `ballots`.
+///
+/// # Arguments
+///
+/// * `acc` - Bare postcard accumulator.
+/// * `election_id` - 32-byte identifier of the election the accumulator belongs to.
+/// * `ballots` - Number of ballots summed into the accumulator.
+///
+/// # Returns
+///
+/// Returns the accumulator in the current format as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn migrate_acc(acc: &Vec<u8>, election_id: &Vec<u8>, ballots: u64) -> Result<Vec<u8>, Error> {
+    let election_id = B256::try_from(election_id.as_slice())?;
+    check_unversioned(acc)?;
+
+    let entries = from_bytes::<Vec<Ciphertext<Ristretto>>>(acc)?;
+
+    encode_acc(&election_id, ballots, 0, &entries)
+}
+
+/// Wraps a voter token written as a bare `(signature, msg_randomizer)` tuple.
+///
+/// Such tokens always carry a message randomizer, so they are migrated as
+/// RSABSSA-SHA384-PSS-Randomized tokens.
+///
+/// # Arguments
+///
+/// * `token` - Bare postcard token.
+/// * `election_id` - 32-byte identifier of the election the token was issued for.
+///
+/// # Returns
+///
+/// Returns the token in the current format as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn migrate_token(token: &Vec<u8>, election_id: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    check_unversioned(token)?;
+
+    let (signature, msg_randomizer) = from_bytes::<Token>(token)?;
+
+    encode_token(
+        &signature,
+        &msg_randomizer.to_vec(),
+        election_id,
+        RSA_BLIND_PSS,
+    )
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+/// Error returned by every fallible function of the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Malformed ABI calldata, postcard, JSON or DER encoding.
+    Decode(String),
+    /// Invalid public, secret or shared key.
+    Key(String),
+    /// Proof or signature that cannot be produced or does not verify.
+    Proof(String),
+    /// Overflowing value or result outside of the expected bounds.
+    Arithmetic(String),
+    /// Arguments that break the rules of the operation, e.g. ballot bounds.
+    InvalidInput(String),
+    /// Blob without an envelope, or written in another format version.
+    Version(String),
+}
+
+impl Error {
+    /// Stable code of the error kind, as written to precompile outputs.
+    ///
+    /// Codes start at 1 and never change meaning, so callers may match on them.
+    pub fn code(&self) -> u8 {
+        match self {
+            Error::Decode(_) => 1,
+            Error::Key(_) => 2,
+            Error::Proof(_) => 3,
+            Error::Arithmetic(_) => 4,
+            Error::InvalidInput(_) => 5,
+            Error::Version(_) => 6,
+        }
+    }
+
+    /// Encodes the error as a precompile output.
+    ///
+    /// The output is a 32-byte word whose first byte is `0xff` and second byte is the
+    /// error code. Its last byte is 0, so callers that only check it for a 1 see the
+    /// input as invalid, and no successful output starts this way.
+    pub fn to_output(&self) -> Vec<u8> {
+        let mut output = vec![0; 32];
+        output[0] = 0xff;
+        output[1] = self.code();
+        output
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Decode(msg) => write!(f, "Decode error: {}", msg),
+            Error::Key(msg) => write!(f, "Key error: {}", msg),
+            Error::Proof(msg) => write!(f, "Proof error: {}", msg),
+            Error::Arithmetic(msg) => write!(f, "Arithmetic error: {}", msg),
+            Error::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            Error::Version(msg) => write!(f, "Version error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<alloy_sol_types::Error> for Error {
+    fn from(e: alloy_sol_types::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<postcard::Error> for Error {
+    fn from(e: postcard::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<std::array::TryFromSliceError> for Error {
+    fn from(e: std::array::TryFromSliceError) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<elastic_elgamal::PublicKeyConversionError> for Error {
+    fn from(e: elastic_elgamal::PublicKeyConversionError) -> Self {
+        Error::Key(e.to_string())
+    }
+}
+
+impl From<elastic_elgamal::sharing::Error> for Error {
+    fn from(e: elastic_elgamal::sharing::Error) -> Self {
+        Error::Key(e.to_string())
+    }
+}
+
+impl From<elastic_elgamal::dkg::Error> for Error {
+    fn from(e: elastic_elgamal::dkg::Error) -> Self {
+        Error::Key(e.to_string())
+    }
+}
+
+impl From<blind_rsa_signatures::Error> for Error {
+    fn from(e: blind_rsa_signatures::Error) -> Self {
+        match e {
+            blind_rsa_signatures::Error::VerificationFailed => Error::Proof(e.to_string()),
+            blind_rsa_signatures::Error::EncodingError => Error::Decode(e.to_string()),
+            _ => Error::Key(e.to_string()),
+        }
+    }
+}
+
+impl From<rsa::Error> for Error {
+    fn from(e: rsa::Error) -> Self {
+        Error::Key(e.to_string())
+    }
+}
+
+impl From<rsa::pkcs8::Error> for Error {
+    fn from(e: rsa::pkcs8::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<rsa::pkcs8::spki::Error> for Error {
+    fn from(e: rsa::pkcs8::spki::Error) -> Self {
+        Error::Decode(e.to_string())
+    }
+}
+
+impl From<rsa::signature::Error> for Error {
+    fn from(e: rsa::signature::Error) -> Self {
+        Error::Proof(e.to_string())
+    }
+}
+
+impl<T> From<alloy_primitives::ruint::FromUintError<T>> for Error {
+    fn from(e: alloy_primitives::ruint::FromUintError<T>) -> Self {
+        Error::Arithmetic(e.to_string())
+    }
+}
+
+/// Runs a precompile entry point without ever aborting the host.
+///
+/// # Arguments
+///
+/// * `entry_point` - ABI entry point, e.g. `ballots::verify_vote`.
+/// * `data` - Calldata passed to the precompile.
+///
+/// # Returns
+///
+/// Returns the output of the entry point, or the output of `Error::to_output` if it failed.
+pub fn run_precompile(
+    entry_point: fn(&Vec<u8>) -> Result<Vec<u8>, Error>,
+    data: &Vec<u8>,
+) -> Vec<u8> {
+    entry_point(data).unwrap_or_else(|e| e.to_output())
+}
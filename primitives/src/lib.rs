@@ -1,13 +1,38 @@
+pub mod error;
+
+pub use error::Error;
+
 pub mod blind_signatures;
 
 pub mod ecc;
 
 pub mod signatures;
 
+pub mod envelope;
+
 pub mod ballots;
 
+pub mod backend;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bfv;
+
+pub mod bn254;
+
+pub mod secp256k1;
+
+pub mod mixnet;
+
+pub mod discrete_log;
+
+pub mod ranked_ballots;
+
+pub mod contests;
+
 pub mod secret_sharing;
 
+pub mod dkg;
+
 pub use alloy_primitives;
 
 pub use alloy_sol_types;
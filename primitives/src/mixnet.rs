@@ -0,0 +1,802 @@
+use alloy_primitives::{B256, Bytes};
+use alloy_sol_types::SolValue;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{Identity, IsIdentity, MultiscalarMul, VartimeMultiscalarMul};
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::sharing::PublicKeySet;
+use elastic_elgamal::{Ciphertext, PublicKey};
+use merlin::Transcript;
+use postcard::{from_bytes, to_allocvec};
+use rand_legacy::seq::SliceRandom;
+use rand_legacy::{CryptoRng, RngCore, thread_rng};
+use rayon::prelude::*;
+
+use crate::Error;
+use crate::ballots::{combine_shares, partial_decryption};
+
+/// Compressed group element or canonical scalar.
+type Encoded = [u8; 32];
+
+/// Number of message bytes carried by one group element.
+pub const CHUNK_SIZE: usize = 30;
+
+/// Encrypted message: one ciphertext per chunk of the padded message.
+type Message = Vec<Ciphertext<Ristretto>>;
+
+/// Commitments `c_i` to the permutation and the chain `ĉ_i` of commitments to the permuted
+/// challenges.
+type ShuffleCommitments = (Vec<Encoded>, Vec<Encoded>);
+
+/// Announcements `t_1, t_2, t_3`, the pair `t_4` of every chunk and `t̂_i` of every message.
+type ShuffleAnnouncements = ([Encoded; 3], Vec<[Encoded; 2]>, Vec<Encoded>);
+
+/// Responses `s_1, s_2, s_3`, `s_4` of every chunk, `ŝ_i` and `s'_i` of every message.
+type ShuffleResponses = ([Encoded; 3], Vec<Encoded>, Vec<Encoded>, Vec<Encoded>);
+
+/// Terelius-Wikström proof that the output of a shuffle re-encrypts a permutation of its
+/// input, in the variant of Haenni et al., "Pseudo-Code Algorithms for Verifiable
+/// Re-Encryption Mix-Nets". Every chunk of a message is re-encrypted under the same
+/// permutation.
+type ShuffleProof = (ShuffleCommitments, ShuffleAnnouncements, ShuffleResponses);
+
+/// Shuffle of a trustee: the re-encrypted permutation of its input and the proof.
+type Shuffle = (Vec<Message>, ShuffleProof);
+
+const SHUFFLE_LABEL: &[u8] = b"mixnet_shuffle";
+const GENERATORS_LABEL: &[u8] = b"mixnet_generators";
+
+/// Maps a chunk of at most `CHUNK_SIZE` bytes to a group element.
+///
+/// The chunk fills bytes 1 to 30 of a candidate encoding whose last byte stays 0, so it
+/// is a canonical field element. About a quarter of the candidates are valid encodings,
+/// hence the first byte is a counter tried until one decompresses. The counter stays even
+/// since odd field elements never decode.
+fn encode_chunk(chunk: &[u8]) -> Result<RistrettoPoint, Error> {
+    let mut bytes = [0; 32];
+    bytes[1..=chunk.len()].copy_from_slice(chunk);
+
+    (0..=u8::MAX)
+        .step_by(2)
+        .find_map(|counter| {
+            bytes[0] = counter;
+            CompressedRistretto(bytes).decompress()
+        })
+        .ok_or_else(|| Error::Arithmetic("Cannot encode the message".into()))
+}
+
+fn decode_chunk(element: &RistrettoPoint) -> [u8; CHUNK_SIZE] {
+    let bytes = element.compress().to_bytes();
+    let mut chunk = [0; CHUNK_SIZE];
+    chunk.copy_from_slice(&bytes[1..=CHUNK_SIZE]);
+    chunk
+}
+
+/// Pads a message with `0x80` and zeros to `width` chunks, so all the messages of a mix
+/// have the same length.
+fn pad(message: &[u8], width: usize) -> Result<Vec<u8>, Error> {
+    let len = width
+        .checked_mul(CHUNK_SIZE)
+        .filter(|len| message.len() < *len)
+        .ok_or_else(|| Error::InvalidInput(format!("Message does not fit in {} chunks", width)))?;
+
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    padded.resize(len, 0);
+
+    Ok(padded)
+}
+
+fn unpad(mut padded: Vec<u8>) -> Option<Vec<u8>> {
+    let end = padded.iter().rposition(|byte| *byte != 0)?;
+
+    (padded[end] == 0x80).then(|| {
+        padded.truncate(end);
+        padded
+    })
+}
+
+/// Encrypts a write-in or free-form answer for the mix-net.
+///
+/// The message is padded to `width` chunks of `CHUNK_SIZE` bytes and every chunk is
+/// encrypted as a group element, so every message of an election must use the same
+/// `width`.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - A byte slice representing the public key of the election.
+/// * `message` - The message to encrypt, shorter than `width * CHUNK_SIZE` bytes.
+/// * `width` - Number of chunks of every message of the election.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the serialized encrypted message.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_message(
+    pub_key_bytes: &Vec<u8>,
+    message: &Vec<u8>,
+    width: usize,
+) -> Result<Vec<u8>, Error> {
+    let rng = &mut thread_rng();
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+
+    let encrypted = pad(message, width)?
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| Ok(receiver.encrypt_element(encode_chunk(chunk)?, rng)))
+        .collect::<Result<Message, Error>>()?;
+
+    Ok(to_allocvec(&encrypted)?)
+}
+
+/// Derives the generators `h, h_1, ..., h_n` of the commitments, whose discrete logs are
+/// unknown to everyone.
+fn generators(count: usize) -> (RistrettoPoint, Vec<RistrettoPoint>) {
+    let mut transcript = Transcript::new(GENERATORS_LABEL);
+    transcript.append_u64(b"n", count as u64);
+
+    let mut generator = || {
+        let mut bytes = [0; 64];
+        transcript.challenge_bytes(b"h", &mut bytes);
+        RistrettoPoint::from_uniform_bytes(&bytes)
+    };
+
+    let h = generator();
+    (h, (0..count).map(|_| generator()).collect())
+}
+
+fn compress(points: &[RistrettoPoint]) -> Vec<Encoded> {
+    points
+        .iter()
+        .map(|point| point.compress().to_bytes())
+        .collect()
+}
+
+fn challenge_scalar(transcript: &mut Transcript, label: &'static [u8]) -> Scalar {
+    let mut bytes = [0; 64];
+    transcript.challenge_bytes(label, &mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn append_messages(transcript: &mut Transcript, label: &'static [u8], messages: &[Message]) {
+    for ciphertext in messages.iter().flatten() {
+        transcript.append_message(label, &ciphertext.to_bytes());
+    }
+}
+
+/// Derives the challenges `u_i` from the statement and the permutation commitments.
+fn permutation_challenges(
+    transcript: &mut Transcript,
+    receiver: &PublicKey<Ristretto>,
+    (inputs, outputs): (&[Message], &[Message]),
+    commitments: &[Encoded],
+) -> Vec<Scalar> {
+    transcript.append_message(b"K", receiver.as_bytes());
+    transcript.append_u64(b"n", inputs.len() as u64);
+    transcript.append_u64(b"width", inputs[0].len() as u64);
+    append_messages(transcript, b"input", inputs);
+    append_messages(transcript, b"output", outputs);
+
+    for commitment in commitments {
+        transcript.append_message(b"c", commitment);
+    }
+
+    (0..inputs.len())
+        .map(|_| challenge_scalar(transcript, b"u"))
+        .collect()
+}
+
+/// Derives the challenge of the proof from the commitment chain and the announcements.
+fn proof_challenge(
+    transcript: &mut Transcript,
+    chain: &[Encoded],
+    (first, pairs, hats): &ShuffleAnnouncements,
+) -> Scalar {
+    let announcements = chain
+        .iter()
+        .chain(first)
+        .chain(pairs.iter().flatten())
+        .chain(hats);
+
+    for announcement in announcements {
+        transcript.append_message(b"t", announcement);
+    }
+
+    challenge_scalar(transcript, b"c")
+}
+
+/// Encryption of zero together with its randomness, which re-encrypts a ciphertext.
+struct Reencryption {
+    ciphertext: Ciphertext<Ristretto>,
+    randomness: Scalar,
+}
+
+/// Builds the ciphertext `(R, X)` from its elements.
+///
+/// elastic-elgamal has no constructor taking them, so it is decoded from its serialized
+/// form, the same one the messages store: both points compressed and prefixed with their
+/// length.
+fn to_ciphertext(
+    random_element: &RistrettoPoint,
+    blinded_element: &RistrettoPoint,
+) -> Result<Ciphertext<Ristretto>, Error> {
+    let random_element = random_element.compress().to_bytes();
+    let blinded_element = blinded_element.compress().to_bytes();
+
+    Ok(from_bytes(&to_allocvec(&(
+        &random_element[..],
+        &blinded_element[..],
+    ))?)?)
+}
+
+/// Encrypts zero as `([r]G, [r]K)`, keeping its randomness for the shuffle proof.
+///
+/// elastic-elgamal keeps the randomness of its own encryptions private.
+fn encrypt_zero<R: CryptoRng + RngCore>(
+    receiver: &PublicKey<Ristretto>,
+    rng: &mut R,
+) -> Result<Reencryption, Error> {
+    let randomness = Scalar::random(rng);
+    let ciphertext = to_ciphertext(
+        &(RISTRETTO_BASEPOINT_POINT * randomness),
+        &(receiver.as_element() * randomness),
+    )?;
+
+    Ok(Reencryption {
+        ciphertext,
+        randomness,
+    })
+}
+
+fn random_scalars<R: CryptoRng + RngCore>(count: usize, rng: &mut R) -> Vec<Scalar> {
+    (0..count).map(|_| Scalar::random(rng)).collect()
+}
+
+/// Starts the transcript of a shuffle proof.
+///
+/// Every shuffle proof commits to the ID of its election, so a chain copied from another
+/// election with the same key and messages fails verification.
+fn shuffle_transcript(election_id: &B256) -> Transcript {
+    let mut transcript = Transcript::new(SHUFFLE_LABEL);
+    transcript.append_message(b"election_id", election_id.as_slice());
+    transcript
+}
+
+/// Re-encrypts a random permutation of `inputs` and proves it.
+///
+/// `inputs` must be non-empty and all its messages must have the same, non-zero width.
+fn prove_shuffle<R: CryptoRng + RngCore>(
+    receiver: &PublicKey<Ristretto>,
+    election_id: &B256,
+    inputs: &[Message],
+    rng: &mut R,
+) -> Result<Shuffle, Error> {
+    let g = RISTRETTO_BASEPOINT_POINT;
+    let k = receiver.as_element();
+    let n = inputs.len();
+    let width = inputs[0].len();
+    let (h, generators) = generators(n);
+
+    // Output `i` re-encrypts input `ψ(i)`
+    let mut permutation = (0..n).collect::<Vec<_>>();
+    permutation.shuffle(rng);
+
+    let zeros = (0..n)
+        .map(|_| {
+            (0..width)
+                .map(|_| encrypt_zero(receiver, rng))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let outputs = permutation
+        .iter()
+        .zip(&zeros)
+        .map(|(j, zeros)| {
+            inputs[*j]
+                .iter()
+                .zip(zeros)
+                .map(|(ciphertext, zero)| *ciphertext + zero.ciphertext)
+                .collect::<Message>()
+        })
+        .collect::<Vec<_>>();
+
+    // Commitments `c_ψ(i) = [r_ψ(i)]G + h_i` to the permutation
+    let randomness = random_scalars(n, rng);
+    let mut commitments = vec![RistrettoPoint::identity(); n];
+
+    for (generator, j) in generators.iter().zip(&permutation) {
+        commitments[*j] = g * randomness[*j] + generator;
+    }
+
+    let commitments = compress(&commitments);
+
+    let mut transcript = shuffle_transcript(election_id);
+    let challenges =
+        permutation_challenges(&mut transcript, receiver, (inputs, &outputs), &commitments);
+    let permuted = permutation
+        .iter()
+        .map(|j| challenges[*j])
+        .collect::<Vec<_>>();
+
+    // Chain `ĉ_i = [r̂_i]G + [u'_i]ĉ_(i-1)` starting from `ĉ_0 = h`
+    let chain_randomness = random_scalars(n, rng);
+    let mut chain = Vec::with_capacity(n + 1);
+    chain.push(h);
+
+    for (r, u) in chain_randomness.iter().zip(&permuted) {
+        let previous = chain[chain.len() - 1];
+        chain.push(g * r + previous * u);
+    }
+
+    let nonces = random_scalars(3, rng);
+    let chunk_nonces = random_scalars(width, rng);
+    let hat_nonces = random_scalars(n, rng);
+    let permuted_nonces = random_scalars(n, rng);
+
+    let first = [
+        g * nonces[0],
+        g * nonces[1],
+        g * nonces[2] + RistrettoPoint::multiscalar_mul(&permuted_nonces, &generators),
+    ];
+
+    let pairs = chunk_nonces
+        .iter()
+        .enumerate()
+        .map(|(chunk, nonce)| {
+            let (blinded, random): (Vec<_>, Vec<_>) = outputs
+                .iter()
+                .map(|message| {
+                    (
+                        *message[chunk].blinded_element(),
+                        *message[chunk].random_element(),
+                    )
+                })
+                .unzip();
+
+            [
+                RistrettoPoint::multiscalar_mul(&permuted_nonces, &blinded) - k * nonce,
+                RistrettoPoint::multiscalar_mul(&permuted_nonces, &random) - g * nonce,
+            ]
+            .map(|point| point.compress().to_bytes())
+        })
+        .collect::<Vec<_>>();
+
+    let hats = hat_nonces
+        .iter()
+        .zip(&permuted_nonces)
+        .zip(&chain)
+        .map(|((hat_nonce, nonce), previous)| g * hat_nonce + previous * nonce)
+        .collect::<Vec<_>>();
+
+    let chain = compress(&chain[1..]);
+    let announcements = (
+        first.map(|point| point.compress().to_bytes()),
+        pairs,
+        compress(&hats),
+    );
+    let c = proof_challenge(&mut transcript, &chain, &announcements);
+
+    // `v_i` is the product of the permuted challenges after `i`
+    let mut weight = Scalar::ONE;
+    let mut chain_sum = Scalar::ZERO;
+
+    for (r, u) in chain_randomness.iter().zip(&permuted).rev() {
+        chain_sum += r * weight;
+        weight *= u;
+    }
+
+    let randomness_sum = randomness.iter().sum::<Scalar>();
+    let weighted_sum = randomness
+        .iter()
+        .zip(&challenges)
+        .map(|(r, u)| r * u)
+        .sum::<Scalar>();
+
+    let responses = [
+        nonces[0] - c * randomness_sum,
+        nonces[1] - c * chain_sum,
+        nonces[2] - c * weighted_sum,
+    ];
+
+    let chunk_responses = chunk_nonces
+        .iter()
+        .enumerate()
+        .map(|(chunk, nonce)| {
+            let reencryption = zeros
+                .iter()
+                .zip(&permuted)
+                .map(|(zeros, u)| zeros[chunk].randomness * u)
+                .sum::<Scalar>();
+
+            (nonce - c * reencryption).to_bytes()
+        })
+        .collect();
+
+    let hat_responses = hat_nonces
+        .iter()
+        .zip(&chain_randomness)
+        .map(|(nonce, r)| (nonce - c * r).to_bytes())
+        .collect();
+
+    let permuted_responses = permuted_nonces
+        .iter()
+        .zip(&permuted)
+        .map(|(nonce, u)| (nonce - c * u).to_bytes())
+        .collect();
+
+    Ok((
+        outputs,
+        (
+            (commitments, chain),
+            announcements,
+            (
+                responses.map(|response| response.to_bytes()),
+                chunk_responses,
+                hat_responses,
+                permuted_responses,
+            ),
+        ),
+    ))
+}
+
+fn decode_point(bytes: &Encoded) -> Option<RistrettoPoint> {
+    CompressedRistretto(*bytes).decompress()
+}
+
+fn decode_scalar(bytes: &Encoded) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(*bytes).into()
+}
+
+fn decode_points(points: &[Encoded]) -> Option<Vec<RistrettoPoint>> {
+    points.iter().map(decode_point).collect()
+}
+
+fn decode_scalars(scalars: &[Encoded]) -> Option<Vec<Scalar>> {
+    scalars.iter().map(decode_scalar).collect()
+}
+
+/// Checks that `Σ [scalars_i] points_i` is the identity.
+fn holds<'a>(
+    scalars: impl IntoIterator<Item = Scalar>,
+    points: impl IntoIterator<Item = &'a RistrettoPoint>,
+) -> bool {
+    RistrettoPoint::vartime_multiscalar_mul(scalars, points).is_identity()
+}
+
+/// Checks the proof that `outputs` re-encrypts a permutation of `inputs`.
+fn verify_shuffle(
+    receiver: &PublicKey<Ristretto>,
+    election_id: &B256,
+    inputs: &[Message],
+    (outputs, proof): &Shuffle,
+) -> bool {
+    let ((commitments, chain), announcements, responses) = proof;
+    let (first, pairs, hats) = announcements;
+    let (first_responses, chunk_responses, hat_responses, permuted_responses) = responses;
+
+    let n = inputs.len();
+    let width = inputs[0].len();
+
+    if outputs.len() != n
+        || outputs.iter().any(|message| message.len() != width)
+        || [commitments, chain, hats, hat_responses, permuted_responses]
+            .iter()
+            .any(|encoded| encoded.len() != n)
+        || pairs.len() != width
+        || chunk_responses.len() != width
+    {
+        return false;
+    }
+
+    let decoded = (
+        decode_points(commitments),
+        decode_points(chain),
+        decode_points(first),
+        decode_points(&pairs.concat()),
+        decode_points(hats),
+        decode_scalars(first_responses),
+        decode_scalars(chunk_responses),
+        decode_scalars(hat_responses),
+        decode_scalars(permuted_responses),
+    );
+
+    let (
+        Some(c_points),
+        Some(chain_points),
+        Some(t),
+        Some(pair_points),
+        Some(hat_points),
+        Some(s),
+        Some(chunk_s),
+        Some(hat_s),
+        Some(permuted_s),
+    ) = decoded
+    else {
+        return false;
+    };
+
+    let mut transcript = shuffle_transcript(election_id);
+    let challenges =
+        permutation_challenges(&mut transcript, receiver, (inputs, outputs), commitments);
+    let c = proof_challenge(&mut transcript, chain, announcements);
+
+    let g = RISTRETTO_BASEPOINT_POINT;
+    let k = receiver.as_element();
+    let (h, generators) = generators(n);
+    let product = challenges.iter().product::<Scalar>();
+    let last = chain_points[n - 1];
+
+    // `t_1 = [c](Σ c_i - Σ h_i) + [s_1]G`
+    let first_holds = holds(
+        [c, -c, s[0], -Scalar::ONE],
+        &[c_points.iter().sum(), generators.iter().sum(), g, t[0]],
+    );
+
+    // `t_2 = [c](ĉ_n - [Π u_i]h) + [s_2]G`
+    let second_holds = holds([c, -c * product, s[1], -Scalar::ONE], &[last, h, g, t[1]]);
+
+    // `t_3 = Σ [c u_i]c_i + [s_3]G + Σ [s'_i]h_i`
+    let third_holds = holds(
+        challenges
+            .iter()
+            .map(|u| c * u)
+            .chain([s[2], -Scalar::ONE])
+            .chain(permuted_s.iter().copied()),
+        c_points.iter().chain([&g, &t[2]]).chain(&generators),
+    );
+
+    // `t_4 = Σ [c u_i]e_i - [s_4](K, G) + Σ [s'_i]e'_i` for every chunk
+    let chunks_hold = (0..width).all(|chunk| {
+        let blinded = |messages: &[Message]| {
+            messages
+                .iter()
+                .map(|message| *message[chunk].blinded_element())
+                .collect::<Vec<_>>()
+        };
+        let random = |messages: &[Message]| {
+            messages
+                .iter()
+                .map(|message| *message[chunk].random_element())
+                .collect::<Vec<_>>()
+        };
+
+        [
+            (blinded(inputs), k, blinded(outputs), pair_points[2 * chunk]),
+            (
+                random(inputs),
+                g,
+                random(outputs),
+                pair_points[2 * chunk + 1],
+            ),
+        ]
+        .iter()
+        .all(|(input, base, output, announcement)| {
+            holds(
+                challenges
+                    .iter()
+                    .map(|u| c * u)
+                    .chain([-chunk_s[chunk], -Scalar::ONE])
+                    .chain(permuted_s.iter().copied()),
+                input.iter().chain([base, announcement]).chain(output),
+            )
+        })
+    });
+
+    // `t̂_i = [c]ĉ_i + [ŝ_i]G + [s'_i]ĉ_(i-1)`
+    let hats_hold = (0..n).all(|i| {
+        let previous = if i == 0 { h } else { chain_points[i - 1] };
+
+        holds(
+            [c, hat_s[i], permuted_s[i], -Scalar::ONE],
+            &[chain_points[i], g, previous, hat_points[i]],
+        )
+    });
+
+    first_holds && second_holds && third_holds && chunks_hold && hats_hold
+}
+
+/// Decodes the encrypted messages of an election, which must all have the same width.
+fn decode_messages(messages: &[Vec<u8>]) -> Result<Vec<Message>, Error> {
+    let messages = messages
+        .iter()
+        .map(|message| from_bytes::<Message>(message))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let width = messages
+        .first()
+        .map(Vec::len)
+        .ok_or_else(|| Error::InvalidInput("No messages to mix".into()))?;
+
+    if width == 0 || messages.iter().any(|message| message.len() != width) {
+        return Err(Error::InvalidInput(
+            "Messages must all have the same number of chunks".into(),
+        ));
+    }
+
+    Ok(messages)
+}
+
+/// Decodes a shuffle chain, where empty bytes stand for a chain without shuffles.
+fn decode_chain(chain: &[u8]) -> Result<Vec<Shuffle>, Error> {
+    if chain.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(from_bytes(chain)?)
+}
+
+/// Shuffles the output of a mix chain and appends the shuffle with its proof.
+///
+/// The trustee re-encrypts a random permutation of the last output of the chain, or of
+/// the submitted messages if the chain is empty. Trustees should check the chain with
+/// `verify_shuffle_chain` before extending it.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - A byte slice representing the public key of the election.
+/// * `election_id` - 32-byte identifier of the election, which the proof commits to.
+/// * `messages` - The encrypted messages submitted by the voters.
+/// * `chain` - The chain returned by the previous trustee, empty for the first one.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the serialized chain extended with the new shuffle.
+#[allow(clippy::ptr_arg)]
+pub fn shuffle(
+    pub_key_bytes: &Vec<u8>,
+    election_id: &Vec<u8>,
+    messages: &Vec<Vec<u8>>,
+    chain: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let rng = &mut thread_rng();
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+    let messages = decode_messages(messages)?;
+    let mut chain = decode_chain(chain)?;
+
+    let inputs = chain.last().map_or(&messages, |(outputs, _)| outputs);
+    let shuffle = prove_shuffle(&receiver, &election_id, inputs, rng)?;
+    chain.push(shuffle);
+
+    Ok(to_allocvec(&chain)?)
+}
+
+/// Verifies every shuffle of a mix chain.
+///
+/// Each shuffle must re-encrypt a permutation of the output of the previous one, the
+/// first one a permutation of the submitted messages. The shuffles are checked in
+/// parallel.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - A byte slice representing the public key of the election.
+/// * `election_id` - 32-byte identifier of the election the chain was shuffled for.
+/// * `messages` - The encrypted messages submitted by the voters.
+/// * `chain` - The chain returned by the last trustee.
+///
+/// # Returns
+///
+/// Returns `true` if the chain has at least one shuffle and every proof is valid, `false`
+/// otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_shuffle_chain(
+    pub_key_bytes: &Vec<u8>,
+    election_id: &Vec<u8>,
+    messages: &Vec<Vec<u8>>,
+    chain: &Vec<u8>,
+) -> Result<bool, Error> {
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+    let election_id = B256::try_from(election_id.as_slice())?;
+    let messages = decode_messages(messages)?;
+    let chain = decode_chain(chain)?;
+
+    if chain.is_empty() {
+        return Ok(false);
+    }
+
+    Ok(chain.par_iter().enumerate().all(|(i, shuffle)| {
+        let inputs = if i == 0 { &messages } else { &chain[i - 1].0 };
+        verify_shuffle(&receiver, &election_id, inputs, shuffle)
+    }))
+}
+
+/// Verifies a mix chain.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(public_key, election_id, messages, chain)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise. Inputs that fail
+/// to decode return an error instead.
+#[allow(clippy::ptr_arg)]
+pub fn verify_mix(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (public_key, election_id, messages, chain) =
+        <(Bytes, B256, Vec<Bytes>, Bytes)>::abi_decode_sequence(data)?;
+
+    let messages = messages
+        .iter()
+        .map(|message| message.to_vec())
+        .collect::<Vec<_>>();
+
+    let mut output = vec![0; 32];
+
+    if verify_shuffle_chain(
+        &public_key.to_vec(),
+        &election_id.to_vec(),
+        &messages,
+        &chain.to_vec(),
+    )? {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Returns the output of the last shuffle of a chain.
+fn mix_output(chain: &[u8]) -> Result<Vec<Message>, Error> {
+    decode_chain(chain)?
+        .pop()
+        .map(|(outputs, _)| outputs)
+        .ok_or_else(|| Error::InvalidInput("The chain has no shuffles".into()))
+}
+
+/// Computes a trustee's partial decryption of the output of a mix chain.
+///
+/// The chain should be checked with `verify_shuffle_chain` first.
+///
+/// # Arguments
+///
+/// * `key_share` - Key share of the trustee, as returned by `dkg::insert_secret_shares`.
+/// * `chain` - The chain returned by the last trustee.
+///
+/// # Returns
+///
+/// Returns the serialized partial decryption as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn decrypt_share(key_share: &Vec<u8>, chain: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let outputs = mix_output(chain)?;
+
+    partial_decryption(key_share, &outputs.concat())
+}
+
+/// Combines the partial decryptions of `k` trustees into the shuffled messages.
+///
+/// Every share is verified before it is used. If any of them is invalid the whole
+/// operation fails, naming the offending trustee.
+///
+/// # Arguments
+///
+/// * `key_set` - Public key set of the election, as returned by `dkg::derive_key_set`.
+/// * `chain` - The chain returned by the last trustee.
+/// * `shares` - Pairs of `(trustee_index, share)` as returned by `decrypt_share`.
+///
+/// # Returns
+///
+/// Returns a `Result` containing the messages in shuffled order, `None` for the ones that
+/// are not padded as `encrypt_message` does.
+#[allow(clippy::ptr_arg)]
+pub fn combine_decryption_shares(
+    key_set: &Vec<u8>,
+    chain: &Vec<u8>,
+    shares: &Vec<(usize, Vec<u8>)>,
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let key_set = from_bytes::<PublicKeySet<Ristretto>>(key_set)?;
+    let outputs = mix_output(chain)?;
+    let width = outputs.first().map_or(0, Vec::len);
+
+    if width == 0 {
+        return Err(Error::InvalidInput(
+            "The last shuffle has no outputs".into(),
+        ));
+    }
+
+    let elements = combine_shares(&key_set, &outputs.concat(), shares)?;
+
+    Ok(elements
+        .chunks(width)
+        .map(|chunks| unpad(chunks.iter().flat_map(decode_chunk).collect()))
+        .collect())
+}
@@ -0,0 +1,398 @@
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, CiphertextWithValue, PublicKey, SumOfSquaresProof};
+use postcard::{from_bytes, to_allocvec};
+use rand_legacy::thread_rng;
+
+use crate::Error;
+
+use crate::ballots::{
+    ProvenRange, accumulate, bound_transcript, context_election, decode_acc, empty_acc, encode_acc,
+    encrypt_ranged_flag, in_election, open_ballot, public_value, verify_ranged_flags,
+};
+use crate::envelope::{RANKED_BALLOT, open, seal};
+
+/// Score of a candidate together with the proof that it is the sum of its matrix row.
+type ProvenScore = (Ciphertext<Ristretto>, SumOfSquaresProof<Ristretto>);
+
+/// Ranked ballot: the off-diagonal entries of the pairwise preference matrix in row-major
+/// order, the proofs that opposite entries add up to 1, the score of every candidate and
+/// the proof that the scores come from a total order.
+pub(crate) type RankedBallot = (
+    Vec<ProvenRange>,
+    Vec<SumOfSquaresProof<Ristretto>>,
+    Vec<ProvenScore>,
+    SumOfSquaresProof<Ristretto>,
+);
+
+/// Transcript labels shared by ranked ballot proofs and their verification.
+const PAIR_LABEL: &[u8] = b"ranked_pair";
+const SCORE_LABEL: &[u8] = b"ranked_score";
+const ORDER_LABEL: &[u8] = b"ranked_order";
+
+/// Position of the entry `(i, j)` among the off-diagonal entries of the `n×n` matrix.
+fn entry(n: usize, i: usize, j: usize) -> usize {
+    i * (n - 1) + if j > i { j - 1 } else { j }
+}
+
+/// Sum of the squared scores of a total order over `n` candidates, `0² + 1² + … + (n-1)²`.
+///
+/// In any other complete and antisymmetric preference matrix some candidates share a
+/// score and the sum is strictly lower.
+fn order_squares(n: usize) -> u64 {
+    let n = n as u64;
+
+    n * (n.saturating_sub(1)) * (2 * n).saturating_sub(1) / 6
+}
+
+/// Generates an accumulator for the pairwise preference matrix initialized to zero.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, election_id)`.
+///
+/// # Returns
+///
+/// Returns the initialized accumulator of `n×n` ciphertexts as a `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn generate_ranked_acc(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, election_id) = <(U256, B256)>::abi_decode_sequence(data)?;
+    let candidate_count: usize = candidate_count.try_into()?;
+    let acc_len = candidate_count
+        .checked_mul(candidate_count)
+        .ok_or_else(|| Error::Arithmetic("Candidate count too large".into()))?;
+
+    empty_acc(&election_id, acc_len)
+}
+
+/// Encrypts a ranking of all the candidates.
+///
+/// The ballot holds the pairwise preference matrix, where entry `(i, j)` is 1 if candidate
+/// `i` is ranked above candidate `j` and 0 otherwise. It proves that every entry is 0 or 1,
+/// that exactly one of `(i, j)` and `(j, i)` is set, and that the row sums are a
+/// permutation of `0..n`, which only holds if the matrix encodes a total order.
+///
+/// # Arguments
+///
+/// * `pub_key_bytes` - Public key of the election authority.
+/// * `ranking` - Candidate indexes from most to least preferred.
+/// * `options_count` - Total number of candidates.
+/// * `context` - Ballot context from `ballots::ballot_context`.
+///
+/// # Returns
+///
+/// Returns the encrypted vote (ballot) as `Result<Vec<u8>, Error>`.
+#[allow(clippy::ptr_arg)]
+pub fn encrypt_ranked_vote(
+    pub_key_bytes: &Vec<u8>,
+    ranking: &Vec<usize>,
+    options_count: usize,
+    context: &Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    if options_count < 2 || ranking.len() != options_count {
+        return Err(Error::InvalidInput(
+            "Ranking must include every candidate".into(),
+        ));
+    }
+
+    let rng = &mut thread_rng();
+    let receiver = PublicKey::<Ristretto>::from_bytes(pub_key_bytes)?;
+    let election_id = context_election(context)?;
+
+    let mut position = vec![options_count; options_count];
+
+    for (rank, &candidate) in ranking.iter().enumerate() {
+        if candidate >= options_count || position[candidate] != options_count {
+            return Err(Error::InvalidInput(
+                "Ranking must be a permutation of the candidates".into(),
+            ));
+        }
+
+        position[candidate] = rank;
+    }
+
+    let n = options_count;
+    let flags = (0..n)
+        .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+        .map(|(i, j)| encrypt_ranged_flag(position[i] < position[j], &receiver, context, rng))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let one = public_value(1, &receiver);
+    let pair_proofs = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            SumOfSquaresProof::new(
+                [&flags[entry(n, i, j)].0, &flags[entry(n, j, i)].0].into_iter(),
+                &one,
+                &receiver,
+                &mut bound_transcript(PAIR_LABEL, context),
+                rng,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let scores = position
+        .iter()
+        .map(|rank| CiphertextWithValue::new((n - 1 - rank) as u64, &receiver, rng).generalize())
+        .collect::<Vec<_>>();
+
+    let score_proofs = scores
+        .iter()
+        .enumerate()
+        .map(|(i, score)| {
+            SumOfSquaresProof::new(
+                flags[i * (n - 1)..(i + 1) * (n - 1)]
+                    .iter()
+                    .map(|(flag, _)| flag),
+                score,
+                &receiver,
+                &mut bound_transcript(SCORE_LABEL, context),
+                rng,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let order_proof = SumOfSquaresProof::new(
+        scores.iter(),
+        &public_value(order_squares(n), &receiver),
+        &receiver,
+        &mut bound_transcript(ORDER_LABEL, context),
+        rng,
+    );
+
+    let flags = flags.into_iter().map(|(_, flag)| flag).collect::<Vec<_>>();
+    let scores = scores
+        .into_iter()
+        .map(Ciphertext::from)
+        .zip(score_proofs)
+        .collect::<Vec<_>>();
+
+    let ballot = to_allocvec(&(flags, pair_proofs, scores, order_proof))?;
+
+    Ok(seal(RANKED_BALLOT, &election_id, &ballot))
+}
+
+/// Checks every proof of a ranked ballot.
+pub(crate) fn verify_ranked(
+    ballot: &RankedBallot,
+    receiver: &PublicKey<Ristretto>,
+    n: usize,
+    context: &[u8],
+) -> bool {
+    let (flags, pair_proofs, scores, order_proof) = ballot;
+
+    if n < 2
+        || n.checked_mul(n - 1) != Some(flags.len())
+        || pair_proofs.len() != flags.len() / 2
+        || scores.len() != n
+    {
+        return false;
+    }
+
+    if !verify_ranged_flags(flags, receiver, context) {
+        return false;
+    }
+
+    let one = Ciphertext::non_blinded(1_u64);
+    let pairs = (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j)));
+
+    for ((i, j), proof) in pairs.zip(pair_proofs) {
+        let entries = [&flags[entry(n, i, j)].0, &flags[entry(n, j, i)].0];
+
+        if proof
+            .verify(
+                entries.into_iter(),
+                &one,
+                receiver,
+                &mut bound_transcript(PAIR_LABEL, context),
+            )
+            .is_err()
+        {
+            return false;
+        }
+    }
+
+    for (i, (score, proof)) in scores.iter().enumerate() {
+        let row = flags[i * (n - 1)..(i + 1) * (n - 1)].iter();
+
+        if proof
+            .verify(
+                row.map(|(ciphertext, _)| ciphertext),
+                score,
+                receiver,
+                &mut bound_transcript(SCORE_LABEL, context),
+            )
+            .is_err()
+        {
+            return false;
+        }
+    }
+
+    order_proof
+        .verify(
+            scores.iter().map(|(score, _)| score),
+            &Ciphertext::non_blinded(order_squares(n)),
+            receiver,
+            &mut bound_transcript(ORDER_LABEL, context),
+        )
+        .is_ok()
+}
+
+/// Verifies the validity of an encrypted ranking using Zero-Knowledge Proofs.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(candidate_count, context, public_key, ballot)`.
+///
+/// # Returns
+///
+/// Returns a 32-byte vector where the last byte is 1 if valid, 0 otherwise.
+#[allow(clippy::ptr_arg)]
+pub fn verify_ranked_vote(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (candidate_count, context, public_key, ballot) =
+        <(U256, Bytes, Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let public_key = PublicKey::<Ristretto>::from_bytes(public_key.as_ref())?;
+    let (election_id, ballot) = open(RANKED_BALLOT, &ballot)?;
+    let ballot = from_bytes::<RankedBallot>(ballot)?;
+
+    let mut output = vec![0; 32];
+
+    if in_election(&election_id, &context)
+        && verify_ranked(&ballot, &public_key, candidate_count.try_into()?, &context)
+    {
+        output[31] = 1;
+    }
+
+    Ok(output)
+}
+
+/// Adds an encrypted ranking to the pairwise preference accumulator.
+///
+/// # Arguments
+///
+/// * `data` - ABI encoded sequence of `(accumulator, ballot)`.
+///
+/// # Returns
+///
+/// Returns the updated accumulator as `Vec<u8>`.
+#[allow(clippy::ptr_arg)]
+pub fn add_ranked_votes(data: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let (acc, ballot) = <(Bytes, Bytes)>::abi_decode_sequence(data)?;
+
+    let (election_id, (count, mut acc)) = decode_acc(&acc)?;
+    let ballot = open_ballot(RANKED_BALLOT, &ballot, &election_id)?;
+    let ballot = from_bytes::<RankedBallot>(ballot)?;
+
+    let n = acc.len().isqrt();
+    if n * n != acc.len() {
+        return Err(Error::InvalidInput(
+            "Accumulator is not a pairwise matrix".into(),
+        ));
+    }
+
+    let matrix = preference_matrix(&ballot, n)?;
+    accumulate(&mut acc, matrix.into_iter())?;
+
+    encode_acc(&election_id, count, 1, &acc)
+}
+
+/// Expands the flags of a ranked ballot into the `n×n` preference matrix, with zeros on the
+/// diagonal.
+///
+/// Fails unless the ballot has exactly one flag per ordered pair of candidates.
+pub(crate) fn preference_matrix(
+    ballot: &RankedBallot,
+    n: usize,
+) -> Result<Vec<Ciphertext<Ristretto>>, Error> {
+    if n.checked_mul(n.saturating_sub(1)) != Some(ballot.0.len()) {
+        return Err(Error::InvalidInput(
+            "Expected one flag per pair of candidates".into(),
+        ));
+    }
+
+    let mut flags = ballot.0.iter();
+
+    Ok((0..n * n)
+        .map(|k| {
+            if k / n == k % n {
+                Ciphertext::zero()
+            } else {
+                flags.next().map_or(Ciphertext::zero(), |(flag, _)| *flag)
+            }
+        })
+        .collect())
+}
+
+/// Checks that a decrypted pairwise matrix has `n×n` entries.
+fn check_matrix(matrix: &[u64], options_count: usize) -> Result<(), Error> {
+    if options_count == 0 || options_count.checked_mul(options_count) != Some(matrix.len()) {
+        return Err(Error::InvalidInput("Invalid pairwise matrix".into()));
+    }
+
+    Ok(())
+}
+
+/// Finds the Condorcet winner of the election.
+///
+/// # Arguments
+///
+/// * `matrix` - Decrypted accumulator, where entry `i * n + j` is the number of voters that
+///   ranked candidate `i` above candidate `j`.
+/// * `options_count` - Total number of candidates.
+///
+/// # Returns
+///
+/// Returns the candidate that beats every other candidate head-to-head, if there is one.
+#[allow(clippy::ptr_arg)]
+pub fn condorcet_winner(matrix: &Vec<u64>, options_count: usize) -> Result<Option<usize>, Error> {
+    check_matrix(matrix, options_count)?;
+
+    let n = options_count;
+
+    Ok((0..n).find(|&i| (0..n).all(|j| j == i || matrix[i * n + j] > matrix[j * n + i])))
+}
+
+/// Computes the winners of the election with the Schulze method.
+///
+/// # Arguments
+///
+/// * `matrix` - Decrypted accumulator, where entry `i * n + j` is the number of voters that
+///   ranked candidate `i` above candidate `j`.
+/// * `options_count` - Total number of candidates.
+///
+/// # Returns
+///
+/// Returns the Schulze winners. There is more than one only in case of a tie.
+#[allow(clippy::ptr_arg)]
+pub fn schulze_winners(matrix: &Vec<u64>, options_count: usize) -> Result<Vec<usize>, Error> {
+    check_matrix(matrix, options_count)?;
+
+    let n = options_count;
+    let mut strength = vec![0; n * n];
+
+    for i in 0..n {
+        for j in 0..n {
+            if i != j && matrix[i * n + j] > matrix[j * n + i] {
+                strength[i * n + j] = matrix[i * n + j];
+            }
+        }
+    }
+
+    // Strongest paths (widest path variant of Floyd-Warshall)
+    for k in 0..n {
+        for i in 0..n {
+            for j in 0..n {
+                if i != j && i != k && j != k {
+                    let path = strength[i * n + k].min(strength[k * n + j]);
+                    strength[i * n + j] = strength[i * n + j].max(path);
+                }
+            }
+        }
+    }
+
+    Ok((0..n)
+        .filter(|&i| (0..n).all(|j| j == i || strength[i * n + j] >= strength[j * n + i]))
+        .collect())
+}
@@ -0,0 +1,413 @@
+use k256::elliptic_curve::bigint::U512;
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::{Field, PrimeField};
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+use merlin::Transcript;
+use rand_legacy::{CryptoRng, RngCore, thread_rng};
+use rayon::prelude::*;
+
+use crate::Error;
+use crate::backend::Backend;
+use crate::ballots::bound_transcript;
+use crate::discrete_log::{self, DiscreteLog};
+use crate::envelope::SECP256K1_ELGAMAL;
+
+/// Transcript labels of ballot proofs.
+const FLAG_LABEL: &[u8] = b"ballot_flag";
+const SUM_LABEL: &[u8] = b"multi_choice_sum";
+
+/// Length of a compressed SEC1 point. The point at infinity is written as zeros.
+const POINT_LEN: usize = 33;
+
+const SCALAR_LEN: usize = 32;
+
+/// Length of an encrypted flag `(r, c)` followed by its proof `[c0, c1, s0, s1]`.
+const FLAG_LEN: usize = 2 * POINT_LEN + 4 * SCALAR_LEN;
+
+/// Length of the proof `[c, s]` that the flags of a ballot add up to 1.
+const SUM_PROOF_LEN: usize = 2 * SCALAR_LEN;
+
+/// ElGamal ciphertext `(r, c) = ([ρ]G, [m]G + [ρ]H)`.
+pub(crate) type Encryption = (ProjectivePoint, ProjectivePoint);
+
+/// Encrypted flag with the proof `[c0, c1, s0, s1]` that it is 0 or 1.
+pub(crate) type ProvenFlag = (Encryption, [Scalar; 4]);
+
+/// Single-choice ballot: one flag per option and the proof `[c, s]` that the flags add up
+/// to 1.
+pub(crate) type Secp256k1Ballot = (Vec<ProvenFlag>, [Scalar; 2]);
+
+fn encode_point(point: &ProjectivePoint) -> [u8; POINT_LEN] {
+    let mut bytes = [0; POINT_LEN];
+
+    if *point != ProjectivePoint::IDENTITY {
+        bytes.copy_from_slice(point.to_affine().to_encoded_point(true).as_bytes());
+    }
+
+    bytes
+}
+
+/// Reads a compressed point, checking that it lies on the curve.
+fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    if bytes.iter().all(|&byte| byte == 0) {
+        return Ok(ProjectivePoint::IDENTITY);
+    }
+
+    EncodedPoint::from_bytes(bytes)
+        .ok()
+        .filter(|encoded| encoded.is_compressed())
+        .and_then(|encoded| AffinePoint::from_encoded_point(&encoded).into_option())
+        .map(ProjectivePoint::from)
+        .ok_or_else(|| Error::Decode("Point is not on the secp256k1 curve".into()))
+}
+
+fn decode_scalar(bytes: &[u8]) -> Result<Scalar, Error> {
+    Scalar::from_repr(FieldBytes::clone_from_slice(bytes))
+        .into_option()
+        .ok_or_else(|| Error::Decode("Scalar exceeds the secp256k1 group order".into()))
+}
+
+fn decode_encryption(bytes: &[u8]) -> Result<Encryption, Error> {
+    Ok((
+        decode_point(&bytes[..POINT_LEN])?,
+        decode_point(&bytes[POINT_LEN..2 * POINT_LEN])?,
+    ))
+}
+
+/// Samples a uniformly random non-zero scalar.
+fn random_scalar(rng: &mut (impl CryptoRng + RngCore)) -> Scalar {
+    loop {
+        let scalar = Scalar::random(&mut *rng);
+
+        if scalar != Scalar::ZERO {
+            return scalar;
+        }
+    }
+}
+
+/// Splits a key into the curve it is tagged with and its encoding on that curve.
+fn untag_key<'a>(key: &'a [u8], len: usize, kind: &str) -> Result<&'a [u8], Error> {
+    match key.split_first() {
+        Some((&SECP256K1_ELGAMAL, key)) if key.len() == len => Ok(key),
+        _ => Err(Error::Key(format!("Not a secp256k1 {}", kind))),
+    }
+}
+
+fn decode_public_key(public_key: &[u8]) -> Result<ProjectivePoint, Error> {
+    decode_point(untag_key(public_key, POINT_LEN, "public key")?)
+        .ok()
+        .filter(|point| *point != ProjectivePoint::IDENTITY)
+        .ok_or_else(|| Error::Key("Invalid secp256k1 public key".into()))
+}
+
+fn decode_secret_key(secret_key: &[u8]) -> Result<Scalar, Error> {
+    decode_scalar(untag_key(secret_key, SCALAR_LEN, "secret key")?)
+        .ok()
+        .filter(|scalar| *scalar != Scalar::ZERO)
+        .ok_or_else(|| Error::Key("Invalid secp256k1 secret key".into()))
+}
+
+/// Appends the points of a proof to its transcript and derives the challenge.
+fn challenge(mut transcript: Transcript, points: &[ProjectivePoint]) -> Scalar {
+    for point in points {
+        transcript.append_message(b"point", &encode_point(point));
+    }
+
+    let mut bytes = [0; 64];
+    transcript.challenge_bytes(b"challenge", &mut bytes);
+
+    <Scalar as Reduce<U512>>::reduce(U512::from_be_slice(&bytes))
+}
+
+/// Encrypts a flag and proves that it is 0 or 1 with a disjunctive Chaum-Pedersen proof.
+fn encrypt_flag(
+    flag: bool,
+    receiver: &ProjectivePoint,
+    context: &[u8],
+    rng: &mut (impl CryptoRng + RngCore),
+) -> (ProvenFlag, Scalar) {
+    let generator = ProjectivePoint::GENERATOR;
+    let randomness = random_scalar(rng);
+    let r = generator * randomness;
+    let mut c = receiver * &randomness;
+    if flag {
+        c += generator;
+    }
+
+    // The branch of the other value is simulated from a chosen challenge and response
+    let real = flag as usize;
+    let fake = 1 - real;
+    let mut challenges = [Scalar::ZERO; 2];
+    let mut responses = [Scalar::ZERO; 2];
+    let mut commitments = [ProjectivePoint::IDENTITY; 4];
+
+    challenges[fake] = random_scalar(rng);
+    responses[fake] = random_scalar(rng);
+    let shifted = if fake == 1 { c - generator } else { c };
+    commitments[2 * fake] = generator * responses[fake] - r * challenges[fake];
+    commitments[2 * fake + 1] = receiver * &responses[fake] - shifted * challenges[fake];
+
+    let nonce = random_scalar(rng);
+    commitments[2 * real] = generator * nonce;
+    commitments[2 * real + 1] = receiver * &nonce;
+
+    let mut points = vec![*receiver, r, c];
+    points.extend(commitments);
+    let challenge = challenge(bound_transcript(FLAG_LABEL, context), &points);
+
+    challenges[real] = challenge - challenges[fake];
+    responses[real] = nonce + challenges[real] * randomness;
+
+    let proof = [challenges[0], challenges[1], responses[0], responses[1]];
+
+    (((r, c), proof), randomness)
+}
+
+/// Checks the proof that an encrypted flag is 0 or 1.
+fn verify_flag(((r, c), proof): &ProvenFlag, receiver: &ProjectivePoint, context: &[u8]) -> bool {
+    let generator = ProjectivePoint::GENERATOR;
+    let [c0, c1, s0, s1] = *proof;
+
+    let points = [
+        *receiver,
+        *r,
+        *c,
+        generator * s0 - r * &c0,
+        receiver * &s0 - c * &c0,
+        generator * s1 - r * &c1,
+        receiver * &s1 - (c - &generator) * c1,
+    ];
+
+    c0 + c1 == challenge(bound_transcript(FLAG_LABEL, context), &points)
+}
+
+/// Points are encoded one by one: the batch normalization of k256 0.13 fails on sums whose
+/// `z` coordinate is not fully reduced.
+impl discrete_log::Group for ProjectivePoint {
+    type Encoding = [u8; POINT_LEN];
+
+    fn identity() -> Self {
+        ProjectivePoint::IDENTITY
+    }
+
+    fn generator() -> Self {
+        ProjectivePoint::GENERATOR
+    }
+
+    fn encode_batch(representatives: &[Self]) -> Vec<Self::Encoding> {
+        representatives.iter().map(encode_point).collect()
+    }
+}
+
+/// Exponential ElGamal over secp256k1, the curve of Ethereum wallet keys, with proofs that
+/// every ballot is well formed.
+///
+/// Keys are tagged with `SECP256K1_ELGAMAL`, the public key as `tag || compressed point`
+/// and the secret key as `tag || scalar`, so they are rejected by the other backends and a
+/// Ristretto key is rejected here. Ballots and accumulators carry the scheme in their
+/// envelope.
+pub struct Secp256k1;
+
+impl Backend for Secp256k1 {
+    const SCHEME: u8 = SECP256K1_ELGAMAL;
+
+    type Ballot = Secp256k1Ballot;
+    type Tally = Vec<Encryption>;
+
+    fn generate_keypair() -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let secret_key = random_scalar(&mut thread_rng());
+        let public_key = ProjectivePoint::GENERATOR * secret_key;
+
+        Ok((
+            [&[SECP256K1_ELGAMAL][..], &encode_point(&public_key)].concat(),
+            [&[SECP256K1_ELGAMAL][..], &secret_key.to_bytes()].concat(),
+        ))
+    }
+
+    fn encrypt(
+        public_key: &[u8],
+        choice: usize,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<Self::Ballot, Error> {
+        if options_count == 0 {
+            return Err(Error::InvalidInput("Invalid number of options".into()));
+        }
+        if choice >= options_count {
+            return Err(Error::InvalidInput("Choice out of bounds".into()));
+        }
+
+        let rng = &mut thread_rng();
+        let receiver = decode_public_key(public_key)?;
+
+        let (flags, randomness): (Vec<_>, Vec<_>) = (0..options_count)
+            .map(|option| encrypt_flag(option == choice, &receiver, context, rng))
+            .unzip();
+
+        // The flags add up to an encryption of 1 with the sum of their randomness
+        let sum_r = randomness.iter().sum::<Scalar>();
+        let total_r = ProjectivePoint::GENERATOR * sum_r;
+        let total_c = receiver * sum_r;
+
+        let nonce = random_scalar(rng);
+        let points = [
+            receiver,
+            total_r,
+            total_c,
+            ProjectivePoint::GENERATOR * nonce,
+            receiver * nonce,
+        ];
+        let challenge = challenge(bound_transcript(SUM_LABEL, context), &points);
+        let response = nonce + challenge * sum_r;
+
+        Ok((flags, [challenge, response]))
+    }
+
+    fn verify(
+        public_key: &[u8],
+        (flags, sum_proof): &Self::Ballot,
+        options_count: usize,
+        context: &[u8],
+    ) -> Result<bool, Error> {
+        let receiver = decode_public_key(public_key)?;
+
+        if flags.len() != options_count
+            || !flags
+                .iter()
+                .all(|flag| verify_flag(flag, &receiver, context))
+        {
+            return Ok(false);
+        }
+
+        let (total_r, total_c) = flags.iter().fold(
+            (ProjectivePoint::IDENTITY, -ProjectivePoint::GENERATOR),
+            |(total_r, total_c), ((r, c), _)| (total_r + r, total_c + c),
+        );
+
+        let [challenge_sum, response] = *sum_proof;
+        let points = [
+            receiver,
+            total_r,
+            total_c,
+            ProjectivePoint::GENERATOR * response - total_r * challenge_sum,
+            receiver * response - total_c * challenge_sum,
+        ];
+
+        Ok(challenge_sum == challenge(bound_transcript(SUM_LABEL, context), &points))
+    }
+
+    fn zero(options_count: usize) -> Result<Self::Tally, Error> {
+        Ok(vec![
+            (ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY);
+            options_count
+        ])
+    }
+
+    fn add(tally: &mut Self::Tally, (flags, _): &Self::Ballot) -> Result<(), Error> {
+        if flags.len() != tally.len() {
+            return Err(Error::InvalidInput(
+                "Ballot does not match the accumulator".into(),
+            ));
+        }
+
+        for ((r, c), ((flag_r, flag_c), _)) in tally.iter_mut().zip(flags) {
+            *r += flag_r;
+            *c += flag_c;
+        }
+
+        Ok(())
+    }
+
+    fn options_count(tally: &Self::Tally) -> usize {
+        tally.len()
+    }
+
+    fn decrypt(
+        secret_key: &[u8],
+        tally: &Self::Tally,
+        ballots: u64,
+        max_score: u64,
+        baby_steps: Option<u64>,
+    ) -> Result<Vec<u64>, Error> {
+        let bound = ballots
+            .checked_mul(max_score)
+            .ok_or_else(|| Error::Arithmetic("Tally bound overflows".into()))?;
+        let secret_key = decode_secret_key(secret_key)?;
+        let solver = match baby_steps {
+            Some(baby_steps) => DiscreteLog::with_baby_steps(bound, baby_steps),
+            None => DiscreteLog::new(bound),
+        };
+
+        tally
+            .par_iter()
+            .map(|(r, c)| {
+                solver.solve(&(c - &(r * &secret_key))).ok_or_else(|| {
+                    Error::Arithmetic("Count exceeds the ballots of the accumulator".into())
+                })
+            })
+            .collect()
+    }
+
+    fn encode_ballot((flags, sum_proof): &Self::Ballot) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::with_capacity(flags.len() * FLAG_LEN + SUM_PROOF_LEN);
+
+        for ((r, c), proof) in flags {
+            bytes.extend(encode_point(r));
+            bytes.extend(encode_point(c));
+            bytes.extend(proof.iter().flat_map(|scalar| scalar.to_bytes()));
+        }
+        bytes.extend(sum_proof.iter().flat_map(|scalar| scalar.to_bytes()));
+
+        Ok(bytes)
+    }
+
+    fn decode_ballot(ballot: &[u8]) -> Result<Self::Ballot, Error> {
+        let flags_len = ballot
+            .len()
+            .checked_sub(SUM_PROOF_LEN)
+            .filter(|len| len.is_multiple_of(FLAG_LEN))
+            .ok_or_else(|| Error::Decode("Malformed secp256k1 ballot".into()))?;
+
+        let (flags, sum_proof) = ballot.split_at(flags_len);
+
+        let flags = flags
+            .chunks(FLAG_LEN)
+            .map(|flag| {
+                let (encryption, proof) = flag.split_at(2 * POINT_LEN);
+                let proof = proof
+                    .chunks(SCALAR_LEN)
+                    .map(decode_scalar)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((
+                    decode_encryption(encryption)?,
+                    [proof[0], proof[1], proof[2], proof[3]],
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let sum_proof = [
+            decode_scalar(&sum_proof[..SCALAR_LEN])?,
+            decode_scalar(&sum_proof[SCALAR_LEN..])?,
+        ];
+
+        Ok((flags, sum_proof))
+    }
+
+    fn encode_tally(tally: &Self::Tally) -> Result<Vec<u8>, Error> {
+        Ok(tally
+            .iter()
+            .flat_map(|(r, c)| [encode_point(r), encode_point(c)])
+            .flatten()
+            .collect())
+    }
+
+    fn decode_tally(tally: &[u8]) -> Result<Self::Tally, Error> {
+        if !tally.len().is_multiple_of(2 * POINT_LEN) {
+            return Err(Error::Decode("Malformed secp256k1 accumulator".into()));
+        }
+
+        tally.chunks(2 * POINT_LEN).map(decode_encryption).collect()
+    }
+}
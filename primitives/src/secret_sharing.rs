@@ -1,5 +1,7 @@
 use sharks::{Share, Sharks};
 
+use crate::Error;
+
 /// Splits a secret into `n` shares using Shamir's Secret Sharing, where `k` shares are required to reconstruct.
 ///
 /// # Arguments
@@ -11,11 +13,16 @@ use sharks::{Share, Sharks};
 /// # Returns
 ///
 /// Returns a vector of shares, where each share is a `Vec<u8>`.
-pub fn split_secret(secret: &Vec<u8>, k: usize, n: usize) -> Vec<Vec<u8>> {
+#[allow(clippy::ptr_arg)]
+pub fn split_secret(secret: &Vec<u8>, k: usize, n: usize) -> Result<Vec<Vec<u8>>, Error> {
+    if k == 0 || k > n || n > u8::MAX as usize {
+        return Err(Error::InvalidInput("Invalid threshold parameters".into()));
+    }
+
     let sharks = Sharks(k as u8);
-    let dealer = sharks.dealer(&secret);
+    let dealer = sharks.dealer(secret);
 
-    dealer.take(n).map(|share| Vec::from(&share)).collect()
+    Ok(dealer.take(n).map(|share| Vec::from(&share)).collect())
 }
 
 /// Recovers a secret from a set of shares using Shamir's Secret Sharing.
@@ -27,15 +34,18 @@ pub fn split_secret(secret: &Vec<u8>, k: usize, n: usize) -> Vec<Vec<u8>> {
 ///
 /// # Returns
 ///
-/// Returns a `Result` containing the recovered secret as a `Vec<u8>` on success,
-/// or a `String` error message on failure.
-pub fn recover_secret(shares: &Vec<Vec<u8>>, k: usize) -> Result<Vec<u8>, String> {
-    let sharks = Sharks(k as u8);
+/// Returns a `Result` containing the recovered secret as a `Vec<u8>` on success.
+#[allow(clippy::ptr_arg)]
+pub fn recover_secret(shares: &Vec<Vec<u8>>, k: usize) -> Result<Vec<u8>, Error> {
+    let k = u8::try_from(k).map_err(|e| Error::InvalidInput(e.to_string()))?;
+    let sharks = Sharks(k);
 
     let parsed_shares = shares
-        .into_iter()
-        .map(|share| Share::try_from(share.as_slice()).unwrap())
-        .collect::<Vec<Share>>();
+        .iter()
+        .map(|share| Share::try_from(share.as_slice()).map_err(|e| Error::Decode(e.into())))
+        .collect::<Result<Vec<Share>, _>>()?;
 
-    sharks.recover(&parsed_shares).map_err(String::from)
+    sharks
+        .recover(&parsed_shares)
+        .map_err(|e| Error::InvalidInput(e.into()))
 }
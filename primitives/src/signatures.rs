@@ -8,7 +8,8 @@ use rsa::{
     Pkcs1v15Encrypt, RsaPublicKey, pkcs8::DecodePrivateKey, pkcs8::DecodePublicKey,
     signature::SignerMut,
 };
-use std::error::Error;
+
+use crate::Error;
 
 /// Generates a new RSA key pair.
 ///
@@ -17,7 +18,7 @@ use std::error::Error;
 /// # Returns
 ///
 /// Returns a `Result` containing the public and secret keys as DER-encoded `Vec<u8>`.
-pub fn generate_rsa_keypair() -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
+pub fn generate_rsa_keypair() -> Result<(Vec<u8>, Vec<u8>), Error> {
     let keypair = KeyPair::<Sha384, PSS, Deterministic>::generate(&mut DefaultRng, 2048)?;
 
     Ok((keypair.pk.to_der()?, keypair.sk.to_der()?))
@@ -33,10 +34,11 @@ pub fn generate_rsa_keypair() -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
 /// # Returns
 ///
 /// Returns the encrypted message as `Vec<u8>`.
-pub fn rsa_encrypt(public_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
-    let public_key = RsaPublicKey::from_public_key_der(&public_key)?;
+#[allow(clippy::ptr_arg)]
+pub fn rsa_encrypt(public_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let public_key = RsaPublicKey::from_public_key_der(public_key)?;
     let mut rng = thread_rng();
-    Ok(public_key.encrypt(&mut rng, Pkcs1v15Encrypt, &msg)?)
+    Ok(public_key.encrypt(&mut rng, Pkcs1v15Encrypt, msg)?)
 }
 
 /// Decrypts a message using RSA-PKCS1v15.
@@ -49,10 +51,11 @@ pub fn rsa_encrypt(public_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Box<d
 /// # Returns
 ///
 /// Returns the decrypted message as `Vec<u8>`.
-pub fn rsa_decrypt(private_key: &Vec<u8>, ecnrypted: &Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
-    let secret_key = RsaPrivateKey::from_pkcs8_der(&private_key)?;
+#[allow(clippy::ptr_arg)]
+pub fn rsa_decrypt(private_key: &Vec<u8>, ecnrypted: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let secret_key = RsaPrivateKey::from_pkcs8_der(private_key)?;
 
-    Ok(secret_key.decrypt(Pkcs1v15Encrypt, &ecnrypted)?)
+    Ok(secret_key.decrypt(Pkcs1v15Encrypt, ecnrypted)?)
 }
 
 /// Signs a message using RSA-PKCS1v15 (SHA-256).
@@ -65,11 +68,12 @@ pub fn rsa_decrypt(private_key: &Vec<u8>, ecnrypted: &Vec<u8>) -> Result<Vec<u8>
 /// # Returns
 ///
 /// Returns the signature as `Vec<u8>`.
-pub fn rsa_sign(private_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
-    let private_key = RsaPrivateKey::from_pkcs8_der(&private_key)?;
+#[allow(clippy::ptr_arg)]
+pub fn rsa_sign(private_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Error> {
+    let private_key = RsaPrivateKey::from_pkcs8_der(private_key)?;
     let mut signing_key = SigningKey::<Sha256>::new(private_key);
 
-    let signature = signing_key.sign(&msg.as_slice());
+    let signature = signing_key.sign(msg.as_slice());
 
     Ok(signature.to_bytes().to_vec())
 }
@@ -85,12 +89,9 @@ pub fn rsa_sign(private_key: &Vec<u8>, msg: &Vec<u8>) -> Result<Vec<u8>, Box<dyn
 /// # Returns
 ///
 /// Returns `Ok(())` if valid, or an error if invalid.
-pub fn rsa_verify(
-    public_key: &Vec<u8>,
-    msg: &Vec<u8>,
-    signature: &Vec<u8>,
-) -> Result<(), Box<dyn Error>> {
-    let public_key = RsaPublicKey::from_public_key_der(&public_key)?;
+#[allow(clippy::ptr_arg)]
+pub fn rsa_verify(public_key: &Vec<u8>, msg: &Vec<u8>, signature: &Vec<u8>) -> Result<(), Error> {
+    let public_key = RsaPublicKey::from_public_key_der(public_key)?;
     let verifying_key = VerifyingKey::<Sha256>::new(public_key);
 
     let decoded = Signature::try_from(signature.as_slice())?;
@@ -0,0 +1,231 @@
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use common::context;
+use primitives::backend::{
+    add_trusted_backend_vote, encrypt_backend_vote, generate_backend_acc, generate_backend_keypair,
+};
+use primitives::ballots::{add_votes, add_votes_batch, decrypt_result, generate_acc, verify_vote};
+use primitives::envelope::{BFV, BN254_ELGAMAL, RISTRETTO_ELGAMAL, SECP256K1_ELGAMAL, inspect};
+use primitives::error::Error;
+
+fn acc(options_count: usize, scheme: u8) -> Vec<u8> {
+    let input = (
+        U256::from(options_count),
+        B256::from([1; 32]),
+        U256::from(scheme),
+    )
+        .abi_encode_sequence();
+    generate_backend_acc(&input).unwrap()
+}
+
+fn verify_input(
+    scheme: u8,
+    options_count: usize,
+    context: &[u8],
+    pk: &[u8],
+    ballot: &[u8],
+) -> Vec<u8> {
+    (
+        U256::from(options_count),
+        Bytes::from(context.to_vec()),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+        U256::from(scheme),
+    )
+        .abi_encode_sequence()
+}
+
+fn batch_input(acc: &[u8], ballots: &[Vec<u8>], verify: bool, max: u64, pk: &[u8]) -> Vec<u8> {
+    (
+        Bytes::from(acc.to_vec()),
+        ballots.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+        verify,
+        U256::from(1),
+        U256::from(max),
+        vec![Bytes::from(context(1)); ballots.len()],
+        Bytes::from(pk.to_vec()),
+    )
+        .abi_encode_sequence()
+}
+
+/// Tests the full voting flow of an election tallied with BFV.
+#[test]
+fn test_bfv_flow() {
+    let (pk, sk) = generate_backend_keypair(BFV).unwrap();
+    let options_count = 4;
+
+    let mut acc = acc(options_count, BFV);
+    assert_eq!(inspect(&acc).unwrap().1, BFV);
+
+    for choice in [0, 2, 2, 3, 2] {
+        let ballot = encrypt_backend_vote(BFV, &pk, choice, options_count, &context(1)).unwrap();
+        assert_eq!(inspect(&ballot).unwrap().1, BFV);
+
+        // There is no proof to check that the ballot holds a single vote
+        let input = verify_input(BFV, options_count, &context(1), &pk, &ballot);
+        assert!(matches!(verify_vote(&input), Err(Error::Proof(_))));
+
+        // Nor can it be added on-chain
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        assert!(matches!(add_votes(&input), Err(Error::InvalidInput(_))));
+        acc = add_trusted_backend_vote(&input).unwrap();
+    }
+
+    assert_eq!(
+        decrypt_result(&sk, &acc, 1, None).unwrap(),
+        vec![1, 0, 3, 1]
+    );
+
+    // A ciphertext packs at most one count per coefficient of its polynomials
+    let input = (U256::from(2049), B256::from([1; 32]), U256::from(BFV)).abi_encode_sequence();
+    assert!(matches!(
+        generate_backend_acc(&input),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests that ballots are checked against the backend of their election.
+#[test]
+fn test_backend_selection() {
+    let options_count = 3;
+    let (elgamal_pk, elgamal_sk) = generate_backend_keypair(RISTRETTO_ELGAMAL).unwrap();
+    let (bfv_pk, _) = generate_backend_keypair(BFV).unwrap();
+
+    // ElGamal accumulators are the ones `generate_acc` writes
+    let input = (U256::from(options_count), B256::from([1; 32])).abi_encode_sequence();
+    assert_eq!(
+        acc(options_count, RISTRETTO_ELGAMAL),
+        generate_acc(&input).unwrap()
+    );
+
+    let ballot = encrypt_backend_vote(
+        RISTRETTO_ELGAMAL,
+        &elgamal_pk,
+        1,
+        options_count,
+        &context(1),
+    )
+    .unwrap();
+    let input = (
+        Bytes::from(acc(options_count, RISTRETTO_ELGAMAL)),
+        Bytes::from(ballot),
+    )
+        .abi_encode_sequence();
+    let elgamal_acc = add_votes(&input).unwrap();
+    assert_eq!(
+        decrypt_result(&elgamal_sk, &elgamal_acc, 1, None).unwrap(),
+        vec![0, 1, 0]
+    );
+
+    // A BFV ballot added to an ElGamal accumulator
+    let ballot = encrypt_backend_vote(BFV, &bfv_pk, 1, options_count, &context(1)).unwrap();
+    let input = (
+        Bytes::from(acc(options_count, RISTRETTO_ELGAMAL)),
+        Bytes::from(ballot.clone()),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(add_votes(&input), Err(Error::InvalidInput(_))));
+
+    // A BFV ballot cast in an ElGamal election, with either key
+    let input = verify_input(
+        RISTRETTO_ELGAMAL,
+        options_count,
+        &context(1),
+        &bfv_pk,
+        &ballot,
+    );
+    assert!(matches!(verify_vote(&input), Err(Error::InvalidInput(_))));
+    let input = verify_input(
+        RISTRETTO_ELGAMAL,
+        options_count,
+        &context(1),
+        &elgamal_pk,
+        &ballot,
+    );
+    assert!(matches!(verify_vote(&input), Err(Error::InvalidInput(_))));
+
+    // A BFV ballot of another election
+    let input = verify_input(BFV, options_count, &context(9), &bfv_pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+
+    // Unknown schemes and malformed keys
+    assert!(matches!(
+        generate_backend_keypair(9),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        encrypt_backend_vote(BFV, &elgamal_pk, 1, options_count, &context(1)),
+        Err(Error::Decode(_) | Error::Key(_))
+    ));
+    assert!(matches!(
+        encrypt_backend_vote(BFV, &bfv_pk, options_count, options_count, &context(1)),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests adding many ballots at once with the backend of the accumulator.
+#[test]
+fn test_backend_votes_batch() {
+    let options_count = 3;
+
+    for scheme in [RISTRETTO_ELGAMAL, BN254_ELGAMAL, SECP256K1_ELGAMAL] {
+        let (pk, sk) = generate_backend_keypair(scheme).unwrap();
+        let (bfv_pk, _) = generate_backend_keypair(BFV).unwrap();
+        let encrypt = |choice, options_count, context| {
+            encrypt_backend_vote(scheme, &pk, choice, options_count, &context).unwrap()
+        };
+
+        let mut malformed = encrypt(1, options_count, context(1));
+        malformed.truncate(malformed.len() - 1);
+
+        let ballots = [
+            encrypt(0, options_count, context(1)),
+            encrypt(2, options_count, context(1)),
+            encrypt(1, options_count + 1, context(1)),
+            malformed,
+            encrypt(2, options_count, context(9)),
+            encrypt_backend_vote(BFV, &bfv_pk, 1, options_count, &context(1)).unwrap(),
+        ];
+
+        let add = |verify| {
+            let input = batch_input(&acc(options_count, scheme), &ballots, verify, 1, &pk);
+            let (acc, rejected) =
+                <(Bytes, Vec<U256>)>::abi_decode_sequence(&add_votes_batch(&input).unwrap())
+                    .unwrap();
+            assert_eq!(inspect(&acc.to_vec()).unwrap().1, scheme);
+
+            let rejected = rejected.iter().map(|i| i.to::<usize>()).collect::<Vec<_>>();
+            (
+                decrypt_result(&sk, &acc.to_vec(), 1, None).unwrap(),
+                rejected,
+            )
+        };
+
+        assert_eq!(add(false), (vec![1, 0, 1], vec![2, 3, 4, 5]));
+        assert_eq!(add(true), (vec![1, 0, 1], vec![2, 3, 4, 5]));
+
+        // Backends other than `ElGamal` only verify single-choice ballots
+        let input = batch_input(&acc(options_count, scheme), &ballots, true, 2, &pk);
+        if scheme == RISTRETTO_ELGAMAL {
+            assert!(add_votes_batch(&input).is_ok());
+        } else {
+            assert!(matches!(
+                add_votes_batch(&input),
+                Err(Error::InvalidInput(_))
+            ));
+        }
+    }
+
+    // BFV ballots cannot be verified, so their batches are refused like single ballots
+    let (pk, _) = generate_backend_keypair(BFV).unwrap();
+    let ballots = [encrypt_backend_vote(BFV, &pk, 1, options_count, &context(1)).unwrap()];
+    for verify in [false, true] {
+        let input = batch_input(&acc(options_count, BFV), &ballots, verify, 1, &pk);
+        assert!(matches!(
+            add_votes_batch(&input),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -1,7 +1,8 @@
-use alloy_primitives::{Bytes, U256};
+use alloy_primitives::{B256, Bytes, U256};
 use alloy_sol_types::SolValue;
 use primitives::ballots::{
-    add_votes, decrypt_result, encrypt_vote, generate_acc, generate_elgamal_keypair,
+    add_votes, ballot_context, decrypt_result, encrypt_vote, generate_acc,
+    generate_elgamal_keypair,
 };
 use rand_legacy::rngs::StdRng;
 use rand_legacy::Rng;
@@ -54,6 +55,7 @@ fn get_cpu_times() -> (f64, f64) {
 
 // --- Helpers (REQ-2) ---
 
+#[allow(clippy::too_many_arguments)]
 fn record_timing(
     test_name: &str,
     phase: &str,
@@ -112,9 +114,9 @@ struct StressResult {
 
 fn run_voting_flow(test_name: &str, candidates: usize, votes: &[usize]) -> StressResult {
     let (pk, sk) = generate_elgamal_keypair();
-    let encoded_count = U256::from(candidates).abi_encode();
-    let mut acc = generate_acc(&encoded_count);
-    let max_count = votes.len() as u64;
+    let encoded_count = (U256::from(candidates), B256::ZERO).abi_encode_sequence();
+    let mut acc = generate_acc(&encoded_count).unwrap();
+    let context = ballot_context(&vec![0; 32], &vec![0; 20], &vec![]).expect("invalid context");
 
     // Phase 1: Encrypt all votes
     let encrypted = measure_phase(
@@ -124,7 +126,7 @@ fn run_voting_flow(test_name: &str, candidates: usize, votes: &[usize]) -> Stres
         || {
             votes
                 .iter()
-                .map(|&choice| encrypt_vote(&pk, choice, candidates).expect("encrypt failed"))
+                .map(|&choice| encrypt_vote(&pk, choice, candidates, &context).expect("encrypt failed"))
                 .collect::<Vec<Vec<u8>>>()
         },
     );
@@ -138,7 +140,7 @@ fn run_voting_flow(test_name: &str, candidates: usize, votes: &[usize]) -> Stres
             for ballot in &encrypted {
                 let input =
                     (Bytes::from(acc.clone()), Bytes::from(ballot.clone())).abi_encode_sequence();
-                acc = add_votes(&input);
+                acc = add_votes(&input).unwrap();
             }
         },
     );
@@ -147,8 +149,8 @@ fn run_voting_flow(test_name: &str, candidates: usize, votes: &[usize]) -> Stres
     let tallies = measure_phase(
         test_name,
         "decryption",
-        &format!("{} candidates, max_count={}", candidates, max_count),
-        || decrypt_result(&sk, &acc, max_count).expect("decrypt failed"),
+        &format!("{} candidates, {} ballots", candidates, votes.len()),
+        || decrypt_result(&sk, &acc, 1, None).expect("decrypt failed"),
     );
 
     StressResult { tallies }
@@ -198,6 +200,7 @@ fn test_stress_many_candidates() {
 }
 
 #[test]
+#[allow(clippy::needless_range_loop, clippy::useless_vec)]
 fn test_stress_random() {
     let mut rng = StdRng::seed_from_u64(42);
     let mut expected = vec![0u64; RANDOM_CANDIDATES];
@@ -217,16 +220,17 @@ fn test_stress_random() {
 }
 
 #[test]
+#[allow(clippy::needless_range_loop)]
 fn test_stress_zero_votes() {
     let (_, sk) = generate_elgamal_keypair();
-    let encoded_count = U256::from(ZERO_VOTE_CANDIDATES).abi_encode();
-    let acc = generate_acc(&encoded_count);
+    let encoded_count = (U256::from(ZERO_VOTE_CANDIDATES), B256::ZERO).abi_encode_sequence();
+    let acc = generate_acc(&encoded_count).unwrap();
 
     let tallies = measure_phase(
         "test_stress_zero_votes",
         "decryption",
-        &format!("{} candidates, max_count=0", ZERO_VOTE_CANDIDATES),
-        || decrypt_result(&sk, &acc, 0).expect("decrypt failed"),
+        &format!("{} candidates, no ballots", ZERO_VOTE_CANDIDATES),
+        || decrypt_result(&sk, &acc, 1, None).expect("decrypt failed"),
     );
 
     for i in 0..ZERO_VOTE_CANDIDATES {
@@ -1,11 +1,23 @@
-use alloy_primitives::{Bytes, U256};
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256, keccak256};
 use alloy_sol_types::SolValue;
-use elastic_elgamal::app::{EncryptedChoice, SingleChoice};
+use common::context;
 use elastic_elgamal::group::Ristretto;
-use postcard::from_bytes;
+use elastic_elgamal::{Ciphertext, SumOfSquaresProof};
+use postcard::{from_bytes, to_allocvec};
 use primitives::ballots::{
-    add_votes, decrypt_result, encrypt_vote, generate_acc, generate_elgamal_keypair,
+    add_multi_votes, add_score_votes, add_votes, add_votes_batch, audit_ballot, ballot_context,
+    ballot_tracker, decrypt_result, decrypt_result_with_proof, encrypt_multi_vote,
+    encrypt_multi_vote_for_audit, encrypt_score_vote, encrypt_vote, encrypt_vote_for_audit,
+    generate_acc, generate_elgamal_keypair, tracker_code, verify_multi_vote, verify_score_vote,
+    verify_tally, verify_vote, verify_votes_batch,
 };
+use primitives::envelope::{HEADER_LEN, RISTRETTO_ELGAMAL};
+use primitives::error::{Error, run_precompile};
+
+type Flag = (Ciphertext<Ristretto>, Vec<u8>);
+type SumProof = SumOfSquaresProof<Ristretto>;
 
 /// Tests direct serialization of encrypted votes using postcard.
 #[test]
@@ -14,23 +26,24 @@ fn test_serialization_isolation() {
     let options_count = 3;
 
     // Encrypt
-    let vote_bytes = encrypt_vote(&pk, 0, options_count).expect("encrypt failed");
+    let vote_bytes = encrypt_vote(&pk, 0, options_count, &context(1)).expect("encrypt failed");
 
-    // Deserialize directly
-    let _: EncryptedChoice<Ristretto, SingleChoice> =
-        from_bytes(&vote_bytes).expect("deserialization failed");
+    // Deserialize the payload directly
+    let _: (Vec<Flag>, Vec<Flag>, SumProof) =
+        from_bytes(&vote_bytes[HEADER_LEN..]).expect("deserialization failed");
 }
 
 /// Tests serialization of the accumulator.
 #[test]
 fn test_acc_serialization() {
     let candidate_count = U256::from(3);
-    let encoded_count = candidate_count.abi_encode();
-    let acc = generate_acc(&encoded_count);
+    let encoded_count = (candidate_count, B256::from([1; 32])).abi_encode_sequence();
+    let acc = generate_acc(&encoded_count).unwrap();
 
-    // Deserialize directly
-    let _: Vec<elastic_elgamal::Ciphertext<Ristretto>> =
-        from_bytes(&acc).expect("acc deserialization failed");
+    // Deserialize the payload directly
+    let (ballots, entries): (u64, Vec<Ciphertext<Ristretto>>) =
+        from_bytes(&acc[HEADER_LEN..]).expect("acc deserialization failed");
+    assert_eq!((ballots, entries.len()), (0, 3));
 }
 
 /// Tests ABI encoding/decoding of accumulator and ballot sequences.
@@ -63,37 +76,669 @@ fn test_ballot_flow() {
     let options_count = 3;
 
     // Initialize Accumulator
-    let encoded_count = candidate_count.abi_encode();
-    let acc = generate_acc(&encoded_count);
+    let encoded_count = (candidate_count, B256::from([1; 32])).abi_encode_sequence();
+    let acc = generate_acc(&encoded_count).unwrap();
 
     // Vote for option 0
-    let vote0 = encrypt_vote(&pk, 0, options_count).expect("failed to encrypt vote 0");
+    let vote0 = encrypt_vote(&pk, 0, options_count, &context(1)).expect("failed to encrypt vote 0");
 
     // Vote for option 1
-    let vote1 = encrypt_vote(&pk, 1, options_count).expect("failed to encrypt vote 1");
+    let vote1 = encrypt_vote(&pk, 1, options_count, &context(1)).expect("failed to encrypt vote 1");
 
     // Vote for option 0 again
-    let vote2 = encrypt_vote(&pk, 0, options_count).expect("failed to encrypt vote 2");
+    let vote2 = encrypt_vote(&pk, 0, options_count, &context(1)).expect("failed to encrypt vote 2");
 
     // Add votes
     let mut current_acc = acc;
 
     // Add vote 0
     let input0 = (Bytes::from(current_acc.clone()), Bytes::from(vote0)).abi_encode_sequence();
-    current_acc = add_votes(&input0);
+    current_acc = add_votes(&input0).unwrap();
 
     // Add vote 1
     let input1 = (Bytes::from(current_acc.clone()), Bytes::from(vote1)).abi_encode_sequence();
-    current_acc = add_votes(&input1);
+    current_acc = add_votes(&input1).unwrap();
 
     // Add vote 2
     let input2 = (Bytes::from(current_acc.clone()), Bytes::from(vote2)).abi_encode_sequence();
-    current_acc = add_votes(&input2);
+    current_acc = add_votes(&input2).unwrap();
 
-    let results = decrypt_result(&sk, &current_acc, 1 << 5).expect("failed to decrypt");
+    let results = decrypt_result(&sk, &current_acc, 1, None).expect("failed to decrypt");
 
     // Expected: Option 0: 2 votes, Option 1: 1 vote, Option 2: 0 votes
     assert_eq!(results[0], 2);
     assert_eq!(results[1], 1);
     assert_eq!(results[2], 0);
 }
+
+/// Tests that a decrypted tally can be verified against the accumulator.
+#[test]
+fn test_tally_proof() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let mut acc =
+        generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+            .unwrap();
+    for choice in [2, 0, 2] {
+        let vote =
+            encrypt_vote(&pk, choice, options_count, &context(1)).expect("failed to encrypt vote");
+        let input = (Bytes::from(acc), Bytes::from(vote)).abi_encode_sequence();
+        acc = add_votes(&input).unwrap();
+    }
+
+    let (counts, proof) = decrypt_result_with_proof(&sk, &acc, 1, None).expect("failed to decrypt");
+    assert_eq!(counts, vec![1, 0, 2]);
+
+    let encode = |counts: &[u64]| {
+        (
+            Bytes::from(pk.clone()),
+            Bytes::from(acc.clone()),
+            counts.iter().map(|c| U256::from(*c)).collect::<Vec<U256>>(),
+            Bytes::from(proof.clone()),
+        )
+            .abi_encode_sequence()
+    };
+
+    // Correct tally
+    assert_eq!(verify_tally(&encode(&counts)).unwrap()[31], 1);
+
+    // Tampered tally
+    assert_eq!(verify_tally(&encode(&[2, 0, 1])).unwrap()[31], 0);
+    assert_eq!(verify_tally(&encode(&[1, 0])).unwrap()[31], 0);
+
+    // Proof under another key
+    let (other_pk, _) = generate_elgamal_keypair();
+    let input = (
+        Bytes::from(other_pk),
+        Bytes::from(acc.clone()),
+        counts.iter().map(|c| U256::from(*c)).collect::<Vec<U256>>(),
+        Bytes::from(proof.clone()),
+    )
+        .abi_encode_sequence();
+    assert_eq!(verify_tally(&input).unwrap()[31], 0);
+
+    // Malformed proof
+    let input = (
+        Bytes::from(pk.clone()),
+        Bytes::from(acc.clone()),
+        counts.iter().map(|c| U256::from(*c)).collect::<Vec<U256>>(),
+        Bytes::from(vec![0xff; 7]),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(verify_tally(&input), Err(Error::Decode(_))));
+}
+
+/// Tests approval and k-of-n ballots: bounds checks, verification and aggregation.
+#[test]
+fn test_multi_choice_flow() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 4;
+
+    let verify = |ballot: &Vec<u8>, min: usize, max: usize| {
+        let input = (
+            U256::from(options_count),
+            U256::from(min),
+            U256::from(max),
+            Bytes::from(context(1)),
+            Bytes::from(pk.clone()),
+            Bytes::from(ballot.clone()),
+        )
+            .abi_encode_sequence();
+        verify_multi_vote(&input).unwrap()[31]
+    };
+
+    // Up to 2 options
+    let up_to_two = encrypt_multi_vote(&pk, &vec![0, 3], options_count, 0, 2, &context(1)).unwrap();
+    let blank = encrypt_multi_vote(&pk, &vec![], options_count, 0, 2, &context(1)).unwrap();
+    assert_eq!(verify(&up_to_two, 0, 2), 1);
+    assert_eq!(verify(&blank, 0, 2), 1);
+
+    // Exactly 2 options
+    let exactly_two =
+        encrypt_multi_vote(&pk, &vec![1, 3], options_count, 2, 2, &context(1)).unwrap();
+    assert_eq!(verify(&exactly_two, 2, 2), 1);
+
+    // Ballots are only valid under the bounds they were cast for
+    assert_eq!(verify(&up_to_two, 2, 2), 0);
+    assert_eq!(verify(&exactly_two, 0, 2), 0);
+    assert_eq!(verify(&up_to_two, 0, 3), 0);
+
+    // Invalid selections cannot be encrypted
+    assert!(encrypt_multi_vote(&pk, &vec![0, 1, 2], options_count, 0, 2, &context(1)).is_err());
+    assert!(encrypt_multi_vote(&pk, &vec![0], options_count, 2, 2, &context(1)).is_err());
+    assert!(encrypt_multi_vote(&pk, &vec![1, 1], options_count, 0, 2, &context(1)).is_err());
+    assert!(encrypt_multi_vote(&pk, &vec![4], options_count, 0, 2, &context(1)).is_err());
+    assert!(encrypt_multi_vote(&pk, &vec![], options_count, 3, 2, &context(1)).is_err());
+
+    let mut acc =
+        generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+            .unwrap();
+    for ballot in [up_to_two, blank, exactly_two] {
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        acc = add_multi_votes(&input).unwrap();
+    }
+
+    let results = decrypt_result(&sk, &acc, 1, None).expect("failed to decrypt");
+    assert_eq!(results, vec![1, 1, 0, 2]);
+}
+
+/// Tests the Benaloh challenge: a spoiled ballot reveals its choices, and only with its own
+/// randomness, in its own election and with valid proofs.
+#[test]
+fn test_audit_ballot() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 4;
+
+    let audit = |pk: &Vec<u8>, ballot: &Vec<u8>, randomness: &Vec<u8>| {
+        audit_ballot(pk, ballot, randomness, options_count, 1, 1, &context(1))
+    };
+
+    let (ballot, randomness) = encrypt_vote_for_audit(&pk, 2, options_count, &context(1)).unwrap();
+    assert_eq!(audit(&pk, &ballot, &randomness).unwrap(), vec![2]);
+
+    // An audited ballot is an ordinary ballot
+    let input = (
+        U256::from(options_count),
+        Bytes::from(context(1)),
+        Bytes::from(pk.clone()),
+        Bytes::from(ballot.clone()),
+        U256::from(RISTRETTO_ELGAMAL),
+    )
+        .abi_encode_sequence();
+    assert_eq!(verify_vote(&input).unwrap()[31], 1);
+
+    let (multi, multi_randomness) =
+        encrypt_multi_vote_for_audit(&pk, &vec![3, 0], options_count, 0, 3, &context(1)).unwrap();
+    assert_eq!(
+        audit_ballot(
+            &pk,
+            &multi,
+            &multi_randomness,
+            options_count,
+            0,
+            3,
+            &context(1)
+        )
+        .unwrap(),
+        vec![0, 3]
+    );
+
+    // The randomness of another ballot does not open this one
+    let (_, other_randomness) = encrypt_vote_for_audit(&pk, 2, options_count, &context(1)).unwrap();
+    assert!(matches!(
+        audit(&pk, &ballot, &other_randomness),
+        Err(Error::Proof(_))
+    ));
+    assert!(matches!(
+        audit(&pk, &ballot, &multi_randomness),
+        Err(Error::Proof(_))
+    ));
+    assert!(matches!(
+        audit(&pk, &ballot, &randomness[..16].to_vec()),
+        Err(Error::Decode(_))
+    ));
+
+    // Nor does another key
+    let (other_pk, _) = generate_elgamal_keypair();
+    assert!(matches!(
+        audit(&other_pk, &ballot, &randomness),
+        Err(Error::Proof(_))
+    ));
+
+    // A ballot of another election, or checked against other bounds, is not audited
+    let other_context = context(9);
+    assert!(matches!(
+        audit_ballot(
+            &pk,
+            &ballot,
+            &randomness,
+            options_count,
+            1,
+            1,
+            &other_context
+        ),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        audit_ballot(
+            &pk,
+            &multi,
+            &multi_randomness,
+            options_count,
+            1,
+            1,
+            &context(1)
+        ),
+        Err(Error::Proof(_))
+    ));
+
+    // Nor is a ballot whose proofs do not hold, even though its flags match the randomness
+    let (choices, padding, _): (Vec<Flag>, Vec<Flag>, SumProof) =
+        from_bytes(&ballot[HEADER_LEN..]).unwrap();
+    let (_, _, other_proof): (Vec<Flag>, Vec<Flag>, SumProof) =
+        from_bytes(&multi[HEADER_LEN..]).unwrap();
+    let payload = to_allocvec(&(choices, padding, other_proof)).unwrap();
+    let forged = [&ballot[..HEADER_LEN], &payload].concat();
+    assert!(matches!(
+        audit(&pk, &forged, &randomness),
+        Err(Error::Proof(_))
+    ));
+}
+
+/// Tests that ballot trackers form a chain and render as short codes.
+#[test]
+fn test_ballot_tracker() {
+    let election_id = vec![1; 32];
+    let genesis = vec![0; 32];
+    let ballot = b"ballot".to_vec();
+
+    let first = ballot_tracker(&election_id, &genesis, &ballot).unwrap();
+    assert_eq!(
+        first,
+        keccak256(
+            (B256::from([1; 32]), B256::ZERO, Bytes::from(ballot.clone())).abi_encode_sequence()
+        )
+        .to_vec()
+    );
+    assert_eq!(tracker_code(&first).unwrap(), "YQ0S-VZCC-A5RM-2QWH");
+
+    // The same ballot gets another tracker further down the chain or in another election
+    let second = ballot_tracker(&election_id, &first, &ballot).unwrap();
+    assert_eq!(tracker_code(&second).unwrap(), "AH09-TS42-3TB1-7SXC");
+    assert_ne!(
+        ballot_tracker(&vec![2; 32], &genesis, &ballot).unwrap(),
+        first
+    );
+
+    assert!(ballot_tracker(&vec![1; 31], &genesis, &ballot).is_err());
+    assert!(ballot_tracker(&election_id, &Vec::new(), &ballot).is_err());
+    assert!(tracker_code(&vec![0; 20]).is_err());
+}
+
+/// Tests that a multi-choice ballot cannot hide extra selections in the padding.
+#[test]
+fn test_multi_choice_rejects_tampered_ballot() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let ballot = encrypt_multi_vote(&pk, &vec![0], options_count, 1, 2, &context(1)).unwrap();
+    let other = encrypt_multi_vote(&pk, &vec![0, 1], options_count, 1, 2, &context(1)).unwrap();
+
+    type Ballot = (Vec<Flag>, Vec<Flag>, SumProof);
+    let (mut choices, padding, sum_proof): Ballot = from_bytes(&ballot[HEADER_LEN..]).unwrap();
+    let (other_choices, _, _): Ballot = from_bytes(&other[HEADER_LEN..]).unwrap();
+
+    // Swap in a valid flag for option 1 without updating the padding
+    choices[1] = other_choices[1].clone();
+    let payload = to_allocvec(&(choices, padding, sum_proof)).unwrap();
+    let tampered = [&ballot[..HEADER_LEN], &payload].concat();
+
+    let input = (
+        U256::from(options_count),
+        U256::from(1),
+        U256::from(2),
+        Bytes::from(context(1)),
+        Bytes::from(pk.clone()),
+        Bytes::from(tampered),
+    )
+        .abi_encode_sequence();
+    assert_eq!(verify_multi_vote(&input).unwrap()[31], 0);
+}
+
+fn verify_score(pk: &[u8], options_count: usize, max: u64, budget: u64, ballot: &[u8]) -> u8 {
+    let input = (
+        U256::from(options_count),
+        U256::from(max),
+        U256::from(budget),
+        Bytes::from(context(1)),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+    )
+        .abi_encode_sequence();
+
+    verify_score_vote(&input).unwrap()[31]
+}
+
+/// Tests the score voting flow with and without a budget.
+#[test]
+fn test_score_flow() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+    let max = 5;
+
+    let stars = encrypt_score_vote(&pk, &vec![5, 0, 3], max, None, &context(1)).unwrap();
+    assert_eq!(verify_score(&pk, options_count, max, 0, &stars), 1);
+
+    // Cumulative voting: 4 points to distribute
+    let cumulative = encrypt_score_vote(&pk, &vec![1, 3, 0], max, Some(4), &context(1)).unwrap();
+    assert_eq!(verify_score(&pk, options_count, max, 4, &cumulative), 1);
+
+    // Ballots are only valid under the bounds they were cast for
+    assert_eq!(verify_score(&pk, options_count, max, 4, &stars), 0);
+    assert_eq!(verify_score(&pk, options_count, max, 0, &cumulative), 0);
+    assert_eq!(verify_score(&pk, options_count, 3, 0, &stars), 0);
+    assert_eq!(verify_score(&pk, 2, max, 0, &stars), 0);
+
+    // Invalid scores cannot be encrypted
+    assert!(encrypt_score_vote(&pk, &vec![6, 0, 0], max, None, &context(1)).is_err());
+    assert!(encrypt_score_vote(&pk, &vec![3, 2, 0], max, Some(4), &context(1)).is_err());
+    assert!(encrypt_score_vote(&pk, &vec![], max, None, &context(1)).is_err());
+    assert!(encrypt_score_vote(&pk, &vec![0, 0, 0], 0, None, &context(1)).is_err());
+
+    let mut acc =
+        generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+            .unwrap();
+    for ballot in [stars, cumulative] {
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        acc = add_score_votes(&input).unwrap();
+    }
+
+    let results = decrypt_result(&sk, &acc, max, None).unwrap();
+    assert_eq!(results, vec![6, 3, 3]);
+}
+
+/// Tests that the scores of a budgeted ballot cannot be swapped for higher ones.
+#[test]
+fn test_score_rejects_budget_overrun() {
+    let (pk, _) = generate_elgamal_keypair();
+    let (options_count, max, budget) = (2, 3, 3);
+
+    let ballot = encrypt_score_vote(&pk, &vec![1, 2], max, Some(budget), &context(1)).unwrap();
+    let other = encrypt_score_vote(&pk, &vec![3, 0], max, Some(budget), &context(1)).unwrap();
+
+    type Proven = (Ciphertext<Ristretto>, Vec<u8>);
+    type Ballot = (
+        Vec<Proven>,
+        Option<(
+            Vec<Proven>,
+            Vec<SumOfSquaresProof<Ristretto>>,
+            Proven,
+            SumOfSquaresProof<Ristretto>,
+        )>,
+    );
+    let (mut scores, budget_proof): Ballot = from_bytes(&ballot[HEADER_LEN..]).unwrap();
+    let (other_scores, _): Ballot = from_bytes(&other[HEADER_LEN..]).unwrap();
+
+    // A valid score of 3 for the first candidate brings the total to 5
+    scores[0] = other_scores[0].clone();
+    let payload = to_allocvec(&(scores, budget_proof)).unwrap();
+    let tampered = [&ballot[..HEADER_LEN], &payload].concat();
+    assert_eq!(verify_score(&pk, options_count, max, budget, &tampered), 0);
+}
+
+/// Tests that a ballot only verifies in the context it was cast for.
+#[test]
+fn test_ballot_bound_to_context() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let election_id = vec![1; 32];
+    let contract = vec![2; 20];
+    let token = b"token".to_vec();
+
+    let context = ballot_context(&election_id, &contract, &token).unwrap();
+    let ballot = encrypt_vote(&pk, 1, options_count, &context).unwrap();
+
+    let verify = |context: Vec<u8>| {
+        let input = (
+            U256::from(options_count),
+            Bytes::from(context),
+            Bytes::from(pk.clone()),
+            Bytes::from(ballot.clone()),
+            U256::from(RISTRETTO_ELGAMAL),
+        )
+            .abi_encode_sequence();
+        verify_vote(&input).unwrap()[31]
+    };
+
+    assert_eq!(verify(context), 1);
+
+    // The same ballot copied by another voter
+    let copied = ballot_context(&election_id, &contract, &b"other token".to_vec()).unwrap();
+    assert_eq!(verify(copied), 0);
+
+    // The same ballot replayed in another election or contract
+    let other_election = ballot_context(&vec![9; 32], &contract, &token).unwrap();
+    let other_contract = ballot_context(&election_id, &vec![9; 20], &token).unwrap();
+    assert_eq!(verify(other_election), 0);
+    assert_eq!(verify(other_contract), 0);
+
+    // Malformed identifiers
+    assert!(ballot_context(&vec![1; 31], &contract, &token).is_err());
+    assert!(ballot_context(&election_id, &vec![2; 32], &token).is_err());
+}
+
+/// Tests that verifying a batch flags exactly the invalid ballots.
+#[test]
+fn test_verify_votes_batch() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let contexts = (0..5)
+        .map(|i| ballot_context(&vec![1; 32], &vec![2; 20], &vec![i; 8]).unwrap())
+        .collect::<Vec<_>>();
+    let mut ballots = contexts
+        .iter()
+        .enumerate()
+        .map(|(i, context)| encrypt_vote(&pk, i % options_count, options_count, context).unwrap())
+        .collect::<Vec<_>>();
+
+    let verify = |contexts: &[Vec<u8>], ballots: &[Vec<u8>]| {
+        let input = (
+            U256::from(options_count),
+            U256::from(1),
+            U256::from(1),
+            contexts
+                .iter()
+                .cloned()
+                .map(Bytes::from)
+                .collect::<Vec<_>>(),
+            Bytes::from(pk.clone()),
+            ballots.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+        )
+            .abi_encode_sequence();
+        <Vec<bool>>::abi_decode(&verify_votes_batch(&input).unwrap()).unwrap()
+    };
+
+    assert_eq!(verify(&contexts, &ballots), vec![true; 5]);
+    assert_eq!(verify(&[], &[]), Vec::<bool>::new());
+
+    // A ballot cast under another token, one with a flag of 2 and one that does not decode
+    ballots[1] = encrypt_vote(&pk, 0, options_count, &contexts[0]).unwrap();
+    let (mut choices, padding, sum_proof): (Vec<Flag>, Vec<Flag>, SumProof) =
+        from_bytes(&ballots[2][HEADER_LEN..]).unwrap();
+    choices[0].0 = choices[0].0 + choices[0].0;
+    let payload = to_allocvec(&(choices, padding, sum_proof)).unwrap();
+    ballots[2] = [&ballots[2][..HEADER_LEN], &payload].concat();
+    ballots[3].truncate(10);
+
+    // A ballot whose only invalid proof is the range proof of one of its flags
+    let (mut choices, padding, sum_proof): (Vec<Flag>, Vec<Flag>, SumProof) =
+        from_bytes(&ballots[4][HEADER_LEN..]).unwrap();
+    choices[0].1 = choices[1].1.clone();
+    let payload = to_allocvec(&(choices, padding, sum_proof)).unwrap();
+    ballots[4] = [&ballots[4][..HEADER_LEN], &payload].concat();
+
+    assert_eq!(
+        verify(&contexts, &ballots),
+        vec![true, false, false, false, false]
+    );
+
+    let input = (
+        U256::from(options_count),
+        U256::from(1),
+        U256::from(1),
+        vec![Bytes::from(contexts[0].clone())],
+        Bytes::from(pk.clone()),
+        Vec::<Bytes>::new(),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(
+        verify_votes_batch(&input),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests adding many ballots at once, with and without verification.
+#[test]
+fn test_add_votes_batch() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+    let acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+        .unwrap();
+
+    let other_context = context(9);
+    let mut malformed = encrypt_vote(&pk, 0, options_count, &context(1)).unwrap();
+    malformed.truncate(10);
+
+    let ballots = [
+        encrypt_vote(&pk, 0, options_count, &context(1)).unwrap(),
+        encrypt_vote(&pk, 2, options_count, &context(1)).unwrap(),
+        encrypt_vote(&pk, 1, options_count + 1, &context(1)).unwrap(),
+        malformed,
+        encrypt_vote(&pk, 2, options_count, &other_context).unwrap(),
+    ];
+
+    let add = |verify: bool| {
+        let input = (
+            Bytes::from(acc.clone()),
+            ballots.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+            verify,
+            U256::from(1),
+            U256::from(1),
+            vec![Bytes::from(context(1)); ballots.len()],
+            Bytes::from(pk.clone()),
+        )
+            .abi_encode_sequence();
+        let output = add_votes_batch(&input).unwrap();
+        let (acc, rejected) = <(Bytes, Vec<U256>)>::abi_decode_sequence(&output).unwrap();
+
+        let rejected = rejected.iter().map(|i| i.to::<usize>()).collect::<Vec<_>>();
+        (
+            decrypt_result(&sk, &acc.to_vec(), 1, None).unwrap(),
+            rejected,
+        )
+    };
+
+    // The ballot cast in another election is left out even without verification
+    assert_eq!(add(false), (vec![1, 0, 1], vec![2, 3, 4]));
+    assert_eq!(add(true), (vec![1, 0, 1], vec![2, 3, 4]));
+
+    let input = (
+        Bytes::from(acc.clone()),
+        ballots.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+        true,
+        U256::from(1),
+        U256::from(1),
+        Vec::<Bytes>::new(),
+        Bytes::from(pk.clone()),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(
+        add_votes_batch(&input),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests adding many multi-choice ballots at once under their choice bounds.
+#[test]
+fn test_add_multi_votes_batch() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+    let acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+        .unwrap();
+
+    let ballots = [
+        encrypt_multi_vote(&pk, &vec![0, 2], options_count, 2, 2, &context(1)).unwrap(),
+        encrypt_vote(&pk, 1, options_count, &context(1)).unwrap(),
+    ];
+
+    let add = |min: u64, max: u64| {
+        let input = (
+            Bytes::from(acc.clone()),
+            ballots.iter().cloned().map(Bytes::from).collect::<Vec<_>>(),
+            true,
+            U256::from(min),
+            U256::from(max),
+            vec![Bytes::from(context(1)); ballots.len()],
+            Bytes::from(pk.clone()),
+        )
+            .abi_encode_sequence();
+        let output = add_votes_batch(&input).unwrap();
+        let (acc, rejected) = <(Bytes, Vec<U256>)>::abi_decode_sequence(&output).unwrap();
+
+        let rejected = rejected.iter().map(|i| i.to::<usize>()).collect::<Vec<_>>();
+        (
+            decrypt_result(&sk, &acc.to_vec(), 1, None).unwrap(),
+            rejected,
+        )
+    };
+
+    // The 2-of-3 ballot is valid, the single-choice one selects too few options
+    assert_eq!(add(2, 2), (vec![1, 0, 1], vec![1]));
+    assert_eq!(add(1, 1), (vec![0, 1, 0], vec![0]));
+    assert_eq!(add(1, 2), (vec![0, 0, 0], vec![0, 1]));
+}
+
+/// Tests that malformed calldata is reported as an error instead of aborting.
+#[test]
+fn test_malformed_calldata() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let acc = generate_acc(&(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence())
+        .unwrap();
+    let ballot = encrypt_vote(&pk, 1, options_count, &context(1)).unwrap();
+
+    let input = |pk: &[u8], ballot: &[u8]| {
+        (
+            U256::from(options_count),
+            Bytes::from(context(1)),
+            Bytes::from(pk.to_vec()),
+            Bytes::from(ballot.to_vec()),
+            U256::from(RISTRETTO_ELGAMAL),
+        )
+            .abi_encode_sequence()
+    };
+
+    assert!(matches!(verify_vote(&vec![1, 2, 3]), Err(Error::Decode(_))));
+    assert!(matches!(
+        verify_vote(&input(&pk, &ballot[..HEADER_LEN + 10])),
+        Err(Error::Decode(_))
+    ));
+    assert!(matches!(
+        verify_vote(&input(&pk, &ballot[..10])),
+        Err(Error::Version(_))
+    ));
+    assert!(matches!(
+        verify_vote(&input(&[0xff; 32], &ballot)),
+        Err(Error::Key(_))
+    ));
+
+    let too_many = (
+        U256::MAX,
+        Bytes::from(context(1)),
+        Bytes::from(pk.clone()),
+        Bytes::from(ballot.clone()),
+        U256::from(RISTRETTO_ELGAMAL),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(verify_vote(&too_many), Err(Error::Arithmetic(_))));
+
+    // A ballot for more options than the accumulator holds
+    let wide = encrypt_vote(&pk, 1, options_count + 1, &context(1)).unwrap();
+    let add_input = (Bytes::from(acc), Bytes::from(wide)).abi_encode_sequence();
+    assert!(matches!(add_votes(&add_input), Err(Error::InvalidInput(_))));
+
+    // Precompile output
+    let output = run_precompile(verify_vote, &vec![1, 2, 3]);
+    assert_eq!(output.len(), 32);
+    assert_eq!(output[0], 0xff);
+    assert_eq!(output[1], 1);
+    assert_eq!(output[31], 0);
+
+    let output = run_precompile(verify_vote, &input(&pk, &ballot));
+    assert_eq!(output[0], 0);
+    assert_eq!(output[31], 1);
+}
@@ -1,6 +1,47 @@
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use blind_rsa_signatures::pbrsa::PartiallyBlindPublicKey;
+use blind_rsa_signatures::{PSS, Randomized, Sha384};
+use primitives::ballots::{
+    encode_keyed_token, encode_token, verify as verify_token,
+    verify_by_key_id as verify_keyed_token, verify_eligibility,
+    verify_partially_blind as verify_partially_blind_token,
+};
 use primitives::blind_signatures::{
-    ExportedKeyPair, create_request, generate_rsa_keypair, sign, unblind, verify,
+    ExportedKeyPair, ExportedThresholdKey, combine_threshold_partials,
+    create_partially_blind_request, create_request, deal_threshold_key, eligibility_nullifier,
+    encode_eligibility_bundle, generate_rsa_keypair, key_id, nullifier, register_key, resolve_key,
+    retire_key, sign, sign_partially_blind, sign_threshold_partial, unblind,
+    unblind_partially_blind, verify, verify_by_key_id, verify_eligibility_bundle,
+    verify_partially_blind, verify_threshold_partial,
 };
+use primitives::envelope::{RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC};
+use primitives::error::Error;
+
+fn pem(public_key: &[u8]) -> String {
+    blind_rsa_signatures::PublicKey::<Sha384, PSS, Randomized>::from_der(public_key)
+        .unwrap()
+        .to_pem()
+        .unwrap()
+}
+
+/// Issues a token for `msg` in the blind signature mode of `scheme`, returning its
+/// signature and message randomizer.
+fn issue(keypair: &ExportedKeyPair, msg: &Vec<u8>, scheme: u8) -> (Vec<u8>, Vec<u8>) {
+    let request = create_request(&keypair.public, msg, scheme).unwrap();
+    let blinded_signature = sign(&keypair.private, &request.blind_message).unwrap();
+    let signature = unblind(
+        &keypair.public,
+        msg,
+        request.secret,
+        &request.msg_randomizer,
+        blinded_signature,
+        scheme,
+    )
+    .unwrap();
+
+    (signature, request.msg_randomizer)
+}
 
 #[test]
 fn test_blind_signature_flow() {
@@ -15,15 +56,497 @@ fn test_blind_signature_flow() {
     let msg = &b"blind message".to_vec();
 
     // 1. Create Request
-    let (blinded_msg, secret) = create_request(public_key, msg).expect("failed to create request");
+    let request = create_request(public_key, msg, RSA_BLIND_PSS).expect("failed to create request");
+    assert_eq!(request.msg_randomizer.len(), 32);
 
     // 2. Sign (Blindly)
-    let blinded_signature = sign(&private_key, &blinded_msg).expect("failed to sign blinded msg");
+    let blinded_signature =
+        sign(private_key, &request.blind_message).expect("failed to sign blinded msg");
 
     // 3. Unblind
-    let signature = unblind(public_key, msg, secret, blinded_signature).expect("failed to unblind");
+    let randomizer = &request.msg_randomizer;
+    let signature = unblind(
+        public_key,
+        msg,
+        request.secret,
+        randomizer,
+        blinded_signature,
+        RSA_BLIND_PSS,
+    )
+    .expect("failed to unblind");
 
     // 4. Verify
-    let is_valid = verify(public_key, signature, msg).expect("verification failed");
+    let is_valid = verify(
+        public_key,
+        signature.clone(),
+        randomizer,
+        msg,
+        RSA_BLIND_PSS,
+    )
+    .expect("verification failed");
     assert!(is_valid);
+
+    // The signature covers the randomizer, and does not verify in the other mode
+    assert!(
+        !verify(
+            public_key,
+            signature.clone(),
+            &vec![0; 32],
+            msg,
+            RSA_BLIND_PSS
+        )
+        .unwrap()
+    );
+    assert!(matches!(
+        verify(
+            public_key,
+            signature,
+            randomizer,
+            msg,
+            RSA_BLIND_PSS_DETERMINISTIC
+        ),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests tokens of both blind signature modes through the ABI `verify` entry point.
+#[test]
+fn test_token_modes() {
+    let keypair = generate_rsa_keypair().unwrap();
+    let msg = &b"voter".to_vec();
+    let election_id = &vec![1; 32];
+
+    for scheme in [RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC] {
+        let (signature, randomizer) = issue(&keypair, msg, scheme);
+        assert_eq!(randomizer.is_empty(), scheme == RSA_BLIND_PSS_DETERMINISTIC);
+        assert!(verify(&keypair.public, signature.clone(), &randomizer, msg, scheme).unwrap());
+
+        let token = encode_token(&signature, &randomizer, election_id, scheme).unwrap();
+        let input = |msg: &Vec<u8>| {
+            (
+                pem(&keypair.public),
+                Bytes::from(token.clone()),
+                Bytes::from(msg.clone()),
+                U256::from(scheme),
+            )
+                .abi_encode_sequence()
+        };
+        let output = verify_token(&input(msg)).unwrap();
+        assert_eq!(output[31], 1);
+        assert_eq!(output[32..], nullifier(msg, &signature)[..]);
+        assert_eq!(
+            verify_token(&input(&b"other".to_vec())).unwrap(),
+            vec![0; 64]
+        );
+    }
+
+    // A randomized signature sealed as a deterministic token, with its randomizer dropped
+    let (signature, randomizer) = issue(&keypair, msg, RSA_BLIND_PSS);
+    let token = encode_token(
+        &signature,
+        &vec![],
+        election_id,
+        RSA_BLIND_PSS_DETERMINISTIC,
+    )
+    .unwrap();
+    let input = |token: &Vec<u8>, msg: &Vec<u8>, scheme: u8| {
+        (
+            pem(&keypair.public),
+            Bytes::from(token.clone()),
+            Bytes::from(msg.clone()),
+            U256::from(scheme),
+        )
+            .abi_encode_sequence()
+    };
+    assert_eq!(
+        verify_token(&input(&token, msg, RSA_BLIND_PSS_DETERMINISTIC)).unwrap(),
+        vec![0; 64]
+    );
+
+    // The same signature is a deterministic one over the randomizer and the message, but a
+    // deterministic token is rejected in a randomized election
+    let prefixed = [randomizer.as_slice(), msg].concat();
+    assert_eq!(
+        verify_token(&input(&token, &prefixed, RSA_BLIND_PSS_DETERMINISTIC)).unwrap()[31],
+        1
+    );
+    assert!(matches!(
+        verify_token(&input(&token, &prefixed, RSA_BLIND_PSS)),
+        Err(Error::InvalidInput(_))
+    ));
+
+    assert!(matches!(
+        create_request(&keypair.public, msg, 9),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests a blind signature produced by 3 out of 5 registrars.
+#[test]
+fn test_threshold_blind_signature_flow() {
+    // Dealing needs a modulus of safe primes, as partially blind keys have
+    let private_key = include_bytes!("data/partially_blind_key.der").to_vec();
+    let ExportedThresholdKey {
+        public: public_key,
+        key_set,
+        shares,
+    } = deal_threshold_key(&private_key, 3, 5).expect("failed to deal key");
+    assert_eq!(shares.len(), 5);
+
+    // Regular keys are not a product of safe primes
+    let regular = generate_rsa_keypair().unwrap();
+    assert!(matches!(
+        deal_threshold_key(&regular.private, 3, 5),
+        Err(Error::Key(_))
+    ));
+
+    let msg = &b"blind message".to_vec();
+    let request =
+        create_request(&public_key, msg, RSA_BLIND_PSS).expect("failed to create request");
+    let blinded_msg = request.blind_message;
+
+    let partials: Vec<Vec<u8>> = shares
+        .iter()
+        .map(|share| sign_threshold_partial(&key_set, share, &blinded_msg).unwrap())
+        .collect();
+    for partial in &partials {
+        assert!(verify_threshold_partial(&key_set, &blinded_msg, partial).unwrap());
+    }
+
+    // A partial signature whose value does not match its proof
+    let mut forged = partials[0].clone();
+    forged[10] ^= 1;
+    assert!(!verify_threshold_partial(&key_set, &blinded_msg, &forged).unwrap());
+
+    // The forged and repeated partial signatures are skipped
+    let selected = vec![
+        forged,
+        partials[1].clone(),
+        partials[1].clone(),
+        partials[4].clone(),
+        partials[2].clone(),
+    ];
+    let blinded_signature = combine_threshold_partials(&key_set, &blinded_msg, &selected)
+        .expect("failed to combine partials");
+    assert_eq!(blinded_signature, sign(&private_key, &blinded_msg).unwrap());
+
+    // A partial signature that cannot be decoded does not block the valid ones
+    let garbage = vec![0xff; 7];
+    assert!(verify_threshold_partial(&key_set, &blinded_msg, &garbage).is_err());
+    let selected = vec![
+        garbage,
+        partials[0].clone(),
+        partials[3].clone(),
+        partials[4].clone(),
+    ];
+    assert_eq!(
+        combine_threshold_partials(&key_set, &blinded_msg, &selected).unwrap(),
+        blinded_signature
+    );
+
+    let randomizer = &request.msg_randomizer;
+    let signature = unblind(
+        &public_key,
+        msg,
+        request.secret,
+        randomizer,
+        blinded_signature,
+        RSA_BLIND_PSS,
+    )
+    .expect("failed to unblind");
+    assert!(verify(&public_key, signature, randomizer, msg, RSA_BLIND_PSS).unwrap());
+
+    // Two registrars are not enough
+    assert!(combine_threshold_partials(&key_set, &blinded_msg, &partials[3..].to_vec()).is_err());
+}
+
+/// Tests the rotation of a signing key, resolved from the key ID of its tokens.
+#[test]
+fn test_key_registry() {
+    let old = generate_rsa_keypair().unwrap();
+    let new = generate_rsa_keypair().unwrap();
+    let (old_id, new_id) = (key_id(&old.public).unwrap(), key_id(&new.public).unwrap());
+    assert_eq!(old_id.len(), 32);
+    assert_ne!(old_id, new_id);
+
+    // The old key is valid until 1000 and the new one from 500
+    let registry = register_key(&vec![], &old.public, 0, 1000).unwrap();
+    let registry = register_key(&registry, &new.public, 500, 2000).unwrap();
+    assert!(matches!(
+        register_key(&registry, &old.public, 0, 1000),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(register_key(&vec![], &old.public, 1000, 0).is_err());
+
+    assert_eq!(resolve_key(&registry, &old_id, 100).unwrap(), old.public);
+    assert!(matches!(
+        resolve_key(&registry, &new_id, 100),
+        Err(Error::Key(_))
+    ));
+
+    let msg = &b"voter".to_vec();
+    let (old_signature, new_signature) = (
+        issue(&old, msg, RSA_BLIND_PSS),
+        issue(&new, msg, RSA_BLIND_PSS),
+    );
+
+    let verify_at = |id: &Vec<u8>,
+                     (signature, randomizer): &(Vec<u8>, Vec<u8>),
+                     registry: &Vec<u8>,
+                     timestamp: u64| {
+        verify_by_key_id(
+            registry,
+            id,
+            signature.clone(),
+            randomizer,
+            msg,
+            timestamp,
+            RSA_BLIND_PSS,
+        )
+        .unwrap()
+    };
+    assert!(verify_at(&old_id, &old_signature, &registry, 100));
+    assert!(!verify_at(&old_id, &old_signature, &registry, 1500));
+    assert!(verify_at(&new_id, &new_signature, &registry, 1500));
+    assert!(!verify_at(&new_id, &old_signature, &registry, 1500));
+
+    // Tokens of a retired key are rejected even within its validity window
+    let retired = retire_key(&registry, &old_id).unwrap();
+    assert!(!verify_at(&old_id, &old_signature, &retired, 100));
+    assert!(retire_key(&registry, &vec![0; 32]).is_err());
+
+    // The precompile reads the key ID from the token
+    let (new_signature, new_randomizer) = new_signature;
+    let token = encode_keyed_token(
+        &new_signature,
+        &new_randomizer,
+        &new_id,
+        &vec![1; 32],
+        RSA_BLIND_PSS,
+    )
+    .unwrap();
+    let input = |registry: &Vec<u8>, timestamp: u64| {
+        (
+            Bytes::from(registry.clone()),
+            Bytes::from(token.clone()),
+            Bytes::from(msg.clone()),
+            U256::from(timestamp),
+            U256::from(RSA_BLIND_PSS),
+        )
+            .abi_encode_sequence()
+    };
+    let output = verify_keyed_token(&input(&registry, 1500)).unwrap();
+    assert_eq!(output[31], 1);
+    assert_eq!(output[32..], nullifier(msg, &new_signature)[..]);
+    assert_eq!(
+        verify_keyed_token(&input(&registry, 2001)).unwrap(),
+        vec![0; 64]
+    );
+    let retired = retire_key(&registry, &new_id).unwrap();
+    assert_eq!(
+        verify_keyed_token(&input(&retired, 1500)).unwrap(),
+        vec![0; 64]
+    );
+}
+
+/// Tests a voter vouched for by 2 out of 3 independent registrars.
+#[test]
+fn test_eligibility_bundle() {
+    let registrars: Vec<ExportedKeyPair> =
+        (0..3).map(|_| generate_rsa_keypair().unwrap()).collect();
+    let public_keys: Vec<Vec<u8>> = registrars.iter().map(|r| r.public.clone()).collect();
+    let election_id = &vec![1; 32];
+    let msg = &b"voter".to_vec();
+
+    // Registrars 0 and 2 sign the same hidden message, in the deterministic mode
+    let scheme = RSA_BLIND_PSS_DETERMINISTIC;
+    let signatures: Vec<Vec<u8>> = [0, 2]
+        .iter()
+        .map(|&i| issue(&registrars[i], msg, scheme).0)
+        .collect();
+    let no_randomizers = &vec![vec![]; 2];
+    let encode = |registrars: Vec<usize>, signatures: &Vec<Vec<u8>>| {
+        encode_eligibility_bundle(&registrars, signatures, no_randomizers, election_id, scheme)
+            .unwrap()
+    };
+    let bundle = encode(vec![0, 2], &signatures);
+
+    assert!(verify_eligibility_bundle(&public_keys, 2, &bundle, msg, election_id, scheme).unwrap());
+    assert!(
+        !verify_eligibility_bundle(&public_keys, 3, &bundle, msg, election_id, scheme).unwrap()
+    );
+    assert!(
+        !verify_eligibility_bundle(&public_keys, 2, &bundle, msg, &vec![2; 32], scheme).unwrap()
+    );
+    assert!(
+        !verify_eligibility_bundle(
+            &public_keys,
+            2,
+            &bundle,
+            &b"other".to_vec(),
+            election_id,
+            scheme
+        )
+        .unwrap()
+    );
+
+    // The same registrar twice, or under two indexes, counts once
+    let repeated = encode(
+        vec![0, 0],
+        &vec![signatures[0].clone(), signatures[0].clone()],
+    );
+    assert!(
+        !verify_eligibility_bundle(&public_keys, 2, &repeated, msg, election_id, scheme).unwrap()
+    );
+    let aliased = vec![public_keys[0].clone(), public_keys[0].clone()];
+    let bundle_aliased = encode(
+        vec![0, 1],
+        &vec![signatures[0].clone(), signatures[0].clone()],
+    );
+    assert!(
+        !verify_eligibility_bundle(&aliased, 2, &bundle_aliased, msg, election_id, scheme).unwrap()
+    );
+
+    // A signature of registrar 2 claimed by registrar 1
+    let misattributed = encode(vec![0, 1], &signatures);
+    assert!(
+        !verify_eligibility_bundle(&public_keys, 2, &misattributed, msg, election_id, scheme)
+            .unwrap()
+    );
+
+    // The precompile returns the nullifier of the voter
+    let public_keys_pem: Vec<String> = public_keys.iter().map(|key| pem(key)).collect();
+    let input = |k: u64, scheme: u8| {
+        (
+            public_keys_pem.clone(),
+            U256::from(k),
+            Bytes::from(bundle.clone()),
+            Bytes::from(msg.clone()),
+            B256::from([1; 32]),
+            U256::from(scheme),
+        )
+            .abi_encode_sequence()
+    };
+    let output = verify_eligibility(&input(2, scheme)).unwrap();
+    assert_eq!(output[31], 1);
+    assert_eq!(output[32..], eligibility_nullifier(msg)[..]);
+    assert_eq!(verify_eligibility(&input(3, scheme)).unwrap(), vec![0; 64]);
+    assert!(matches!(
+        verify_eligibility(&input(4, scheme)),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // A deterministic bundle in a randomized election
+    assert!(matches!(
+        verify_eligibility(&input(2, RSA_BLIND_PSS)),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests partially blind tokens bound to an election and an expiry, through the ABI
+/// `verify_partially_blind` entry point.
+#[test]
+fn test_partially_blind_signature_flow() {
+    // Safe primes take minutes to find, so the test uses a fixed key
+    let public_key = include_bytes!("data/partially_blind_key.pub.der").to_vec();
+    let private_key = include_bytes!("data/partially_blind_key.der").to_vec();
+
+    let msg = &b"voter".to_vec();
+    let election_id = &vec![1; 32];
+    let expiry = 1_900_000_000;
+
+    let request = create_partially_blind_request(&public_key, msg, election_id, expiry)
+        .expect("failed to create request");
+    assert_eq!(request.msg_randomizer.len(), 32);
+
+    let blinded_signature =
+        sign_partially_blind(&private_key, &request.blind_message, election_id, expiry)
+            .expect("failed to sign blinded msg");
+
+    let signature = unblind_partially_blind(
+        &public_key,
+        msg,
+        election_id,
+        expiry,
+        request.secret,
+        &request.msg_randomizer,
+        blinded_signature,
+    )
+    .expect("failed to unblind");
+
+    let randomizer = &request.msg_randomizer;
+    assert!(
+        verify_partially_blind(
+            &public_key,
+            signature.clone(),
+            randomizer,
+            msg,
+            election_id,
+            expiry
+        )
+        .unwrap()
+    );
+
+    // The metadata is bound to the signature
+    assert!(
+        !verify_partially_blind(
+            &public_key,
+            signature.clone(),
+            randomizer,
+            msg,
+            &vec![2; 32],
+            expiry
+        )
+        .unwrap()
+    );
+    assert!(
+        !verify_partially_blind(
+            &public_key,
+            signature.clone(),
+            randomizer,
+            msg,
+            election_id,
+            expiry + 1
+        )
+        .unwrap()
+    );
+
+    // The ABI entry point also checks the election of the token and the expiry
+    let public_key_pem = PartiallyBlindPublicKey::<Sha384, PSS, Randomized>::from_der(&public_key)
+        .unwrap()
+        .to_pem()
+        .unwrap();
+    let token = encode_token(&signature, randomizer, election_id, RSA_BLIND_PSS).unwrap();
+    let input = |election_id: B256, expiry: u64, timestamp: u64| {
+        (
+            public_key_pem.clone(),
+            Bytes::from(token.clone()),
+            Bytes::from(msg.clone()),
+            election_id,
+            U256::from(expiry),
+            U256::from(timestamp),
+        )
+            .abi_encode_sequence()
+    };
+
+    let valid = verify_partially_blind_token(&input(B256::from([1; 32]), expiry, expiry)).unwrap();
+    assert_eq!(valid[31], 1);
+
+    // The same token always yields the same nullifier, derived from its message
+    assert_eq!(valid[32..], nullifier(msg, &signature)[..]);
+    assert_ne!(
+        nullifier(msg, &signature),
+        nullifier(&b"other".to_vec(), &signature)
+    );
+    assert_eq!(
+        verify_partially_blind_token(&input(B256::from([1; 32]), expiry, 0)).unwrap(),
+        valid
+    );
+
+    let expired =
+        verify_partially_blind_token(&input(B256::from([1; 32]), expiry, expiry + 1)).unwrap();
+    assert_eq!(expired[31], 0);
+    let other_election =
+        verify_partially_blind_token(&input(B256::from([2; 32]), expiry, 0)).unwrap();
+    assert_eq!(other_election, vec![0; 64]);
 }
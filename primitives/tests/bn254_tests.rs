@@ -0,0 +1,217 @@
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256, hex};
+use alloy_sol_types::{SolCall, SolValue, sol};
+use common::context;
+use primitives::backend::{encrypt_backend_vote, generate_backend_acc, generate_backend_keypair};
+use primitives::ballots::{add_votes, ballot_context, decrypt_result, verify_vote};
+use primitives::bn254::{Bn254Ballot, VERIFIER_CONTRACT};
+use primitives::envelope::{BN254_ELGAMAL, HEADER_LEN, inspect};
+use revm::context::TxEnv;
+use revm::database::{CacheDB, EmptyDB};
+use revm::primitives::TxKind;
+use revm::{Context, ExecuteCommitEvm, MainBuilder, MainContext};
+
+sol!("contracts/Bn254BallotVerifier.sol");
+
+fn verify_input(options_count: usize, context: &[u8], pk: &[u8], ballot: &[u8]) -> Vec<u8> {
+    (
+        U256::from(options_count),
+        Bytes::from(context.to_vec()),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+        U256::from(BN254_ELGAMAL),
+    )
+        .abi_encode_sequence()
+}
+
+/// Creation code of `Bn254BallotVerifier.sol`, generated by `contracts/build.sh` with the
+/// solc release it pins.
+const VERIFIER_BYTECODE: &str = include_str!("../contracts/Bn254BallotVerifier.bin");
+
+/// Deploys the verifier contract to an in-memory EVM and returns a call to its
+/// `verifyVote`, a reverted call counting as a rejected ballot.
+fn deploy_verifier() -> impl FnMut(usize, &[u8], &[u8], &[u8]) -> bool {
+    let mut evm = Context::mainnet()
+        .with_db(CacheDB::new(EmptyDB::default()))
+        .build_mainnet();
+
+    let code = hex::decode(VERIFIER_BYTECODE.trim()).unwrap();
+    let tx = TxEnv::builder()
+        .kind(TxKind::Create)
+        .data(code.into())
+        .gas_limit(16_000_000)
+        .build()
+        .unwrap();
+    let result = evm.transact_commit(tx).unwrap();
+    assert!(result.is_success());
+
+    let address = result.created_address().unwrap();
+    let mut nonce = 0;
+
+    move |options_count, context, pk, ballot| {
+        let call = Bn254BallotVerifier::verifyVoteCall {
+            optionsCount: U256::from(options_count),
+            context: Bytes::from(context.to_vec()),
+            publicKey: <[U256; 2]>::abi_decode(pk).unwrap(),
+            ballot: Bytes::from(ballot.to_vec()),
+        };
+        nonce += 1;
+        let tx = TxEnv::builder()
+            .kind(TxKind::Call(address))
+            .data(call.abi_encode().into())
+            .gas_limit(16_000_000)
+            .nonce(nonce)
+            .build()
+            .unwrap();
+
+        let result = evm.transact_commit(tx).unwrap();
+        result.is_success()
+            && Bn254BallotVerifier::verifyVoteCall::abi_decode_returns(result.output().unwrap())
+                .unwrap()
+    }
+}
+
+fn reseal(ballot: &[u8], payload: &Bn254Ballot) -> Vec<u8> {
+    [&ballot[..HEADER_LEN], &payload.abi_encode()].concat()
+}
+
+/// Tests the full voting flow of an election tallied over BN254.
+#[test]
+fn test_bn254_flow() {
+    let (pk, sk) = generate_backend_keypair(BN254_ELGAMAL).unwrap();
+    let options_count = 3;
+
+    let input = (
+        U256::from(options_count),
+        B256::from([1; 32]),
+        U256::from(BN254_ELGAMAL),
+    )
+        .abi_encode_sequence();
+    let mut acc = generate_backend_acc(&input).unwrap();
+
+    for choice in [1, 2, 1] {
+        let ballot =
+            encrypt_backend_vote(BN254_ELGAMAL, &pk, choice, options_count, &context(1)).unwrap();
+        assert_eq!(inspect(&ballot).unwrap().1, BN254_ELGAMAL);
+
+        let input = verify_input(options_count, &context(1), &pk, &ballot);
+        assert_eq!(verify_vote(&input).unwrap()[31], 1);
+
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        acc = add_votes(&input).unwrap();
+    }
+
+    assert_eq!(decrypt_result(&sk, &acc, 1, None).unwrap(), vec![0, 2, 1]);
+}
+
+/// Tests that ballots fit the calldata of the verifier contract and that tampered proofs
+/// are rejected.
+#[test]
+fn test_bn254_verifier_layout() {
+    let (pk, _) = generate_backend_keypair(BN254_ELGAMAL).unwrap();
+    let options_count = 2;
+    let ballot = encrypt_backend_vote(BN254_ELGAMAL, &pk, 0, options_count, &context(1)).unwrap();
+
+    // The contract reads the public key as `uint256[2]` and the payload as its ballot struct
+    let public_key = <[U256; 2]>::abi_decode(&pk).unwrap();
+    let call = Bn254BallotVerifier::verifyVoteCall {
+        optionsCount: U256::from(options_count),
+        context: Bytes::from(context(1)),
+        publicKey: public_key,
+        ballot: Bytes::from(ballot.clone()),
+    };
+    let call = Bn254BallotVerifier::verifyVoteCall::abi_decode(&call.abi_encode()).unwrap();
+    let decoded =
+        <Bn254BallotVerifier::Bn254Ballot as SolValue>::abi_decode(&call.ballot[HEADER_LEN..])
+            .unwrap();
+    assert_eq!(decoded.flags.len(), options_count);
+    assert!(VERIFIER_CONTRACT.contains("contract Bn254BallotVerifier"));
+
+    let payload = <Bn254Ballot as SolValue>::abi_decode(&ballot[HEADER_LEN..]).unwrap();
+
+    // A flag proof that does not match its flag
+    let mut tampered = payload.clone();
+    tampered.flags[0].proof[2] += U256::from(1);
+    let input = verify_input(options_count, &context(1), &pk, &reseal(&ballot, &tampered));
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+
+    // Two votes, each flag with a valid proof, but not adding up to 1
+    let other = encrypt_backend_vote(BN254_ELGAMAL, &pk, 1, options_count, &context(1)).unwrap();
+    let mut double = payload.clone();
+    double.flags[1] = <Bn254Ballot as SolValue>::abi_decode(&other[HEADER_LEN..])
+        .unwrap()
+        .flags[1]
+        .clone();
+    let input = verify_input(options_count, &context(1), &pk, &reseal(&ballot, &double));
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+
+    // The proofs are bound to the context
+    let mut other_context = context(1);
+    other_context[40] ^= 1;
+    let input = verify_input(options_count, &other_context, &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+
+    // Points off the curve
+    let mut off_curve = payload;
+    off_curve.flags[0].c[1] += U256::from(1);
+    let input = verify_input(
+        options_count,
+        &context(1),
+        &pk,
+        &reseal(&ballot, &off_curve),
+    );
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+}
+
+/// Tests that the deployed verifier contract agrees with `verify_vote`.
+#[test]
+fn test_bn254_verifier_contract() {
+    let mut contract_verify_vote = deploy_verifier();
+    let (pk, _) = generate_backend_keypair(BN254_ELGAMAL).unwrap();
+    let options_count = 3;
+    let ballot = encrypt_backend_vote(BN254_ELGAMAL, &pk, 2, options_count, &context(1)).unwrap();
+
+    let input = verify_input(options_count, &context(1), &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 1);
+    assert!(contract_verify_vote(
+        options_count,
+        &context(1),
+        &pk,
+        &ballot
+    ));
+
+    // A flag proof that does not match its flag
+    let mut tampered = <Bn254Ballot as SolValue>::abi_decode(&ballot[HEADER_LEN..]).unwrap();
+    tampered.flags[1].proof[0] += U256::from(1);
+    let tampered = reseal(&ballot, &tampered);
+    let input = verify_input(options_count, &context(1), &pk, &tampered);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+    assert!(!contract_verify_vote(
+        options_count,
+        &context(1),
+        &pk,
+        &tampered
+    ));
+
+    // The same ballot cast with another token
+    let other_token = ballot_context(&vec![1; 32], &vec![2; 20], &b"other".to_vec()).unwrap();
+    let input = verify_input(options_count, &other_token, &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+    assert!(!contract_verify_vote(
+        options_count,
+        &other_token,
+        &pk,
+        &ballot
+    ));
+
+    // The same ballot cast in another election
+    let input = verify_input(options_count, &context(2), &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+    assert!(!contract_verify_vote(
+        options_count,
+        &context(2),
+        &pk,
+        &ballot
+    ));
+}
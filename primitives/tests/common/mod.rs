@@ -0,0 +1,6 @@
+use primitives::ballots::ballot_context;
+
+/// Context of the ballots cast with the test token in the election `[election_id; 32]`.
+pub fn context(election_id: u8) -> Vec<u8> {
+    ballot_context(&vec![election_id; 32], &vec![2; 20], &b"token".to_vec()).unwrap()
+}
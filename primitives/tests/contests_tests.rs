@@ -0,0 +1,139 @@
+mod common;
+
+use alloy_primitives::{Bytes, U256};
+use alloy_sol_types::SolValue;
+use common::context;
+use postcard::{from_bytes, to_allocvec};
+use primitives::ballots::{decrypt_result, encrypt_vote, generate_elgamal_keypair};
+use primitives::contests::{
+    MULTI_CHOICE, RANKED, SCORE, SINGLE_CHOICE, add_contest_votes, encrypt_contest_votes,
+    generate_contest_acc, split_contest_results, verify_contest_votes,
+};
+use primitives::envelope::HEADER_LEN;
+
+fn contest(kind: u64, options_count: u64, a: u64, b: u64) -> (U256, U256, U256, U256) {
+    (
+        U256::from(kind),
+        U256::from(options_count),
+        U256::from(a),
+        U256::from(b),
+    )
+}
+
+/// President, treasurer (up to 2 seats), a 0-3 stars rating and a ranked referendum.
+fn manifest() -> Vec<u8> {
+    vec![
+        contest(SINGLE_CHOICE, 3, 0, 0),
+        contest(MULTI_CHOICE, 4, 1, 2),
+        contest(SCORE, 2, 3, 0),
+        contest(RANKED, 3, 0, 0),
+    ]
+    .abi_encode()
+}
+
+fn verify(manifest: &[u8], pk: &[u8], ballot: &[u8]) -> u8 {
+    let input = (
+        Bytes::from(manifest.to_vec()),
+        Bytes::from(context(1)),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+    )
+        .abi_encode_sequence();
+
+    verify_contest_votes(&input).unwrap()[31]
+}
+
+/// Tests casting and tallying ballots with several contests.
+#[test]
+fn test_contests_flow() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let manifest = manifest();
+
+    let selections = vec![
+        vec![vec![1], vec![0, 3], vec![3, 1], vec![2, 0, 1]],
+        vec![vec![1], vec![2], vec![0, 2], vec![0, 1, 2]],
+    ];
+
+    let mut acc = generate_contest_acc(&manifest, &vec![1; 32]).unwrap();
+
+    for selection in &selections {
+        let ballot = encrypt_contest_votes(&pk, &manifest, selection, &context(1)).unwrap();
+        assert_eq!(verify(&manifest, &pk, &ballot), 1);
+
+        let input = (
+            Bytes::from(manifest.clone()),
+            Bytes::from(acc),
+            Bytes::from(ballot),
+        )
+            .abi_encode_sequence();
+        acc = add_contest_votes(&input).unwrap();
+    }
+
+    let results = decrypt_result(&sk, &acc, 3, None).unwrap();
+    let results = split_contest_results(&manifest, &results).unwrap();
+
+    #[rustfmt::skip]
+    assert_eq!(results, vec![
+        vec![0, 2, 0],
+        vec![1, 0, 1, 1],
+        vec![3, 3],
+        vec![
+            0, 2, 1,
+            0, 0, 1,
+            1, 1, 0,
+        ],
+    ]);
+}
+
+/// Tests that selections breaking a contest rule cannot be encrypted.
+#[test]
+fn test_contests_invalid_selections() {
+    let (pk, _) = generate_elgamal_keypair();
+    let manifest = manifest();
+
+    let valid = vec![vec![0], vec![1], vec![0, 0], vec![0, 1, 2]];
+    assert!(encrypt_contest_votes(&pk, &manifest, &valid, &context(1)).is_ok());
+
+    assert!(encrypt_contest_votes(&pk, &manifest, &valid[..3].to_vec(), &context(1)).is_err());
+
+    let mut two_presidents = valid.clone();
+    two_presidents[0] = vec![0, 1];
+    assert!(encrypt_contest_votes(&pk, &manifest, &two_presidents, &context(1)).is_err());
+
+    let mut no_treasurer = valid.clone();
+    no_treasurer[1] = vec![];
+    assert!(encrypt_contest_votes(&pk, &manifest, &no_treasurer, &context(1)).is_err());
+
+    let mut missing_score = valid.clone();
+    missing_score[2] = vec![1];
+    assert!(encrypt_contest_votes(&pk, &manifest, &missing_score, &context(1)).is_err());
+
+    let unknown_kind = vec![contest(7, 2, 0, 0)].abi_encode();
+    assert!(encrypt_contest_votes(&pk, &unknown_kind, &vec![vec![0]], &context(1)).is_err());
+}
+
+/// Tests that every contest of a ballot is checked against the manifest.
+#[test]
+fn test_contests_rejects_tampered_ballot() {
+    let (pk, _) = generate_elgamal_keypair();
+    let manifest = manifest();
+
+    let selection = vec![vec![2], vec![1], vec![1, 1], vec![1, 0, 2]];
+    let ballot = encrypt_contest_votes(&pk, &manifest, &selection, &context(1)).unwrap();
+
+    // A ballot for another manifest
+    let other_manifest = vec![contest(SINGLE_CHOICE, 4, 0, 0)].abi_encode();
+    assert_eq!(verify(&other_manifest, &pk, &ballot), 0);
+
+    // The president contest replaced by a vote with more options
+    let (header, payload) = ballot.split_at(HEADER_LEN);
+    let mut contests: Vec<Vec<u8>> = from_bytes(payload).unwrap();
+    contests[0] = encrypt_vote(&pk, 3, 4, &context(1)).unwrap();
+    let tampered = [header, &to_allocvec(&contests).unwrap()].concat();
+    assert_eq!(verify(&manifest, &pk, &tampered), 0);
+
+    // A missing contest
+    contests.truncate(3);
+    let truncated = [header, &to_allocvec(&contests).unwrap()].concat();
+    assert_eq!(verify(&manifest, &pk, &truncated), 0);
+}
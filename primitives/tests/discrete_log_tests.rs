@@ -0,0 +1,93 @@
+use ark_bn254::{Fr, G1Projective};
+use ark_ec::PrimeGroup;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::scalar::Scalar;
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, Keypair};
+use k256::ProjectivePoint;
+use postcard::to_allocvec;
+use primitives::ballots::decrypt_result;
+use primitives::discrete_log::{DiscreteLog, MAX_BABY_STEPS};
+use primitives::envelope::migrate_acc;
+use rand_legacy::thread_rng;
+
+/// Tests the solver at the edges of its range and with an unbalanced table.
+#[test]
+fn test_discrete_log() {
+    let element = |value: u64| RISTRETTO_BASEPOINT_POINT * Scalar::from(value);
+
+    for solver in [
+        DiscreteLog::new(3_000_000),
+        DiscreteLog::with_baby_steps(3_000_000, 100),
+    ] {
+        for value in [0, 1, 1733, 1_234_567, 2_999_999, 3_000_000] {
+            assert_eq!(solver.solve(&element(value)), Some(value));
+        }
+
+        assert_eq!(solver.solve(&element(3_000_001)), None);
+        assert_eq!(solver.solve(&-element(1)), None);
+    }
+
+    assert_eq!(DiscreteLog::new(0).solve(&element(0)), Some(0));
+    assert_eq!(DiscreteLog::new(0).solve(&element(1)), None);
+}
+
+/// Tests that a table asked for more than `MAX_BABY_STEPS` baby steps is capped.
+#[test]
+fn test_discrete_log_baby_steps_cap() {
+    let element = |value: u64| RISTRETTO_BASEPOINT_POINT * Scalar::from(value);
+
+    // Without the cap the table would not fit in memory
+    let solver = DiscreteLog::with_baby_steps(u64::MAX, u64::MAX);
+    for value in [0, MAX_BABY_STEPS - 1, MAX_BABY_STEPS, 3 * MAX_BABY_STEPS + 5] {
+        assert_eq!(solver.solve(&element(value)), Some(value));
+    }
+}
+
+/// Tests the solver over the groups of the BN254 and secp256k1 backends.
+#[test]
+fn test_discrete_log_groups() {
+    let bn254 = |value: u64| G1Projective::generator() * Fr::from(value);
+    let solver = DiscreteLog::<G1Projective>::with_baby_steps(100_000, 100);
+    for value in [0, 1, 4321, 100_000] {
+        assert_eq!(solver.solve(&bn254(value)), Some(value));
+    }
+    assert_eq!(solver.solve(&bn254(100_001)), None);
+
+    let secp256k1 = |value: u64| ProjectivePoint::GENERATOR * k256::Scalar::from(value);
+    let solver = DiscreteLog::<ProjectivePoint>::new(100_000);
+    for value in [0, 1, 4321, 100_000] {
+        assert_eq!(solver.solve(&secp256k1(value)), Some(value));
+    }
+    assert_eq!(solver.solve(&secp256k1(100_001)), None);
+}
+
+/// Tests decrypting counts in the millions, bounded by the ballots of the accumulator.
+#[test]
+fn test_decrypt_large_tally() {
+    let rng = &mut thread_rng();
+    let keys = Keypair::<Ristretto>::generate(rng);
+
+    let counts = [2_500_000, 0, 1, 2_000_001];
+    let entries = counts
+        .iter()
+        .map(|&count| keys.public().encrypt(count, rng))
+        .collect::<Vec<Ciphertext<Ristretto>>>();
+    let secret_key = keys.secret().expose_scalar().as_bytes().to_vec();
+
+    let election_id = vec![1; 32];
+    let acc = to_allocvec(&entries).unwrap();
+    let acc = migrate_acc(&acc, &election_id, 4_500_001).unwrap();
+    assert_eq!(decrypt_result(&secret_key, &acc, 1, None).unwrap(), counts);
+
+    // A smaller table trades memory for giant steps
+    assert_eq!(
+        decrypt_result(&secret_key, &acc, 1, Some(1 << 10)).unwrap(),
+        counts
+    );
+
+    // A count above the number of ballots cannot be decrypted
+    let acc = to_allocvec(&entries).unwrap();
+    let acc = migrate_acc(&acc, &election_id, 2_000_000).unwrap();
+    assert!(decrypt_result(&secret_key, &acc, 1, None).is_err());
+}
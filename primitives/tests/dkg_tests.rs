@@ -0,0 +1,158 @@
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use primitives::ballots::{
+    add_votes, ballot_context, combine_decryption_shares, decrypt_share, encrypt_vote,
+    generate_acc, verify_decryption_share,
+};
+use primitives::dkg::{
+    derive_key_set, derive_public_key, generate_participant, get_commitment, get_public_info,
+    get_secret_share, get_shared_public_key, insert_commitments, insert_public_infos,
+    insert_secret_shares,
+};
+
+/// Runs the whole ceremony and returns the public infos and key shares of every trustee.
+fn run_ceremony(k: usize, n: usize) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let states: Vec<Vec<u8>> = (0..n)
+        .map(|i| generate_participant(k, n, i).expect("failed to generate participant"))
+        .collect();
+
+    // 1. Commitments
+    let commitments: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| get_commitment(state).expect("failed to get commitment"))
+        .collect();
+    let states: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| insert_commitments(state, &commitments).expect("failed to insert"))
+        .collect();
+
+    // 2. Public polynomials
+    let infos: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| get_public_info(state).expect("failed to get public info"))
+        .collect();
+    let states: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| insert_public_infos(state, &infos).expect("failed to insert"))
+        .collect();
+
+    // 3. Secret shares
+    let key_shares = (0..n)
+        .map(|i| {
+            let shares: Vec<Vec<u8>> = states
+                .iter()
+                .map(|state| get_secret_share(state, i).expect("failed to get share"))
+                .collect();
+            insert_secret_shares(&states[i], &shares).expect("failed to insert shares")
+        })
+        .collect();
+
+    (infos, key_shares)
+}
+
+#[test]
+fn test_dkg_flow() {
+    let (k, n) = (2, 3);
+    let (infos, key_shares) = run_ceremony(k, n);
+
+    let public_key = get_shared_public_key(&key_shares[0]).expect("failed to get public key");
+    for key_share in &key_shares {
+        assert_eq!(get_shared_public_key(key_share).unwrap(), public_key);
+    }
+
+    // Observers derive the same key from the published information
+    assert_eq!(derive_public_key(k, n, &infos).unwrap(), public_key);
+
+    // The joint key can be used to cast ballots
+    let context = ballot_context(&vec![1; 32], &vec![2; 20], &vec![3]).unwrap();
+    encrypt_vote(&public_key, 0, 3, &context).expect("failed to encrypt vote");
+}
+
+#[test]
+fn test_dkg_rejects_mismatched_commitment() {
+    let (k, n) = (2, 3);
+    let states: Vec<Vec<u8>> = (0..n)
+        .map(|i| generate_participant(k, n, i).unwrap())
+        .collect();
+
+    let mut commitments: Vec<Vec<u8>> = states.iter().map(|s| get_commitment(s).unwrap()).collect();
+    commitments[1] = vec![0; 32];
+
+    let states: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| insert_commitments(state, &commitments).unwrap())
+        .collect();
+    let infos: Vec<Vec<u8>> = states.iter().map(|s| get_public_info(s).unwrap()).collect();
+
+    assert!(insert_public_infos(&states[0], &infos).is_err());
+}
+
+#[test]
+fn test_dkg_invalid_params() {
+    assert!(generate_participant(3, 2, 0).is_err());
+    assert!(generate_participant(2, 3, 3).is_err());
+}
+
+/// Builds an accumulator with the given votes under `public_key`.
+fn tally(public_key: &Vec<u8>, candidates: usize, votes: &[usize]) -> Vec<u8> {
+    let mut acc =
+        generate_acc(&(U256::from(candidates), B256::from([1; 32])).abi_encode_sequence()).unwrap();
+    let context = ballot_context(&vec![1; 32], &vec![2; 20], &vec![3]).unwrap();
+
+    for &choice in votes {
+        let vote =
+            encrypt_vote(public_key, choice, candidates, &context).expect("failed to encrypt vote");
+        let input = (Bytes::from(acc), Bytes::from(vote)).abi_encode_sequence();
+        acc = add_votes(&input).unwrap();
+    }
+
+    acc
+}
+
+#[test]
+fn test_threshold_decryption() {
+    let (k, n) = (2, 3);
+    let (infos, key_shares) = run_ceremony(k, n);
+    let public_key = derive_public_key(k, n, &infos).unwrap();
+    let key_set = derive_key_set(k, n, &infos).unwrap();
+
+    let acc = tally(&public_key, 3, &[0, 1, 0]);
+
+    // Any k trustees can decrypt
+    let shares = vec![
+        (0, decrypt_share(&key_shares[0], &acc).unwrap()),
+        (2, decrypt_share(&key_shares[2], &acc).unwrap()),
+    ];
+    for (index, share) in &shares {
+        assert!(verify_decryption_share(&key_set, &acc, *index, share).unwrap());
+    }
+
+    let results = combine_decryption_shares(&key_set, &acc, &shares, 1, None).unwrap();
+    assert_eq!(results, vec![2, 1, 0]);
+
+    // Fewer than k trustees cannot
+    assert!(combine_decryption_shares(&key_set, &acc, &shares[..1].to_vec(), 1, None).is_err());
+}
+
+#[test]
+fn test_threshold_decryption_detects_bad_share() {
+    let (k, n) = (2, 3);
+    let (infos, key_shares) = run_ceremony(k, n);
+    let public_key = derive_public_key(k, n, &infos).unwrap();
+    let key_set = derive_key_set(k, n, &infos).unwrap();
+
+    let acc = tally(&public_key, 2, &[0, 1]);
+    let other_acc = tally(&public_key, 2, &[1]);
+
+    // Trustee 1 decrypts a different accumulator
+    let bad_share = decrypt_share(&key_shares[1], &other_acc).unwrap();
+    assert!(!verify_decryption_share(&key_set, &acc, 1, &bad_share).unwrap());
+
+    // A valid share presented under another trustee's index is rejected as well
+    let good_share = decrypt_share(&key_shares[0], &acc).unwrap();
+    assert!(!verify_decryption_share(&key_set, &acc, 2, &good_share).unwrap());
+
+    let shares = vec![(0, good_share), (1, bad_share)];
+    let err = combine_decryption_shares(&key_set, &acc, &shares, 1, None).unwrap_err();
+    assert!(err.to_string().contains("trustee 1"));
+}
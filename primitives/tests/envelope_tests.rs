@@ -0,0 +1,291 @@
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use common::context;
+use elastic_elgamal::app::{ChoiceParams, EncryptedChoice, SingleChoice};
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, PublicKey};
+use primitives::ballots::{
+    add_legacy_votes, add_votes, add_votes_batch, decrypt_result, encode_token, encrypt_score_vote,
+    encrypt_vote, generate_acc, generate_elgamal_keypair, verify, verify_legacy_vote, verify_vote,
+};
+use primitives::envelope::{
+    ACCUMULATOR, HEADER_LEN, LEGACY_SINGLE_CHOICE_BALLOT, MULTI_CHOICE_BALLOT, RISTRETTO_ELGAMAL,
+    RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC, SCORE_BALLOT, TOKEN, VERSION, inspect, migrate_acc,
+    migrate_ballot, migrate_token,
+};
+use primitives::error::Error;
+use rand_legacy::thread_rng;
+
+fn acc(options_count: usize, election_id: u8) -> Vec<u8> {
+    let input = (U256::from(options_count), B256::from([election_id; 32])).abi_encode_sequence();
+    generate_acc(&input).unwrap()
+}
+
+fn verify_input(pk: &[u8], options_count: usize, ballot: &[u8]) -> Vec<u8> {
+    (
+        U256::from(options_count),
+        Bytes::from(context(1)),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+        U256::from(RISTRETTO_ELGAMAL),
+    )
+        .abi_encode_sequence()
+}
+
+/// Tests the header written in front of ballots, accumulators and tokens.
+#[test]
+fn test_envelope_header() {
+    let (pk, _) = generate_elgamal_keypair();
+
+    let ballot = encrypt_vote(&pk, 0, 3, &context(1)).unwrap();
+    assert_eq!(&ballot[..4], b"VOTE");
+    assert_eq!(
+        inspect(&ballot).unwrap(),
+        (VERSION, RISTRETTO_ELGAMAL, MULTI_CHOICE_BALLOT, vec![1; 32])
+    );
+
+    let ballot = encrypt_score_vote(&pk, &vec![1, 0], 2, None, &context(1)).unwrap();
+    assert_eq!(inspect(&ballot).unwrap().2, SCORE_BALLOT);
+
+    assert_eq!(
+        inspect(&acc(3, 7)).unwrap(),
+        (VERSION, RISTRETTO_ELGAMAL, ACCUMULATOR, vec![7; 32])
+    );
+
+    let token = encode_token(&vec![5; 256], &vec![6; 32], &vec![1; 32], RSA_BLIND_PSS).unwrap();
+    assert_eq!(
+        inspect(&token).unwrap(),
+        (VERSION, RSA_BLIND_PSS, TOKEN, vec![1; 32])
+    );
+    assert!(matches!(
+        encode_token(&vec![5; 256], &vec![6; 31], &vec![1; 32], RSA_BLIND_PSS),
+        Err(Error::Decode(_))
+    ));
+
+    // The blind signature mode is recorded as the scheme
+    let token = encode_token(
+        &vec![5; 256],
+        &vec![],
+        &vec![1; 32],
+        RSA_BLIND_PSS_DETERMINISTIC,
+    )
+    .unwrap();
+    assert_eq!(inspect(&token).unwrap().1, RSA_BLIND_PSS_DETERMINISTIC);
+    assert!(matches!(
+        encode_token(
+            &vec![5; 256],
+            &vec![6; 32],
+            &vec![1; 32],
+            RSA_BLIND_PSS_DETERMINISTIC
+        ),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        encode_token(&vec![5; 256], &vec![], &vec![1; 32], RISTRETTO_ELGAMAL),
+        Err(Error::InvalidInput(_))
+    ));
+
+    assert!(matches!(inspect(&vec![1, 2, 3]), Err(Error::Version(_))));
+    assert!(matches!(
+        encrypt_vote(&pk, 0, 3, &vec![1; 31]),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests that blobs of another version, kind or election are rejected.
+#[test]
+fn test_envelope_rejects_mismatches() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+    let ballot = encrypt_vote(&pk, 0, options_count, &context(1)).unwrap();
+    assert_eq!(
+        verify_vote(&verify_input(&pk, options_count, &ballot)).unwrap()[31],
+        1
+    );
+
+    // A ballot written by a future version of the library
+    let mut future = ballot.clone();
+    future[4] = VERSION + 1;
+    assert!(matches!(
+        verify_vote(&verify_input(&pk, options_count, &future)),
+        Err(Error::Version(_))
+    ));
+
+    // A ballot written before envelopes were introduced
+    let bare = ballot[HEADER_LEN..].to_vec();
+    assert!(matches!(
+        verify_vote(&verify_input(&pk, options_count, &bare)),
+        Err(Error::Version(_))
+    ));
+
+    // An accumulator passed as a ballot
+    let acc = acc(options_count, 1);
+    assert!(matches!(
+        verify_vote(&verify_input(&pk, options_count, &acc)),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // A ballot of another election, relabelled with the election of the context
+    let other = encrypt_vote(&pk, 0, options_count, &context(9)).unwrap();
+    assert_eq!(
+        verify_vote(&verify_input(&pk, options_count, &other)).unwrap()[31],
+        0
+    );
+    let mut relabelled = other.clone();
+    relabelled[7..HEADER_LEN].copy_from_slice(&[1; 32]);
+    assert_eq!(
+        verify_vote(&verify_input(&pk, options_count, &relabelled)).unwrap()[31],
+        0
+    );
+
+    // Adding it to the accumulator of another election
+    let input = (Bytes::from(acc), Bytes::from(other)).abi_encode_sequence();
+    assert!(matches!(add_votes(&input), Err(Error::InvalidInput(_))));
+}
+
+/// Tests converting blobs written before envelopes were introduced.
+#[test]
+fn test_migrations() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+    let election_id = vec![1; 32];
+
+    // Single-choice ballots of the first format, as the original `encrypt_vote` wrote them
+    let receiver = PublicKey::<Ristretto>::from_bytes(&pk).unwrap();
+    let params = ChoiceParams::single(receiver, options_count);
+    let legacy = EncryptedChoice::single(&params, 2, &mut thread_rng());
+    let legacy = postcard::to_allocvec(&legacy).unwrap();
+
+    // Ballots of the current format were never written without an envelope
+    let ballot = encrypt_vote(&pk, 2, options_count, &context(1)).unwrap();
+    assert!(matches!(
+        migrate_ballot(&ballot, &election_id),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(migrate_ballot(&ballot[HEADER_LEN..].to_vec(), &election_id).is_err());
+
+    let migrated = migrate_ballot(&legacy, &election_id).unwrap();
+    assert_eq!(
+        inspect(&migrated).unwrap(),
+        (
+            VERSION,
+            RISTRETTO_ELGAMAL,
+            LEGACY_SINGLE_CHOICE_BALLOT,
+            election_id.clone()
+        )
+    );
+    let payload =
+        postcard::from_bytes::<EncryptedChoice<Ristretto, SingleChoice>>(&migrated[HEADER_LEN..])
+            .unwrap();
+    assert!(payload.verify(&params).is_ok());
+    assert!(matches!(
+        verify_vote(&verify_input(&pk, options_count, &migrated)),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Legacy ballots are verified and tallied with their own entry points
+    let legacy_input = |election_id: [u8; 32], ballot: &Vec<u8>| {
+        (
+            U256::from(options_count),
+            B256::from(election_id),
+            Bytes::from(pk.clone()),
+            Bytes::from(ballot.clone()),
+        )
+            .abi_encode_sequence()
+    };
+    assert_eq!(
+        verify_legacy_vote(&legacy_input([1; 32], &migrated)).unwrap()[31],
+        1
+    );
+    assert_eq!(
+        verify_legacy_vote(&legacy_input([9; 32], &migrated)).unwrap()[31],
+        0
+    );
+
+    let forged = EncryptedChoice::new(&params, &[true, false, true], &mut thread_rng());
+    let forged = postcard::to_allocvec(&forged).unwrap();
+    let forged = migrate_ballot(&forged, &election_id).unwrap();
+    assert_eq!(
+        verify_legacy_vote(&legacy_input([1; 32], &forged)).unwrap()[31],
+        0
+    );
+
+    let input = (
+        Bytes::from(acc(options_count, 1)),
+        Bytes::from(migrated.clone()),
+    )
+        .abi_encode_sequence();
+    let tally = add_legacy_votes(&input).unwrap();
+    assert_eq!(decrypt_result(&sk, &tally, 1, None).unwrap(), vec![0, 0, 1]);
+
+    let input = (
+        Bytes::from(acc(options_count, 9)),
+        Bytes::from(migrated.clone()),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(
+        add_legacy_votes(&input),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Batches take legacy ballots alongside current ones, verified as single-choice ballots
+    let batch_input = |verify: bool, max: u64| {
+        let ballots = [&migrated, &forged, &ballot];
+        (
+            Bytes::from(acc(options_count, 1)),
+            ballots.map(|ballot| Bytes::from(ballot.clone())).to_vec(),
+            verify,
+            U256::from(1),
+            U256::from(max),
+            vec![Bytes::from(context(1)); ballots.len()],
+            Bytes::from(pk.clone()),
+        )
+            .abi_encode_sequence()
+    };
+    let add = |verify: bool, max: u64| {
+        let output = add_votes_batch(&batch_input(verify, max)).unwrap();
+        let (acc, rejected) = <(Bytes, Vec<U256>)>::abi_decode_sequence(&output).unwrap();
+
+        let rejected = rejected.iter().map(|i| i.to::<usize>()).collect::<Vec<_>>();
+        (
+            decrypt_result(&sk, &acc.to_vec(), 2, None).unwrap(),
+            rejected,
+        )
+    };
+    assert_eq!(add(false, 1), (vec![1, 0, 3], vec![]));
+    assert_eq!(add(true, 1), (vec![0, 0, 2], vec![1]));
+    assert_eq!(add(true, 2), (vec![0, 0, 0], vec![0, 1, 2]));
+
+    // Accumulators, which were a bare list of ciphertexts
+    let input = (Bytes::from(acc(options_count, 1)), Bytes::from(ballot)).abi_encode_sequence();
+    let acc = add_votes(&input).unwrap();
+    let entries = postcard::from_bytes::<(u64, Vec<Ciphertext<Ristretto>>)>(&acc[HEADER_LEN..])
+        .unwrap()
+        .1;
+    let listed = postcard::to_allocvec(&entries).unwrap();
+    let migrated = migrate_acc(&listed, &election_id, 1).unwrap();
+    assert_eq!(migrated, acc);
+    assert_eq!(
+        decrypt_result(&sk, &migrated, 1, None).unwrap(),
+        vec![0, 0, 1]
+    );
+    assert!(matches!(
+        migrate_acc(&acc, &election_id, 1),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // Tokens
+    let token = encode_token(&vec![5; 256], &vec![6; 32], &election_id, RSA_BLIND_PSS).unwrap();
+    let bare_token = postcard::to_allocvec(&(vec![5_u8; 256], [6_u8; 32])).unwrap();
+    assert_eq!(migrate_token(&bare_token, &election_id).unwrap(), token);
+
+    let input = (
+        String::from("-----BEGIN PUBLIC KEY-----"),
+        Bytes::from(bare_token),
+        Bytes::new(),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(verify(&input), Err(Error::Version(_))));
+}
@@ -0,0 +1,225 @@
+use alloy_primitives::{B256, Bytes};
+use alloy_sol_types::SolValue;
+use elastic_elgamal::Ciphertext;
+use elastic_elgamal::group::Ristretto;
+use postcard::{from_bytes, to_allocvec};
+use primitives::ballots::generate_elgamal_keypair;
+use primitives::dkg::{
+    derive_key_set, generate_participant, get_commitment, get_public_info, get_secret_share,
+    get_shared_public_key, insert_commitments, insert_public_infos, insert_secret_shares,
+};
+use primitives::error::Error;
+use primitives::mixnet::{
+    combine_decryption_shares, decrypt_share, encrypt_message, shuffle, verify_mix,
+    verify_shuffle_chain,
+};
+
+type Encoded = [u8; 32];
+type Message = Vec<Ciphertext<Ristretto>>;
+type ShuffleProof = (
+    (Vec<Encoded>, Vec<Encoded>),
+    ([Encoded; 3], Vec<[Encoded; 2]>, Vec<Encoded>),
+    ([Encoded; 3], Vec<Encoded>, Vec<Encoded>, Vec<Encoded>),
+);
+type Chain = Vec<(Vec<Message>, ShuffleProof)>;
+
+/// Runs a key generation ceremony and returns the key set and key shares of every trustee.
+fn run_ceremony(k: usize, n: usize) -> (Vec<u8>, Vec<Vec<u8>>) {
+    let states: Vec<Vec<u8>> = (0..n)
+        .map(|i| generate_participant(k, n, i).unwrap())
+        .collect();
+
+    let commitments: Vec<Vec<u8>> = states.iter().map(|s| get_commitment(s).unwrap()).collect();
+    let states: Vec<Vec<u8>> = states
+        .iter()
+        .map(|s| insert_commitments(s, &commitments).unwrap())
+        .collect();
+
+    let infos: Vec<Vec<u8>> = states.iter().map(|s| get_public_info(s).unwrap()).collect();
+    let states: Vec<Vec<u8>> = states
+        .iter()
+        .map(|s| insert_public_infos(s, &infos).unwrap())
+        .collect();
+
+    let key_shares = (0..n)
+        .map(|i| {
+            let shares: Vec<Vec<u8>> = states
+                .iter()
+                .map(|s| get_secret_share(s, i).unwrap())
+                .collect();
+            insert_secret_shares(&states[i], &shares).unwrap()
+        })
+        .collect();
+
+    (derive_key_set(k, n, &infos).unwrap(), key_shares)
+}
+
+fn encrypt_all(public_key: &Vec<u8>, messages: &[&str], width: usize) -> Vec<Vec<u8>> {
+    messages
+        .iter()
+        .map(|message| encrypt_message(public_key, &message.as_bytes().to_vec(), width).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_mixnet_flow() {
+    let (key_set, key_shares) = run_ceremony(2, 3);
+    let public_key = get_shared_public_key(&key_shares[0]).unwrap();
+    let election_id = vec![1; 32];
+
+    let answers = [
+        "Alice",
+        "",
+        "Carol",
+        "Write-in with accents: éèê",
+        "exactly fifty-nine bytes long, the longest answer that fits",
+    ];
+    let messages = encrypt_all(&public_key, &answers, 2);
+
+    // Every trustee shuffles the output of the previous one
+    let mut chain = Vec::new();
+    for _ in 0..3 {
+        assert!(
+            chain.is_empty()
+                || verify_shuffle_chain(&public_key, &election_id, &messages, &chain).unwrap()
+        );
+        chain = shuffle(&public_key, &election_id, &messages, &chain).expect("failed to shuffle");
+    }
+    assert!(verify_shuffle_chain(&public_key, &election_id, &messages, &chain).unwrap());
+
+    let messages_abi = messages
+        .iter()
+        .cloned()
+        .map(Bytes::from)
+        .collect::<Vec<_>>();
+    let input = |election_id: [u8; 32]| {
+        (
+            Bytes::from(public_key.clone()),
+            B256::from(election_id),
+            messages_abi.clone(),
+            Bytes::from(chain.clone()),
+        )
+            .abi_encode_sequence()
+    };
+    assert_eq!(verify_mix(&input([1; 32])).unwrap()[31], 1);
+
+    // The proofs commit to the election, so the chain is not valid in another one
+    assert_eq!(verify_mix(&input([9; 32])).unwrap()[31], 0);
+
+    // A chain that does not decode is an error rather than an invalid mix
+    let input = (
+        Bytes::from(public_key.clone()),
+        B256::from([1; 32]),
+        messages_abi,
+        Bytes::from(vec![0xff; 7]),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(verify_mix(&input), Err(Error::Decode(_))));
+
+    // The output of the mix is re-encrypted
+    let decoded: Chain = from_bytes(&chain).unwrap();
+    let outputs = decoded[2]
+        .0
+        .iter()
+        .map(|message| to_allocvec(message).unwrap())
+        .collect::<Vec<_>>();
+    for message in &messages {
+        assert!(!outputs.contains(message));
+    }
+
+    let shares = [0, 2]
+        .iter()
+        .map(|&i| (i, decrypt_share(&key_shares[i], &chain).unwrap()))
+        .collect::<Vec<_>>();
+    let plaintexts = combine_decryption_shares(&key_set, &chain, &shares).unwrap();
+
+    let mut revealed = plaintexts
+        .into_iter()
+        .map(|plaintext| String::from_utf8(plaintext.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    let mut expected = answers.map(String::from).to_vec();
+    revealed.sort();
+    expected.sort();
+    assert_eq!(revealed, expected);
+
+    // A single share is below the threshold
+    assert!(matches!(
+        combine_decryption_shares(&key_set, &chain, &shares[..1].to_vec()),
+        Err(Error::InvalidInput(_))
+    ));
+
+    // A last shuffle without outputs
+    let mut decoded: Chain = from_bytes(&chain).unwrap();
+    decoded[2].0.clear();
+    let emptied = to_allocvec(&decoded).unwrap();
+    assert!(matches!(
+        combine_decryption_shares(&key_set, &emptied, &shares),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn test_shuffle_chain_rejects_tampering() {
+    let (public_key, _) = generate_elgamal_keypair();
+    let election_id = vec![1; 32];
+    let messages = encrypt_all(&public_key, &["yes", "no", "maybe"], 1);
+
+    assert!(!verify_shuffle_chain(&public_key, &election_id, &messages, &Vec::new()).unwrap());
+
+    let chain = shuffle(&public_key, &election_id, &messages, &Vec::new()).unwrap();
+    let chain = shuffle(&public_key, &election_id, &messages, &chain).unwrap();
+    assert!(verify_shuffle_chain(&public_key, &election_id, &messages, &chain).unwrap());
+
+    // Reordering the output of a shuffle
+    let mut decoded: Chain = from_bytes(&chain).unwrap();
+    decoded[0].0.swap(0, 1);
+    let tampered = to_allocvec(&decoded).unwrap();
+    assert!(!verify_shuffle_chain(&public_key, &election_id, &messages, &tampered).unwrap());
+
+    // Replacing a message with a fresh encryption
+    let mut decoded: Chain = from_bytes(&chain).unwrap();
+    let forged = encrypt_message(&public_key, &b"forged".to_vec(), 1).unwrap();
+    decoded[1].0[2] = from_bytes(&forged).unwrap();
+    let tampered = to_allocvec(&decoded).unwrap();
+    assert!(!verify_shuffle_chain(&public_key, &election_id, &messages, &tampered).unwrap());
+
+    // Checking the chain against other submitted messages
+    let mut other = messages.clone();
+    other[0] = encrypt_message(&public_key, &b"yes".to_vec(), 1).unwrap();
+    assert!(!verify_shuffle_chain(&public_key, &election_id, &other, &chain).unwrap());
+
+    // Checking the chain against another election
+    assert!(!verify_shuffle_chain(&public_key, &vec![9; 32], &messages, &chain).unwrap());
+    assert!(matches!(
+        verify_shuffle_chain(&public_key, &vec![1; 20], &messages, &chain),
+        Err(Error::Decode(_))
+    ));
+
+    // Dropping the first shuffle
+    let mut decoded: Chain = from_bytes(&chain).unwrap();
+    decoded.remove(0);
+    let tampered = to_allocvec(&decoded).unwrap();
+    assert!(!verify_shuffle_chain(&public_key, &election_id, &messages, &tampered).unwrap());
+}
+
+#[test]
+fn test_encrypt_message_rejects_invalid_width() {
+    let (public_key, _) = generate_elgamal_keypair();
+
+    assert!(matches!(
+        encrypt_message(&public_key, &vec![1; 30], 1),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        encrypt_message(&public_key, &Vec::new(), 0),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let election_id = vec![1; 32];
+    let mut messages = encrypt_all(&public_key, &["short"], 1);
+    messages.extend(encrypt_all(&public_key, &["long"], 2));
+    assert!(matches!(
+        shuffle(&public_key, &election_id, &messages, &Vec::new()),
+        Err(Error::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use common::context;
+use elastic_elgamal::group::Ristretto;
+use elastic_elgamal::{Ciphertext, SumOfSquaresProof};
+use postcard::{from_bytes, to_allocvec};
+use primitives::ballots::{decrypt_result, generate_elgamal_keypair};
+use primitives::envelope::HEADER_LEN;
+use primitives::error::Error;
+use primitives::ranked_ballots::{
+    add_ranked_votes, condorcet_winner, encrypt_ranked_vote, generate_ranked_acc, schulze_winners,
+    verify_ranked_vote,
+};
+
+type Flag = (Ciphertext<Ristretto>, Vec<u8>);
+
+type Ballot = (
+    Vec<Flag>,
+    Vec<SumOfSquaresProof<Ristretto>>,
+    Vec<(Ciphertext<Ristretto>, SumOfSquaresProof<Ristretto>)>,
+    SumOfSquaresProof<Ristretto>,
+);
+
+fn verify(pk: &[u8], options_count: usize, ballot: &[u8]) -> u8 {
+    let input = (
+        U256::from(options_count),
+        Bytes::from(context(1)),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+    )
+        .abi_encode_sequence();
+
+    verify_ranked_vote(&input).unwrap()[31]
+}
+
+/// Tests the full ranked voting flow: encryption, verification, aggregation and decryption.
+#[test]
+fn test_ranked_flow() {
+    let (pk, sk) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let rankings = vec![vec![0, 1, 2], vec![1, 0, 2], vec![0, 2, 1]];
+    let mut acc = generate_ranked_acc(
+        &(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence(),
+    )
+    .unwrap();
+
+    for ranking in &rankings {
+        let ballot = encrypt_ranked_vote(&pk, ranking, options_count, &context(1))
+            .expect("failed to encrypt");
+        assert_eq!(verify(&pk, options_count, &ballot), 1);
+
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        acc = add_ranked_votes(&input).unwrap();
+    }
+
+    let matrix = decrypt_result(&sk, &acc, 1, None).expect("failed to decrypt");
+    #[rustfmt::skip]
+    assert_eq!(matrix, vec![
+        0, 2, 3,
+        1, 0, 2,
+        0, 1, 0,
+    ]);
+
+    assert_eq!(condorcet_winner(&matrix, options_count).unwrap(), Some(0));
+    assert_eq!(schulze_winners(&matrix, options_count).unwrap(), vec![0]);
+}
+
+/// Tests that only complete rankings can be encrypted.
+#[test]
+fn test_ranked_invalid_rankings() {
+    let (pk, _) = generate_elgamal_keypair();
+
+    assert!(encrypt_ranked_vote(&pk, &vec![0, 1], 3, &context(1)).is_err());
+    assert!(encrypt_ranked_vote(&pk, &vec![0, 1, 1], 3, &context(1)).is_err());
+    assert!(encrypt_ranked_vote(&pk, &vec![0, 1, 3], 3, &context(1)).is_err());
+    assert!(encrypt_ranked_vote(&pk, &vec![0], 1, &context(1)).is_err());
+}
+
+/// Tests that a ballot with a modified preference matrix is rejected.
+#[test]
+fn test_ranked_rejects_tampered_ballot() {
+    let (pk, _) = generate_elgamal_keypair();
+    let options_count = 3;
+
+    let ballot = encrypt_ranked_vote(&pk, &vec![0, 1, 2], options_count, &context(1)).unwrap();
+    let other = encrypt_ranked_vote(&pk, &vec![2, 1, 0], options_count, &context(1)).unwrap();
+    assert_eq!(verify(&pk, 4, &ballot), 0);
+
+    let (header, payload) = ballot.split_at(HEADER_LEN);
+    let (mut flags, pair_proofs, scores, order_proof): Ballot = from_bytes(payload).unwrap();
+    let (other_flags, _, _, _): Ballot = from_bytes(&other[HEADER_LEN..]).unwrap();
+
+    // Entry (0, 1) now says that candidate 1 is preferred over candidate 0
+    flags[0] = other_flags[0].clone();
+    let payload = to_allocvec(&(&flags, &pair_proofs, &scores, &order_proof)).unwrap();
+    let tampered = [header, &payload].concat();
+    assert_eq!(verify(&pk, options_count, &tampered), 0);
+
+    // A ballot missing a flag is not added to the tally
+    flags.pop();
+    let payload = to_allocvec(&(flags, pair_proofs, scores, order_proof)).unwrap();
+    let truncated = [header, &payload].concat();
+    let acc = generate_ranked_acc(
+        &(U256::from(options_count), B256::from([1; 32])).abi_encode_sequence(),
+    )
+    .unwrap();
+    let input = (Bytes::from(acc), Bytes::from(truncated)).abi_encode_sequence();
+    assert!(matches!(
+        add_ranked_votes(&input),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+/// Tests the Schulze method against a known election without a Condorcet winner.
+#[test]
+fn test_schulze_winners() {
+    // 45 voters over 5 candidates; E wins without beating every candidate head-to-head
+    #[rustfmt::skip]
+    let matrix = vec![
+        0, 20, 26, 30, 22,
+        25, 0, 16, 33, 18,
+        19, 29, 0, 17, 24,
+        15, 12, 28, 0, 14,
+        23, 27, 21, 31, 0,
+    ];
+
+    assert_eq!(condorcet_winner(&matrix, 5).unwrap(), None);
+    assert_eq!(schulze_winners(&matrix, 5).unwrap(), vec![4]);
+
+    // A tie between two candidates
+    assert_eq!(schulze_winners(&vec![0, 1, 1, 0], 2).unwrap(), vec![0, 1]);
+
+    assert!(schulze_winners(&matrix, 4).is_err());
+}
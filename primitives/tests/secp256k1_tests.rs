@@ -0,0 +1,117 @@
+mod common;
+
+use alloy_primitives::{B256, Bytes, U256};
+use alloy_sol_types::SolValue;
+use common::context;
+use primitives::backend::{encrypt_backend_vote, generate_backend_acc, generate_backend_keypair};
+use primitives::ballots::{add_votes, decrypt_result, verify_vote};
+use primitives::envelope::{HEADER_LEN, RISTRETTO_ELGAMAL, SECP256K1_ELGAMAL, inspect};
+use primitives::error::Error;
+
+fn acc(options_count: usize, scheme: u8) -> Vec<u8> {
+    let input = (
+        U256::from(options_count),
+        B256::from([1; 32]),
+        U256::from(scheme),
+    )
+        .abi_encode_sequence();
+    generate_backend_acc(&input).unwrap()
+}
+
+fn verify_input(options_count: usize, context: &[u8], pk: &[u8], ballot: &[u8]) -> Vec<u8> {
+    (
+        U256::from(options_count),
+        Bytes::from(context.to_vec()),
+        Bytes::from(pk.to_vec()),
+        Bytes::from(ballot.to_vec()),
+        U256::from(SECP256K1_ELGAMAL),
+    )
+        .abi_encode_sequence()
+}
+
+/// Tests the full voting flow of an election tallied over secp256k1.
+#[test]
+fn test_secp256k1_flow() {
+    let (pk, sk) = generate_backend_keypair(SECP256K1_ELGAMAL).unwrap();
+    assert_eq!(pk[0], SECP256K1_ELGAMAL);
+    assert_eq!(sk[0], SECP256K1_ELGAMAL);
+
+    let options_count = 3;
+    let mut acc = acc(options_count, SECP256K1_ELGAMAL);
+    assert_eq!(inspect(&acc).unwrap().1, SECP256K1_ELGAMAL);
+
+    for choice in [2, 0, 2, 2] {
+        let ballot =
+            encrypt_backend_vote(SECP256K1_ELGAMAL, &pk, choice, options_count, &context(1))
+                .unwrap();
+        assert_eq!(inspect(&ballot).unwrap().1, SECP256K1_ELGAMAL);
+
+        let input = verify_input(options_count, &context(1), &pk, &ballot);
+        assert_eq!(verify_vote(&input).unwrap()[31], 1);
+
+        let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+        acc = add_votes(&input).unwrap();
+    }
+
+    assert_eq!(decrypt_result(&sk, &acc, 1, None).unwrap(), vec![1, 0, 3]);
+}
+
+/// Tests that secp256k1 keys, ballots and accumulators cannot be mixed with Ristretto ones,
+/// and that invalid ballots are rejected.
+#[test]
+fn test_secp256k1_rejections() {
+    let options_count = 2;
+    let (pk, sk) = generate_backend_keypair(SECP256K1_ELGAMAL).unwrap();
+    let (ristretto_pk, ristretto_sk) = generate_backend_keypair(RISTRETTO_ELGAMAL).unwrap();
+
+    // Keys of one curve are rejected by the other
+    assert!(matches!(
+        encrypt_backend_vote(SECP256K1_ELGAMAL, &ristretto_pk, 0, 2, &context(1)),
+        Err(Error::Key(_))
+    ));
+    assert!(encrypt_backend_vote(RISTRETTO_ELGAMAL, &pk, 0, 2, &context(1)).is_err());
+    assert!(matches!(
+        decrypt_result(
+            &ristretto_sk,
+            &acc(options_count, SECP256K1_ELGAMAL),
+            1,
+            None
+        ),
+        Err(Error::Key(_))
+    ));
+    assert!(decrypt_result(&sk, &acc(options_count, RISTRETTO_ELGAMAL), 1, None).is_err());
+
+    // A secp256k1 ballot added to a Ristretto accumulator
+    let ballot =
+        encrypt_backend_vote(SECP256K1_ELGAMAL, &pk, 1, options_count, &context(1)).unwrap();
+    let input = (
+        Bytes::from(acc(options_count, RISTRETTO_ELGAMAL)),
+        Bytes::from(ballot.clone()),
+    )
+        .abi_encode_sequence();
+    assert!(matches!(add_votes(&input), Err(Error::InvalidInput(_))));
+
+    // Wrong number of options, another election or a Ristretto key
+    let input = verify_input(options_count + 1, &context(1), &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+    let input = verify_input(options_count, &context(9), &pk, &ballot);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+    let input = verify_input(options_count, &context(1), &ristretto_pk, &ballot);
+    assert!(matches!(verify_vote(&input), Err(Error::Key(_))));
+
+    // A flag swapped with one of another ballot, so that both flags are set
+    let other =
+        encrypt_backend_vote(SECP256K1_ELGAMAL, &pk, 0, options_count, &context(1)).unwrap();
+    let flag_len = (ballot.len() - HEADER_LEN - 64) / options_count;
+    let mut double = ballot.clone();
+    double[HEADER_LEN..HEADER_LEN + flag_len]
+        .copy_from_slice(&other[HEADER_LEN..HEADER_LEN + flag_len]);
+    let input = verify_input(options_count, &context(1), &pk, &double);
+    assert_eq!(verify_vote(&input).unwrap()[31], 0);
+
+    // A point that is not a compressed point of the curve
+    let mut malformed = ballot;
+    malformed[HEADER_LEN] = 4;
+    let input = verify_input(options_count, &context(1), &pk, &malformed);
+    assert!(matches!(verify_vote(&input), Err(Error::Decode(_))));
+}
@@ -1,20 +1,108 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+export declare function addContestVotes(data: Buffer): Buffer
+
+export declare function addLegacyVotes(data: Buffer): Buffer
+
+export declare function addMultiVotes(data: Buffer): Buffer
+
+export declare function addRankedVotes(data: Buffer): Buffer
+
+export declare function addScoreVotes(data: Buffer): Buffer
+
+/**
+ * Adds a ballot to an accumulator without checking it. BFV ballots have no validity proof,
+ * so BFV elections are for trusted clients only, and `addVotes` rejects them.
+ */
+export declare function addTrustedBackendVote(data: Buffer): Buffer
+
 export declare function addVotes(data: Buffer): Buffer
 
-export declare function createRequest(publicKey: Buffer, clientAddr: string, iatDelay: number, electionId: string): ExportedBlindingResult
+export declare function addVotesBatch(data: Buffer): Buffer
+
+export declare function auditBallot(pubKeyBytes: Buffer, ballot: Buffer, randomness: Buffer, optionsCount: number, min: number, max: number, context: Buffer): Array<number>
+
+export declare function ballotContext(electionId: Buffer, contractAddress: Buffer, token: Buffer): Buffer
+
+export declare function ballotTracker(electionId: Buffer, previous: Buffer, ballot: Buffer): Buffer
+
+export declare function bn254VerifierContract(): string
+
+export declare function combineDecryptionShares(keySet: Buffer, rawResult: Buffer, indexes: Array<number>, shares: Array<Buffer>, maxScore?: number | undefined | null, babySteps?: number | undefined | null): Array<number>
+
+export declare function combineMixShares(keySet: Buffer, chain: Buffer, indexes: Array<number>, shares: Array<Buffer>): Array<Buffer | null>
+
+export declare function combineThresholdPartials(keySet: Buffer, blindMsg: Buffer, partials: Array<Buffer>): Buffer
+
+export declare function condorcetWinner(matrix: Array<number>, optionsCount: number): number | null
+
+export declare function createPartiallyBlindRequest(publicKey: Buffer, clientAddr: string, iatDelay: number, electionId: Buffer, expiry: number): ExportedBlindingResult
+
+export declare function createRequest(publicKey: Buffer, clientAddr: string, iatDelay: number, electionId: string, scheme: number): ExportedBlindingResult
+
+export declare function dealThresholdKey(privateKey: Buffer, k: number, n: number): ExportedThresholdKey
+
+export declare function decryptMixShare(keyShare: Buffer, chain: Buffer): Buffer
+
+export declare function decryptResult(secretKey: Buffer, rawResult: Buffer, maxScore?: number | undefined | null, babySteps?: number | undefined | null): Array<number>
+
+export declare function decryptResultWithProof(secretKey: Buffer, rawResult: Buffer, maxScore?: number | undefined | null, babySteps?: number | undefined | null): ExportedTally
+
+export declare function decryptScoreResult(secretKey: Buffer, rawResult: Buffer, max: number, babySteps?: number | undefined | null): Array<number>
+
+export declare function decryptShare(keyShare: Buffer, rawResult: Buffer): Buffer
 
-export declare function decryptResult(secretKey: Buffer, rawResult: Buffer): Array<number>
+export declare function deriveKeySet(k: number, n: number, infos: Array<Buffer>): Buffer
+
+export declare function derivePublicKey(k: number, n: number, infos: Array<Buffer>): Buffer
 
 export declare function eccDecrypt(sk: Buffer, encrypted: Buffer): Buffer
 
 export declare function eccEncrypt(pk: Buffer, msg: Buffer): Buffer
 
-export declare function encryptVote(pubKeyBytes: Buffer, choice: number, optionsCount: number): Buffer
+export declare function eligibilityNullifier(msg: Buffer): Buffer
+
+export declare function encodeEligibilityBundle(registrars: Array<number>, signatures: Array<Buffer>, msgRandomizers: Array<Buffer>, electionId: Buffer, scheme: number): Buffer
+
+export declare function encodeKeyedToken(signature: Buffer, msgRandomizer: Buffer | undefined | null, keyId: Buffer, electionId: Buffer, scheme: number): Buffer
+
+export declare function encodeToken(signature: Buffer, msgRandomizer: Buffer | undefined | null, electionId: Buffer, scheme: number): Buffer
+
+export declare function encryptBackendVote(scheme: number, pubKeyBytes: Buffer, choice: number, optionsCount: number, context: Buffer): Buffer
+
+export declare function encryptContestVotes(pubKeyBytes: Buffer, manifest: Buffer, selections: Array<Array<number>>, context: Buffer): Buffer
+
+export declare function encryptMessage(pubKeyBytes: Buffer, message: Buffer, width: number): Buffer
+
+export declare function encryptMultiVote(pubKeyBytes: Buffer, choices: Array<number>, optionsCount: number, min: number, max: number, context: Buffer): Buffer
+
+export declare function encryptMultiVoteForAudit(pubKeyBytes: Buffer, choices: Array<number>, optionsCount: number, min: number, max: number, context: Buffer): ExportedAuditableBallot
+
+export declare function encryptRankedVote(pubKeyBytes: Buffer, ranking: Array<number>, optionsCount: number, context: Buffer): Buffer
+
+export declare function encryptScoreVote(pubKeyBytes: Buffer, scores: Array<number>, max: number, budget: number | undefined | null, context: Buffer): Buffer
+
+export declare function encryptVote(pubKeyBytes: Buffer, choice: number, optionsCount: number, context: Buffer): Buffer
+
+export declare function encryptVoteForAudit(pubKeyBytes: Buffer, choice: number, optionsCount: number, context: Buffer): ExportedAuditableBallot
+
+export interface ExportedAuditableBallot {
+  ballot: Buffer
+  randomness: Buffer
+}
 
 export interface ExportedBlindingResult {
   blindMsg: Buffer
   secret: Buffer
+  msgRandomizer?: Buffer
+  keyId: Buffer
+}
+
+export interface ExportedEnvelopeHeader {
+  version: number
+  scheme: number
+  kind: number
+  electionId: Buffer
 }
 
 export interface ExportedKeyPair {
@@ -22,14 +110,111 @@ export interface ExportedKeyPair {
   private: Buffer
 }
 
+export interface ExportedTally {
+  counts: Array<number>
+  proof: Buffer
+}
+
+export interface ExportedThresholdKey {
+  public: Buffer
+  keySet: Buffer
+  shares: Array<Buffer>
+}
+
 export declare function generateAcc(data: Buffer): Buffer
 
+export declare function generateBackendAcc(data: Buffer): Buffer
+
+export declare function generateBackendKeypair(scheme: number): ExportedKeyPair
+
+export declare function generateContestAcc(manifest: Buffer, electionId: Buffer): Buffer
+
 export declare function generateElgamalKeypair(): ExportedKeyPair
 
+export declare function generatePartiallyBlindKeypair(): ExportedKeyPair
+
+export declare function generateParticipant(k: number, n: number, index: number): Buffer
+
+export declare function generateRankedAcc(data: Buffer): Buffer
+
 export declare function generateRsaKeypair(): ExportedKeyPair
 
+export declare function getCommitment(state: Buffer): Buffer
+
+export declare function getKeySet(keyShare: Buffer): Buffer
+
+export declare function getPublicInfo(state: Buffer): Buffer
+
+export declare function getSecretShare(state: Buffer, participantIndex: number): Buffer
+
+export declare function getSharedPublicKey(keyShare: Buffer): Buffer
+
+export declare function insertCommitments(state: Buffer, commitments: Array<Buffer>): Buffer
+
+export declare function insertPublicInfos(state: Buffer, infos: Array<Buffer>): Buffer
+
+export declare function insertSecretShares(state: Buffer, shares: Array<Buffer>): Buffer
+
+export declare function inspectEnvelope(blob: Buffer): ExportedEnvelopeHeader
+
+export declare function keyId(publicKey: Buffer): Buffer
+
+export declare function migrateAcc(acc: Buffer, electionId: Buffer, ballots: number): Buffer
+
+export declare function migrateBallot(ballot: Buffer, electionId: Buffer): Buffer
+
+export declare function migrateToken(token: Buffer, electionId: Buffer): Buffer
+
+export declare function nullifier(msg: Buffer, signature: Buffer): Buffer
+
+export declare function registerKey(registry: Buffer, publicKey: Buffer, notBefore: number, notAfter: number): Buffer
+
+export declare function resolveKey(registry: Buffer, keyId: Buffer, timestamp: number): Buffer
+
+export declare function retireKey(registry: Buffer, keyId: Buffer): Buffer
+
+export declare function schulzeWinners(matrix: Array<number>, optionsCount: number): Array<number>
+
+export declare function shuffle(pubKeyBytes: Buffer, electionId: Buffer, messages: Array<Buffer>, chain: Buffer): Buffer
+
 export declare function sign(secretKey: Buffer, blindMsg: Buffer): Buffer
 
-export declare function unblind(publicKey: Buffer, secret: Buffer, blindSig: Buffer, clientAddr: string, iatDelay: number, electionId: string): Buffer
+export declare function signPartiallyBlind(secretKey: Buffer, blindMsg: Buffer, electionId: Buffer, expiry: number): Buffer
+
+export declare function signThresholdPartial(keySet: Buffer, keyShare: Buffer, blindMsg: Buffer): Buffer
+
+export declare function splitContestResults(manifest: Buffer, results: Array<number>): Array<Array<number>>
+
+export declare function trackerCode(tracker: Buffer): string
+
+export declare function unblind(publicKey: Buffer, secret: Buffer, msgRandomizer: Buffer | undefined | null, blindSig: Buffer, clientAddr: string, iatDelay: number, electionId: string, scheme: number): Buffer
+
+export declare function unblindPartiallyBlind(publicKey: Buffer, secret: Buffer, msgRandomizer: Buffer, blindSig: Buffer, clientAddr: string, iatDelay: number, electionId: Buffer, expiry: number): Buffer
+
+export declare function verify(publicKey: Buffer, signatureBytes: Buffer, msgRandomizer: Buffer | undefined | null, msg: Buffer, scheme: number): void
+
+export declare function verifyByKeyId(registry: Buffer, keyId: Buffer, signatureBytes: Buffer, msgRandomizer: Buffer | undefined | null, msg: Buffer, timestamp: number, scheme: number): boolean
+
+export declare function verifyContestVotes(data: Buffer): Buffer
+
+export declare function verifyDecryptionShare(keySet: Buffer, rawResult: Buffer, index: number, share: Buffer): boolean
+
+export declare function verifyEligibilityBundle(publicKeys: Array<Buffer>, k: number, bundle: Buffer, msg: Buffer, electionId: Buffer, scheme: number): boolean
+
+export declare function verifyLegacyVote(data: Buffer): Buffer
+
+export declare function verifyMix(data: Buffer): Buffer
+
+export declare function verifyMultiVote(data: Buffer): Buffer
+
+export declare function verifyPartiallyBlind(publicKey: Buffer, signatureBytes: Buffer, msgRandomizer: Buffer, msg: Buffer, electionId: Buffer, expiry: number): boolean
+
+export declare function verifyRankedVote(data: Buffer): Buffer
+
+export declare function verifyScoreVote(data: Buffer): Buffer
+
+export declare function verifyTally(data: Buffer): Buffer
+
+export declare function verifyThresholdPartial(keySet: Buffer, blindMsg: Buffer, partial: Buffer): boolean
 
-export declare function verify(publicKey: Buffer, signatureBytes: Buffer, msg: Buffer): void
+export declare function verifyVotesBatch(data: Buffer): Buffer
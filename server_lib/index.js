@@ -3,15 +3,93 @@ if (!nativeBinding) {
   throw Error("Couldn't load binary lib");
 }
 module.exports = nativeBinding
+module.exports.addContestVotes = nativeBinding.addContestVotes
+module.exports.addLegacyVotes = nativeBinding.addLegacyVotes
+module.exports.addMultiVotes = nativeBinding.addMultiVotes
+module.exports.addRankedVotes = nativeBinding.addRankedVotes
+module.exports.addScoreVotes = nativeBinding.addScoreVotes
+module.exports.addTrustedBackendVote = nativeBinding.addTrustedBackendVote
 module.exports.addVotes = nativeBinding.addVotes
+module.exports.addVotesBatch = nativeBinding.addVotesBatch
+module.exports.auditBallot = nativeBinding.auditBallot
+module.exports.ballotContext = nativeBinding.ballotContext
+module.exports.ballotTracker = nativeBinding.ballotTracker
+module.exports.bn254VerifierContract = nativeBinding.bn254VerifierContract
+module.exports.combineDecryptionShares = nativeBinding.combineDecryptionShares
+module.exports.combineMixShares = nativeBinding.combineMixShares
+module.exports.combineThresholdPartials = nativeBinding.combineThresholdPartials
+module.exports.condorcetWinner = nativeBinding.condorcetWinner
+module.exports.createPartiallyBlindRequest = nativeBinding.createPartiallyBlindRequest
 module.exports.createRequest = nativeBinding.createRequest
+module.exports.dealThresholdKey = nativeBinding.dealThresholdKey
+module.exports.decryptMixShare = nativeBinding.decryptMixShare
 module.exports.decryptResult = nativeBinding.decryptResult
+module.exports.decryptResultWithProof = nativeBinding.decryptResultWithProof
+module.exports.decryptScoreResult = nativeBinding.decryptScoreResult
+module.exports.decryptShare = nativeBinding.decryptShare
+module.exports.deriveKeySet = nativeBinding.deriveKeySet
+module.exports.derivePublicKey = nativeBinding.derivePublicKey
 module.exports.eccDecrypt = nativeBinding.eccDecrypt
 module.exports.eccEncrypt = nativeBinding.eccEncrypt
+module.exports.eligibilityNullifier = nativeBinding.eligibilityNullifier
+module.exports.encodeEligibilityBundle = nativeBinding.encodeEligibilityBundle
+module.exports.encodeKeyedToken = nativeBinding.encodeKeyedToken
+module.exports.encodeToken = nativeBinding.encodeToken
+module.exports.encryptBackendVote = nativeBinding.encryptBackendVote
+module.exports.encryptContestVotes = nativeBinding.encryptContestVotes
+module.exports.encryptMessage = nativeBinding.encryptMessage
+module.exports.encryptMultiVote = nativeBinding.encryptMultiVote
+module.exports.encryptMultiVoteForAudit = nativeBinding.encryptMultiVoteForAudit
+module.exports.encryptRankedVote = nativeBinding.encryptRankedVote
+module.exports.encryptScoreVote = nativeBinding.encryptScoreVote
 module.exports.encryptVote = nativeBinding.encryptVote
+module.exports.encryptVoteForAudit = nativeBinding.encryptVoteForAudit
 module.exports.generateAcc = nativeBinding.generateAcc
+module.exports.generateBackendAcc = nativeBinding.generateBackendAcc
+module.exports.generateBackendKeypair = nativeBinding.generateBackendKeypair
+module.exports.generateContestAcc = nativeBinding.generateContestAcc
 module.exports.generateElgamalKeypair = nativeBinding.generateElgamalKeypair
+module.exports.generatePartiallyBlindKeypair = nativeBinding.generatePartiallyBlindKeypair
+module.exports.generateParticipant = nativeBinding.generateParticipant
+module.exports.generateRankedAcc = nativeBinding.generateRankedAcc
 module.exports.generateRsaKeypair = nativeBinding.generateRsaKeypair
+module.exports.getCommitment = nativeBinding.getCommitment
+module.exports.getKeySet = nativeBinding.getKeySet
+module.exports.getPublicInfo = nativeBinding.getPublicInfo
+module.exports.getSecretShare = nativeBinding.getSecretShare
+module.exports.getSharedPublicKey = nativeBinding.getSharedPublicKey
+module.exports.insertCommitments = nativeBinding.insertCommitments
+module.exports.insertPublicInfos = nativeBinding.insertPublicInfos
+module.exports.insertSecretShares = nativeBinding.insertSecretShares
+module.exports.inspectEnvelope = nativeBinding.inspectEnvelope
+module.exports.keyId = nativeBinding.keyId
+module.exports.migrateAcc = nativeBinding.migrateAcc
+module.exports.migrateBallot = nativeBinding.migrateBallot
+module.exports.migrateToken = nativeBinding.migrateToken
+module.exports.nullifier = nativeBinding.nullifier
+module.exports.registerKey = nativeBinding.registerKey
+module.exports.resolveKey = nativeBinding.resolveKey
+module.exports.retireKey = nativeBinding.retireKey
+module.exports.schulzeWinners = nativeBinding.schulzeWinners
+module.exports.shuffle = nativeBinding.shuffle
 module.exports.sign = nativeBinding.sign
+module.exports.signPartiallyBlind = nativeBinding.signPartiallyBlind
+module.exports.signThresholdPartial = nativeBinding.signThresholdPartial
+module.exports.splitContestResults = nativeBinding.splitContestResults
+module.exports.trackerCode = nativeBinding.trackerCode
 module.exports.unblind = nativeBinding.unblind
+module.exports.unblindPartiallyBlind = nativeBinding.unblindPartiallyBlind
 module.exports.verify = nativeBinding.verify
+module.exports.verifyByKeyId = nativeBinding.verifyByKeyId
+module.exports.verifyContestVotes = nativeBinding.verifyContestVotes
+module.exports.verifyDecryptionShare = nativeBinding.verifyDecryptionShare
+module.exports.verifyEligibilityBundle = nativeBinding.verifyEligibilityBundle
+module.exports.verifyLegacyVote = nativeBinding.verifyLegacyVote
+module.exports.verifyMix = nativeBinding.verifyMix
+module.exports.verifyMultiVote = nativeBinding.verifyMultiVote
+module.exports.verifyPartiallyBlind = nativeBinding.verifyPartiallyBlind
+module.exports.verifyRankedVote = nativeBinding.verifyRankedVote
+module.exports.verifyScoreVote = nativeBinding.verifyScoreVote
+module.exports.verifyTally = nativeBinding.verifyTally
+module.exports.verifyThresholdPartial = nativeBinding.verifyThresholdPartial
+module.exports.verifyVotesBatch = nativeBinding.verifyVotesBatch
@@ -0,0 +1,60 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use crate::ExportedKeyPair;
+
+#[napi]
+pub fn generate_backend_keypair(scheme: u32) -> Result<ExportedKeyPair> {
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  let keypair = primitives::backend::generate_backend_keypair(scheme)
+    .map_err(|e| Error::from_reason(e.to_string()))?;
+
+  Ok(ExportedKeyPair {
+    public: keypair.0.into(),
+    private: keypair.1.into(),
+  })
+}
+
+#[napi]
+pub fn generate_backend_acc(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::backend::generate_backend_acc(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_backend_vote(
+  scheme: u32,
+  pub_key_bytes: Buffer,
+  choice: u32,
+  options_count: u32,
+  context: Buffer,
+) -> Result<Buffer> {
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::backend::encrypt_backend_vote(
+    scheme,
+    &pub_key_bytes.into(),
+    choice as usize,
+    options_count as usize,
+    &context.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+/// Adds a ballot to an accumulator without checking it. BFV ballots have no validity proof,
+/// so BFV elections are for trusted clients only, and `addVotes` rejects them.
+#[napi]
+pub fn add_trusted_backend_vote(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::backend::add_trusted_backend_vote(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn bn254_verifier_contract() -> String {
+  primitives::bn254::VERIFIER_CONTRACT.to_string()
+}
@@ -4,6 +4,18 @@ use napi_derive::napi;
 
 use crate::ExportedKeyPair;
 
+#[napi(object)]
+pub struct ExportedTally {
+  pub counts: Vec<i64>,
+  pub proof: Buffer,
+}
+
+#[napi(object)]
+pub struct ExportedAuditableBallot {
+  pub ballot: Buffer,
+  pub randomness: Buffer,
+}
+
 #[napi]
 pub fn generate_elgamal_keypair() -> Result<ExportedKeyPair> {
   let keypair = primitives::ballots::generate_elgamal_keypair();
@@ -15,11 +27,150 @@ pub fn generate_elgamal_keypair() -> Result<ExportedKeyPair> {
 }
 
 #[napi]
-pub fn encrypt_vote(pub_key_bytes: Buffer, choice: u32, options_count: u32) -> Result<Buffer> {
+pub fn ballot_context(
+  election_id: Buffer,
+  contract_address: Buffer,
+  token: Buffer,
+) -> Result<Buffer> {
+  primitives::ballots::ballot_context(&election_id.into(), &contract_address.into(), &token.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn ballot_tracker(election_id: Buffer, previous: Buffer, ballot: Buffer) -> Result<Buffer> {
+  primitives::ballots::ballot_tracker(&election_id.into(), &previous.into(), &ballot.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn tracker_code(tracker: Buffer) -> Result<String> {
+  primitives::ballots::tracker_code(&tracker.into()).map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_vote(
+  pub_key_bytes: Buffer,
+  choice: u32,
+  options_count: u32,
+  context: Buffer,
+) -> Result<Buffer> {
   primitives::ballots::encrypt_vote(
     &pub_key_bytes.into(),
     choice as usize,
     options_count as usize,
+    &context.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_multi_vote(
+  pub_key_bytes: Buffer,
+  choices: Vec<u32>,
+  options_count: u32,
+  min: u32,
+  max: u32,
+  context: Buffer,
+) -> Result<Buffer> {
+  let choices_vec = choices.into_iter().map(|choice| choice as usize).collect();
+  primitives::ballots::encrypt_multi_vote(
+    &pub_key_bytes.into(),
+    &choices_vec,
+    options_count as usize,
+    min as usize,
+    max as usize,
+    &context.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_vote_for_audit(
+  pub_key_bytes: Buffer,
+  choice: u32,
+  options_count: u32,
+  context: Buffer,
+) -> Result<ExportedAuditableBallot> {
+  primitives::ballots::encrypt_vote_for_audit(
+    &pub_key_bytes.into(),
+    choice as usize,
+    options_count as usize,
+    &context.into(),
+  )
+  .map(|(ballot, randomness)| ExportedAuditableBallot {
+    ballot: ballot.into(),
+    randomness: randomness.into(),
+  })
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_multi_vote_for_audit(
+  pub_key_bytes: Buffer,
+  choices: Vec<u32>,
+  options_count: u32,
+  min: u32,
+  max: u32,
+  context: Buffer,
+) -> Result<ExportedAuditableBallot> {
+  let choices_vec = choices.into_iter().map(|choice| choice as usize).collect();
+  primitives::ballots::encrypt_multi_vote_for_audit(
+    &pub_key_bytes.into(),
+    &choices_vec,
+    options_count as usize,
+    min as usize,
+    max as usize,
+    &context.into(),
+  )
+  .map(|(ballot, randomness)| ExportedAuditableBallot {
+    ballot: ballot.into(),
+    randomness: randomness.into(),
+  })
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn audit_ballot(
+  pub_key_bytes: Buffer,
+  ballot: Buffer,
+  randomness: Buffer,
+  options_count: u32,
+  min: u32,
+  max: u32,
+  context: Buffer,
+) -> Result<Vec<u32>> {
+  primitives::ballots::audit_ballot(
+    &pub_key_bytes.into(),
+    &ballot.into(),
+    &randomness.into(),
+    options_count as usize,
+    min as usize,
+    max as usize,
+    &context.into(),
+  )
+  .map(|choices| choices.into_iter().map(|choice| choice as u32).collect())
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_score_vote(
+  pub_key_bytes: Buffer,
+  scores: Vec<u32>,
+  max: u32,
+  budget: Option<u32>,
+  context: Buffer,
+) -> Result<Buffer> {
+  let scores_vec = scores.into_iter().map(|score| score as u64).collect();
+  primitives::ballots::encrypt_score_vote(
+    &pub_key_bytes.into(),
+    &scores_vec,
+    max as u64,
+    budget.map(|budget| budget as u64),
+    &context.into(),
   )
   .map(Into::into)
   .map_err(|e| Error::from_reason(e.to_string()))
@@ -28,20 +179,243 @@ pub fn encrypt_vote(pub_key_bytes: Buffer, choice: u32, options_count: u32) -> R
 #[napi]
 pub fn generate_acc(data: Buffer) -> Result<Buffer> {
   let data_vec: Vec<u8> = data.into();
-  Ok(primitives::ballots::generate_acc(&data_vec).into())
+  primitives::ballots::generate_acc(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
 }
 
 #[napi]
 pub fn add_votes(data: Buffer) -> Result<Buffer> {
   let data_vec: Vec<u8> = data.into();
-  Ok(primitives::ballots::add_votes(&data_vec).into())
+  primitives::ballots::add_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_votes_batch(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::verify_votes_batch(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn add_votes_batch(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::add_votes_batch(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_multi_vote(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::verify_multi_vote(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn add_multi_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::add_multi_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_legacy_vote(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::verify_legacy_vote(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
 }
 
 #[napi]
-pub fn decrypt_result(secret_key: Buffer, raw_result: Buffer) -> Result<Vec<i64>> {
+pub fn add_legacy_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::add_legacy_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_score_vote(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::verify_score_vote(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn add_score_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::add_score_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encode_token(
+  signature: Buffer,
+  msg_randomizer: Option<Buffer>,
+  election_id: Buffer,
+  scheme: u32,
+) -> Result<Buffer> {
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::ballots::encode_token(
+    &signature.into(),
+    &msg_randomizer.map(Into::into).unwrap_or_default(),
+    &election_id.into(),
+    scheme,
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encode_keyed_token(
+  signature: Buffer,
+  msg_randomizer: Option<Buffer>,
+  key_id: Buffer,
+  election_id: Buffer,
+  scheme: u32,
+) -> Result<Buffer> {
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::ballots::encode_keyed_token(
+    &signature.into(),
+    &msg_randomizer.map(Into::into).unwrap_or_default(),
+    &key_id.into(),
+    &election_id.into(),
+    scheme,
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn decrypt_result(
+  secret_key: Buffer,
+  raw_result: Buffer,
+  max_score: Option<u32>,
+  baby_steps: Option<u32>,
+) -> Result<Vec<i64>> {
   let secret_key_vec: Vec<u8> = secret_key.into();
   let raw_result_vec: Vec<u8> = raw_result.into();
-  primitives::ballots::decrypt_result(&secret_key_vec, &raw_result_vec, 10_000)
-    .map(|v| v.into_iter().map(|x| x as i64).collect())
+  let max_score = max_score.unwrap_or(1) as u64;
+  primitives::ballots::decrypt_result(
+    &secret_key_vec,
+    &raw_result_vec,
+    max_score,
+    baby_steps.map(u64::from),
+  )
+  .map(|v| v.into_iter().map(|x| x as i64).collect())
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn decrypt_score_result(
+  secret_key: Buffer,
+  raw_result: Buffer,
+  max: u32,
+  baby_steps: Option<u32>,
+) -> Result<Vec<i64>> {
+  let secret_key_vec: Vec<u8> = secret_key.into();
+  let raw_result_vec: Vec<u8> = raw_result.into();
+  primitives::ballots::decrypt_result(
+    &secret_key_vec,
+    &raw_result_vec,
+    max as u64,
+    baby_steps.map(u64::from),
+  )
+  .map(|v| v.into_iter().map(|x| x as i64).collect())
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn decrypt_result_with_proof(
+  secret_key: Buffer,
+  raw_result: Buffer,
+  max_score: Option<u32>,
+  baby_steps: Option<u32>,
+) -> Result<ExportedTally> {
+  let secret_key_vec: Vec<u8> = secret_key.into();
+  let raw_result_vec: Vec<u8> = raw_result.into();
+  let max_score = max_score.unwrap_or(1) as u64;
+  primitives::ballots::decrypt_result_with_proof(
+    &secret_key_vec,
+    &raw_result_vec,
+    max_score,
+    baby_steps.map(u64::from),
+  )
+  .map(|(counts, proof)| ExportedTally {
+    counts: counts.into_iter().map(|x| x as i64).collect(),
+    proof: proof.into(),
+  })
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_tally(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ballots::verify_tally(&data_vec)
+    .map(Into::into)
     .map_err(|e| Error::from_reason(e.to_string()))
 }
+
+#[napi]
+pub fn decrypt_share(key_share: Buffer, raw_result: Buffer) -> Result<Buffer> {
+  let key_share_vec: Vec<u8> = key_share.into();
+  let raw_result_vec: Vec<u8> = raw_result.into();
+  primitives::ballots::decrypt_share(&key_share_vec, &raw_result_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_decryption_share(
+  key_set: Buffer,
+  raw_result: Buffer,
+  index: u32,
+  share: Buffer,
+) -> Result<bool> {
+  let key_set_vec: Vec<u8> = key_set.into();
+  let raw_result_vec: Vec<u8> = raw_result.into();
+  let share_vec: Vec<u8> = share.into();
+  primitives::ballots::verify_decryption_share(
+    &key_set_vec,
+    &raw_result_vec,
+    index as usize,
+    &share_vec,
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn combine_decryption_shares(
+  key_set: Buffer,
+  raw_result: Buffer,
+  indexes: Vec<u32>,
+  shares: Vec<Buffer>,
+  max_score: Option<u32>,
+  baby_steps: Option<u32>,
+) -> Result<Vec<i64>> {
+  let key_set_vec: Vec<u8> = key_set.into();
+  let raw_result_vec: Vec<u8> = raw_result.into();
+  let shares_vec = indexes
+    .into_iter()
+    .map(|index| index as usize)
+    .zip(shares.into_iter().map(Into::into))
+    .collect();
+  let max_score = max_score.unwrap_or(1) as u64;
+  primitives::ballots::combine_decryption_shares(
+    &key_set_vec,
+    &raw_result_vec,
+    &shares_vec,
+    max_score,
+    baby_steps.map(u64::from),
+  )
+  .map(|v| v.into_iter().map(|x| x as i64).collect())
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
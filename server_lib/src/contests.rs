@@ -0,0 +1,62 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+#[napi]
+pub fn generate_contest_acc(manifest: Buffer, election_id: Buffer) -> Result<Buffer> {
+  let manifest_vec: Vec<u8> = manifest.into();
+  primitives::contests::generate_contest_acc(&manifest_vec, &election_id.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_contest_votes(
+  pub_key_bytes: Buffer,
+  manifest: Buffer,
+  selections: Vec<Vec<u32>>,
+  context: Buffer,
+) -> Result<Buffer> {
+  let selections_vec = selections
+    .into_iter()
+    .map(|selection| selection.into_iter().map(|x| x as u64).collect())
+    .collect();
+  primitives::contests::encrypt_contest_votes(
+    &pub_key_bytes.into(),
+    &manifest.into(),
+    &selections_vec,
+    &context.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_contest_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::contests::verify_contest_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn add_contest_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::contests::add_contest_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn split_contest_results(manifest: Buffer, results: Vec<i64>) -> Result<Vec<Vec<i64>>> {
+  let manifest_vec: Vec<u8> = manifest.into();
+  let results_vec = results.into_iter().map(|x| x as u64).collect();
+  primitives::contests::split_contest_results(&manifest_vec, &results_vec)
+    .map(|contests| {
+      contests
+        .into_iter()
+        .map(|v| v.into_iter().map(|x| x as i64).collect())
+        .collect()
+    })
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
@@ -0,0 +1,92 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+fn into_vecs(buffers: Vec<Buffer>) -> Vec<Vec<u8>> {
+  buffers.into_iter().map(Into::into).collect()
+}
+
+#[napi]
+pub fn generate_participant(k: u32, n: u32, index: u32) -> Result<Buffer> {
+  primitives::dkg::generate_participant(k as usize, n as usize, index as usize)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn get_commitment(state: Buffer) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::get_commitment(&state_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn insert_commitments(state: Buffer, commitments: Vec<Buffer>) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::insert_commitments(&state_vec, &into_vecs(commitments))
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn get_public_info(state: Buffer) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::get_public_info(&state_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn insert_public_infos(state: Buffer, infos: Vec<Buffer>) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::insert_public_infos(&state_vec, &into_vecs(infos))
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn get_secret_share(state: Buffer, participant_index: u32) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::get_secret_share(&state_vec, participant_index as usize)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn insert_secret_shares(state: Buffer, shares: Vec<Buffer>) -> Result<Buffer> {
+  let state_vec: Vec<u8> = state.into();
+  primitives::dkg::insert_secret_shares(&state_vec, &into_vecs(shares))
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn get_shared_public_key(key_share: Buffer) -> Result<Buffer> {
+  let key_share_vec: Vec<u8> = key_share.into();
+  primitives::dkg::get_shared_public_key(&key_share_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn get_key_set(key_share: Buffer) -> Result<Buffer> {
+  let key_share_vec: Vec<u8> = key_share.into();
+  primitives::dkg::get_key_set(&key_share_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn derive_key_set(k: u32, n: u32, infos: Vec<Buffer>) -> Result<Buffer> {
+  primitives::dkg::derive_key_set(k as usize, n as usize, &into_vecs(infos))
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn derive_public_key(k: u32, n: u32, infos: Vec<Buffer>) -> Result<Buffer> {
+  primitives::dkg::derive_public_key(k as usize, n as usize, &into_vecs(infos))
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
@@ -7,7 +7,7 @@ pub fn ecc_encrypt(pk: Buffer, msg: Buffer) -> Result<Buffer> {
   let msg_vec: Vec<u8> = msg.into();
   primitives::ecc::ecc_encrypt(&pk_vec, &msg_vec)
     .map(Into::into)
-    .map_err(|e| Error::from_reason(e))
+    .map_err(|e| Error::from_reason(e.to_string()))
 }
 
 #[napi]
@@ -16,5 +16,5 @@ pub fn ecc_decrypt(sk: Buffer, encrypted: Buffer) -> Result<Buffer> {
   let encrypted_vec: Vec<u8> = encrypted.into();
   primitives::ecc::ecc_decrypt(&sk_vec, &encrypted_vec)
     .map(Into::into)
-    .map_err(|e| Error::from_reason(e))
+    .map_err(|e| Error::from_reason(e.to_string()))
 }
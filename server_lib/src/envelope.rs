@@ -0,0 +1,45 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+#[napi(object)]
+pub struct ExportedEnvelopeHeader {
+  pub version: u32,
+  pub scheme: u32,
+  pub kind: u32,
+  pub election_id: Buffer,
+}
+
+#[napi]
+pub fn inspect_envelope(blob: Buffer) -> Result<ExportedEnvelopeHeader> {
+  let (version, scheme, kind, election_id) =
+    primitives::envelope::inspect(&blob.into()).map_err(|e| Error::from_reason(e.to_string()))?;
+
+  Ok(ExportedEnvelopeHeader {
+    version: version.into(),
+    scheme: scheme.into(),
+    kind: kind.into(),
+    election_id: election_id.into(),
+  })
+}
+
+#[napi]
+pub fn migrate_ballot(ballot: Buffer, election_id: Buffer) -> Result<Buffer> {
+  primitives::envelope::migrate_ballot(&ballot.into(), &election_id.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn migrate_acc(acc: Buffer, election_id: Buffer, ballots: i64) -> Result<Buffer> {
+  primitives::envelope::migrate_acc(&acc.into(), &election_id.into(), ballots as u64)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn migrate_token(token: Buffer, election_id: Buffer) -> Result<Buffer> {
+  primitives::envelope::migrate_token(&token.into(), &election_id.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
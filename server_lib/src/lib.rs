@@ -1,7 +1,13 @@
 #![deny(clippy::all)]
 
+pub mod backend;
 pub mod ballots;
+pub mod contests;
+pub mod dkg;
 pub mod ecc;
+pub mod envelope;
+pub mod mixnet;
+pub mod ranked_ballots;
 pub mod signatures;
 
 use napi::bindgen_prelude::*;
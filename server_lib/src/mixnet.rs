@@ -0,0 +1,65 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+#[napi]
+pub fn encrypt_message(pub_key_bytes: Buffer, message: Buffer, width: u32) -> Result<Buffer> {
+  primitives::mixnet::encrypt_message(&pub_key_bytes.into(), &message.into(), width as usize)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn shuffle(
+  pub_key_bytes: Buffer,
+  election_id: Buffer,
+  messages: Vec<Buffer>,
+  chain: Buffer,
+) -> Result<Buffer> {
+  let messages_vec = messages.into_iter().map(Into::into).collect();
+  primitives::mixnet::shuffle(
+    &pub_key_bytes.into(),
+    &election_id.into(),
+    &messages_vec,
+    &chain.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_mix(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::mixnet::verify_mix(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn decrypt_mix_share(key_share: Buffer, chain: Buffer) -> Result<Buffer> {
+  primitives::mixnet::decrypt_share(&key_share.into(), &chain.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn combine_mix_shares(
+  key_set: Buffer,
+  chain: Buffer,
+  indexes: Vec<u32>,
+  shares: Vec<Buffer>,
+) -> Result<Vec<Option<Buffer>>> {
+  let shares_vec = indexes
+    .into_iter()
+    .map(|index| index as usize)
+    .zip(shares.into_iter().map(Into::into))
+    .collect();
+  primitives::mixnet::combine_decryption_shares(&key_set.into(), &chain.into(), &shares_vec)
+    .map(|messages| {
+      messages
+        .into_iter()
+        .map(|message| message.map(Into::into))
+        .collect()
+    })
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
@@ -0,0 +1,63 @@
+use napi::bindgen_prelude::Buffer;
+use napi::{Error, Result};
+use napi_derive::napi;
+
+fn into_counts(matrix: Vec<i64>) -> Vec<u64> {
+  matrix.into_iter().map(|x| x as u64).collect()
+}
+
+#[napi]
+pub fn generate_ranked_acc(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ranked_ballots::generate_ranked_acc(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encrypt_ranked_vote(
+  pub_key_bytes: Buffer,
+  ranking: Vec<u32>,
+  options_count: u32,
+  context: Buffer,
+) -> Result<Buffer> {
+  let ranking_vec = ranking.into_iter().map(|c| c as usize).collect();
+  primitives::ranked_ballots::encrypt_ranked_vote(
+    &pub_key_bytes.into(),
+    &ranking_vec,
+    options_count as usize,
+    &context.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_ranked_vote(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ranked_ballots::verify_ranked_vote(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn add_ranked_votes(data: Buffer) -> Result<Buffer> {
+  let data_vec: Vec<u8> = data.into();
+  primitives::ranked_ballots::add_ranked_votes(&data_vec)
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn condorcet_winner(matrix: Vec<i64>, options_count: u32) -> Result<Option<u32>> {
+  primitives::ranked_ballots::condorcet_winner(&into_counts(matrix), options_count as usize)
+    .map(|winner| winner.map(|w| w as u32))
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn schulze_winners(matrix: Vec<i64>, options_count: u32) -> Result<Vec<u32>> {
+  primitives::ranked_ballots::schulze_winners(&into_counts(matrix), options_count as usize)
+    .map(|winners| winners.into_iter().map(|w| w as u32).collect())
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
@@ -10,6 +10,15 @@ use crate::ExportedKeyPair;
 pub struct ExportedBlindingResult {
   pub blind_msg: Buffer,
   pub secret: Buffer,
+  pub msg_randomizer: Option<Buffer>,
+  pub key_id: Buffer,
+}
+
+#[napi(object)]
+pub struct ExportedThresholdKey {
+  pub public: Buffer,
+  pub key_set: Buffer,
+  pub shares: Vec<Buffer>,
 }
 
 #[napi]
@@ -18,14 +27,23 @@ pub fn create_request(
   client_addr: String,
   iat_delay: u32,
   election_id: String,
+  scheme: u32,
 ) -> Result<ExportedBlindingResult> {
   let encoded = (client_addr, election_id, U256::from(iat_delay)).abi_encode_sequence();
   let public_key_vec: Vec<u8> = public_key.into();
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
 
-  match primitives::blind_signatures::create_request(&public_key_vec, &encoded) {
+  let key_id = primitives::blind_signatures::key_id(&public_key_vec)
+    .map_err(|e| Error::from_reason(e.to_string()))?;
+
+  match primitives::blind_signatures::create_request(&public_key_vec, &encoded, scheme) {
     Ok(request) => Ok(ExportedBlindingResult {
-      blind_msg: request.0.into(),
-      secret: request.1.into(),
+      blind_msg: request.blind_message.into(),
+      secret: request.secret.into(),
+      msg_randomizer: Some(request.msg_randomizer)
+        .filter(|randomizer| !randomizer.is_empty())
+        .map(Into::into),
+      key_id: key_id.into(),
     }),
     Err(e) => Err(Error::from_reason(e.to_string())),
   }
@@ -52,27 +70,325 @@ pub fn sign(secret_key: Buffer, blind_msg: Buffer) -> Result<Buffer> {
 }
 
 #[napi]
+#[allow(clippy::too_many_arguments)]
 pub fn unblind(
   public_key: Buffer,
   secret: Buffer,
+  msg_randomizer: Option<Buffer>,
   blind_sig: Buffer,
   client_addr: String,
   iat_delay: u32,
   election_id: String,
+  scheme: u32,
 ) -> Result<Buffer> {
   let encoded = (client_addr, election_id, U256::from(iat_delay)).abi_encode_sequence();
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
 
   let public_key_vec: Vec<u8> = public_key.into();
-  primitives::blind_signatures::unblind(&public_key_vec, &encoded, secret.into(), blind_sig.into())
-    .map(Into::into)
-    .map_err(|e| Error::from_reason(e.to_string()))
+  primitives::blind_signatures::unblind(
+    &public_key_vec,
+    &encoded,
+    secret.into(),
+    &msg_randomizer.map(Into::into).unwrap_or_default(),
+    blind_sig.into(),
+    scheme,
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
 }
 
 #[napi]
-pub fn verify(public_key: Buffer, signature_bytes: Buffer, msg: Buffer) -> Result<()> {
+pub fn verify(
+  public_key: Buffer,
+  signature_bytes: Buffer,
+  msg_randomizer: Option<Buffer>,
+  msg: Buffer,
+  scheme: u32,
+) -> Result<()> {
   let public_key_vec: Vec<u8> = public_key.into();
   let msg_vec: Vec<u8> = msg.into();
-  primitives::blind_signatures::verify(&public_key_vec, signature_bytes.into(), &msg_vec)
-    .map(|_| ())
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::blind_signatures::verify(
+    &public_key_vec,
+    signature_bytes.into(),
+    &msg_randomizer.map(Into::into).unwrap_or_default(),
+    &msg_vec,
+    scheme,
+  )
+  .map(|_| ())
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn nullifier(msg: Buffer, signature: Buffer) -> Buffer {
+  primitives::blind_signatures::nullifier(&msg.into(), &signature.into()).into()
+}
+
+#[napi]
+pub fn generate_partially_blind_keypair() -> Result<ExportedKeyPair> {
+  primitives::blind_signatures::generate_partially_blind_keypair()
+    .map(|keypair| ExportedKeyPair {
+      public: keypair.public.into(),
+      private: keypair.private.into(),
+    })
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn create_partially_blind_request(
+  public_key: Buffer,
+  client_addr: String,
+  iat_delay: u32,
+  election_id: Buffer,
+  expiry: u32,
+) -> Result<ExportedBlindingResult> {
+  let encoded = (client_addr, U256::from(iat_delay)).abi_encode_sequence();
+  let public_key_vec: Vec<u8> = public_key.into();
+  let key_id = primitives::blind_signatures::key_id(&public_key_vec)
+    .map_err(|e| Error::from_reason(e.to_string()))?;
+
+  primitives::blind_signatures::create_partially_blind_request(
+    &public_key_vec,
+    &encoded,
+    &election_id.into(),
+    expiry.into(),
+  )
+  .map(|request| ExportedBlindingResult {
+    blind_msg: request.blind_message.into(),
+    secret: request.secret.into(),
+    msg_randomizer: Some(request.msg_randomizer.into()),
+    key_id: key_id.into(),
+  })
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn sign_partially_blind(
+  secret_key: Buffer,
+  blind_msg: Buffer,
+  election_id: Buffer,
+  expiry: u32,
+) -> Result<Buffer> {
+  primitives::blind_signatures::sign_partially_blind(
+    &secret_key.into(),
+    &blind_msg.into(),
+    &election_id.into(),
+    expiry.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+#[allow(clippy::too_many_arguments)]
+pub fn unblind_partially_blind(
+  public_key: Buffer,
+  secret: Buffer,
+  msg_randomizer: Buffer,
+  blind_sig: Buffer,
+  client_addr: String,
+  iat_delay: u32,
+  election_id: Buffer,
+  expiry: u32,
+) -> Result<Buffer> {
+  let encoded = (client_addr, U256::from(iat_delay)).abi_encode_sequence();
+
+  primitives::blind_signatures::unblind_partially_blind(
+    &public_key.into(),
+    &encoded,
+    &election_id.into(),
+    expiry.into(),
+    secret.into(),
+    &msg_randomizer.into(),
+    blind_sig.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_partially_blind(
+  public_key: Buffer,
+  signature_bytes: Buffer,
+  msg_randomizer: Buffer,
+  msg: Buffer,
+  election_id: Buffer,
+  expiry: u32,
+) -> Result<bool> {
+  primitives::blind_signatures::verify_partially_blind(
+    &public_key.into(),
+    signature_bytes.into(),
+    &msg_randomizer.into(),
+    &msg.into(),
+    &election_id.into(),
+    expiry.into(),
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn deal_threshold_key(private_key: Buffer, k: u32, n: u32) -> Result<ExportedThresholdKey> {
+  primitives::blind_signatures::deal_threshold_key(&private_key.into(), k as usize, n as usize)
+    .map(|key| ExportedThresholdKey {
+      public: key.public.into(),
+      key_set: key.key_set.into(),
+      shares: key.shares.into_iter().map(Into::into).collect(),
+    })
     .map_err(|e| Error::from_reason(e.to_string()))
 }
+
+#[napi]
+pub fn sign_threshold_partial(
+  key_set: Buffer,
+  key_share: Buffer,
+  blind_msg: Buffer,
+) -> Result<Buffer> {
+  primitives::blind_signatures::sign_threshold_partial(
+    &key_set.into(),
+    &key_share.into(),
+    &blind_msg.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_threshold_partial(
+  key_set: Buffer,
+  blind_msg: Buffer,
+  partial: Buffer,
+) -> Result<bool> {
+  primitives::blind_signatures::verify_threshold_partial(
+    &key_set.into(),
+    &blind_msg.into(),
+    &partial.into(),
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn combine_threshold_partials(
+  key_set: Buffer,
+  blind_msg: Buffer,
+  partials: Vec<Buffer>,
+) -> Result<Buffer> {
+  let partials_vec: Vec<Vec<u8>> = partials.into_iter().map(Into::into).collect();
+  primitives::blind_signatures::combine_threshold_partials(
+    &key_set.into(),
+    &blind_msg.into(),
+    &partials_vec,
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn encode_eligibility_bundle(
+  registrars: Vec<u32>,
+  signatures: Vec<Buffer>,
+  msg_randomizers: Vec<Buffer>,
+  election_id: Buffer,
+  scheme: u32,
+) -> Result<Buffer> {
+  let registrars_vec: Vec<usize> = registrars.into_iter().map(|i| i as usize).collect();
+  let signatures_vec: Vec<Vec<u8>> = signatures.into_iter().map(Into::into).collect();
+  let msg_randomizers_vec: Vec<Vec<u8>> = msg_randomizers.into_iter().map(Into::into).collect();
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::blind_signatures::encode_eligibility_bundle(
+    &registrars_vec,
+    &signatures_vec,
+    &msg_randomizers_vec,
+    &election_id.into(),
+    scheme,
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_eligibility_bundle(
+  public_keys: Vec<Buffer>,
+  k: u32,
+  bundle: Buffer,
+  msg: Buffer,
+  election_id: Buffer,
+  scheme: u32,
+) -> Result<bool> {
+  let public_keys_vec: Vec<Vec<u8>> = public_keys.into_iter().map(Into::into).collect();
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::blind_signatures::verify_eligibility_bundle(
+    &public_keys_vec,
+    k as usize,
+    &bundle.into(),
+    &msg.into(),
+    &election_id.into(),
+    scheme,
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn eligibility_nullifier(msg: Buffer) -> Buffer {
+  primitives::blind_signatures::eligibility_nullifier(&msg.into()).into()
+}
+
+#[napi]
+pub fn key_id(public_key: Buffer) -> Result<Buffer> {
+  primitives::blind_signatures::key_id(&public_key.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn register_key(
+  registry: Buffer,
+  public_key: Buffer,
+  not_before: u32,
+  not_after: u32,
+) -> Result<Buffer> {
+  primitives::blind_signatures::register_key(
+    &registry.into(),
+    &public_key.into(),
+    not_before.into(),
+    not_after.into(),
+  )
+  .map(Into::into)
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn retire_key(registry: Buffer, key_id: Buffer) -> Result<Buffer> {
+  primitives::blind_signatures::retire_key(&registry.into(), &key_id.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn resolve_key(registry: Buffer, key_id: Buffer, timestamp: u32) -> Result<Buffer> {
+  primitives::blind_signatures::resolve_key(&registry.into(), &key_id.into(), timestamp.into())
+    .map(Into::into)
+    .map_err(|e| Error::from_reason(e.to_string()))
+}
+
+#[napi]
+pub fn verify_by_key_id(
+  registry: Buffer,
+  key_id: Buffer,
+  signature_bytes: Buffer,
+  msg_randomizer: Option<Buffer>,
+  msg: Buffer,
+  timestamp: u32,
+  scheme: u32,
+) -> Result<bool> {
+  let scheme = u8::try_from(scheme).map_err(|e| Error::from_reason(e.to_string()))?;
+  primitives::blind_signatures::verify_by_key_id(
+    &registry.into(),
+    &key_id.into(),
+    signature_bytes.into(),
+    &msg_randomizer.map(Into::into).unwrap_or_default(),
+    &msg.into(),
+    timestamp.into(),
+    scheme,
+  )
+  .map_err(|e| Error::from_reason(e.to_string()))
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::context;
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use primitives::envelope::{BFV, BN254_ELGAMAL, RISTRETTO_ELGAMAL, SECP256K1_ELGAMAL};
+use server_utilities::backend::{
+  add_trusted_backend_vote, bn254_verifier_contract, encrypt_backend_vote, generate_backend_acc,
+  generate_backend_keypair,
+};
+use server_utilities::ballots::{add_votes, decrypt_result};
+use server_utilities::envelope::inspect_envelope;
+use server_utilities::ExportedKeyPair;
+
+#[test]
+fn test_backend_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_backend_keypair(BFV.into()).expect("failed to generate keypair");
+
+  let input = (U256::from(3), B256::from([1; 32]), U256::from(BFV)).abi_encode_sequence();
+  let mut acc: Vec<u8> = generate_backend_acc(Buffer::from(input)).unwrap().into();
+  assert_eq!(
+    inspect_envelope(Buffer::from(acc.clone())).unwrap().scheme,
+    u32::from(BFV)
+  );
+
+  for choice in [2, 0, 2] {
+    let ballot: Vec<u8> =
+      encrypt_backend_vote(BFV.into(), Buffer::from(pk.as_ref()), choice, 3, context())
+        .unwrap()
+        .into();
+    let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+
+    // BFV ballots cannot be verified, so only trusted clients add them
+    assert!(add_votes(Buffer::from(input.clone())).is_err());
+    acc = add_trusted_backend_vote(Buffer::from(input)).unwrap().into();
+  }
+
+  assert_eq!(
+    decrypt_result(sk, Buffer::from(acc), None, None).unwrap(),
+    vec![1, 0, 2]
+  );
+
+  // ElGamal keys do not encrypt BFV ballots
+  let ExportedKeyPair { public: pk, .. } =
+    generate_backend_keypair(RISTRETTO_ELGAMAL.into()).unwrap();
+  assert!(encrypt_backend_vote(BFV.into(), pk, 0, 3, context()).is_err());
+  assert!(generate_backend_keypair(256).is_err());
+}
+
+#[test]
+fn test_bn254_backend_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_backend_keypair(BN254_ELGAMAL.into()).expect("failed to generate keypair");
+
+  let input = (
+    U256::from(2),
+    B256::from([1; 32]),
+    U256::from(BN254_ELGAMAL),
+  )
+    .abi_encode_sequence();
+  let acc = generate_backend_acc(Buffer::from(input)).unwrap();
+
+  let ballot = encrypt_backend_vote(BN254_ELGAMAL.into(), pk, 1, 2, context()).unwrap();
+  let input = (Bytes::from(acc.to_vec()), Bytes::from(ballot.to_vec())).abi_encode_sequence();
+  let acc = add_votes(Buffer::from(input)).unwrap();
+
+  assert_eq!(decrypt_result(sk, acc, None, None).unwrap(), vec![0, 1]);
+  assert!(bn254_verifier_contract().contains("function verifyVote("));
+}
+
+#[test]
+fn test_secp256k1_backend_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_backend_keypair(SECP256K1_ELGAMAL.into()).expect("failed to generate keypair");
+
+  let input = (
+    U256::from(2),
+    B256::from([1; 32]),
+    U256::from(SECP256K1_ELGAMAL),
+  )
+    .abi_encode_sequence();
+  let acc = generate_backend_acc(Buffer::from(input)).unwrap();
+
+  let ballot = encrypt_backend_vote(
+    SECP256K1_ELGAMAL.into(),
+    Buffer::from(pk.as_ref()),
+    0,
+    2,
+    context(),
+  )
+  .unwrap();
+  let input = (Bytes::from(acc.to_vec()), Bytes::from(ballot.to_vec())).abi_encode_sequence();
+  let acc = add_votes(Buffer::from(input)).unwrap();
+
+  assert_eq!(decrypt_result(sk, acc, None, None).unwrap(), vec![1, 0]);
+
+  // secp256k1 keys do not encrypt Ristretto ballots
+  assert!(encrypt_backend_vote(RISTRETTO_ELGAMAL.into(), pk, 0, 2, context()).is_err());
+}
@@ -1,8 +1,15 @@
+mod common;
+
+use common::context;
 use napi::bindgen_prelude::Buffer;
-use primitives::alloy_primitives::{Bytes, U256};
+use primitives::alloy_primitives::{Bytes, B256, U256};
 use primitives::alloy_sol_types::SolValue;
 use server_utilities::ballots::{
-  add_votes, decrypt_result, encrypt_vote, generate_acc, generate_elgamal_keypair,
+  add_multi_votes, add_score_votes, add_votes, add_votes_batch, audit_ballot, ballot_tracker,
+  decrypt_result, decrypt_result_with_proof, decrypt_score_result, encrypt_multi_vote,
+  encrypt_multi_vote_for_audit, encrypt_score_vote, encrypt_vote, encrypt_vote_for_audit,
+  generate_acc, generate_elgamal_keypair, tracker_code, verify_multi_vote, verify_score_vote,
+  verify_tally, verify_votes_batch,
 };
 use server_utilities::ExportedKeyPair;
 
@@ -18,20 +25,20 @@ fn test_ballot_flow() {
   let options_count = 3;
 
   // Initialize Accumulator
-  let encoded_count = candidate_count.abi_encode();
+  let encoded_count = (candidate_count, B256::from([1; 32])).abi_encode_sequence();
   let acc = generate_acc(Buffer::from(encoded_count)).expect("failed to generate acc");
 
   // Vote for option 0
-  let vote0 =
-    encrypt_vote(Buffer::from(pk.as_ref()), 0, options_count).expect("failed to encrypt vote 0");
+  let vote0 = encrypt_vote(Buffer::from(pk.as_ref()), 0, options_count, context())
+    .expect("failed to encrypt vote 0");
 
   // Vote for option 1
-  let vote1 =
-    encrypt_vote(Buffer::from(pk.as_ref()), 1, options_count).expect("failed to encrypt vote 1");
+  let vote1 = encrypt_vote(Buffer::from(pk.as_ref()), 1, options_count, context())
+    .expect("failed to encrypt vote 1");
 
   // Vote for option 0 again
-  let vote2 =
-    encrypt_vote(Buffer::from(pk.as_ref()), 0, options_count).expect("failed to encrypt vote 2");
+  let vote2 = encrypt_vote(Buffer::from(pk.as_ref()), 0, options_count, context())
+    .expect("failed to encrypt vote 2");
 
   // Add votes
   let mut current_acc: Vec<u8> = acc.into();
@@ -66,10 +73,250 @@ fn test_ballot_flow() {
     .expect("failed to add vote 2")
     .into();
 
-  let results = decrypt_result(sk, current_acc.into()).expect("failed to decrypt");
+  let results = decrypt_result(sk, current_acc.into(), None, None).expect("failed to decrypt");
 
   // Expected: Option 0: 2 votes, Option 1: 1 vote, Option 2: 0 votes
   assert_eq!(results[0], 2);
   assert_eq!(results[1], 1);
   assert_eq!(results[2], 0);
 }
+
+#[test]
+fn test_tally_proof_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let acc = generate_acc(Buffer::from(
+    (U256::from(2), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .expect("failed to generate acc");
+  let vote =
+    encrypt_vote(Buffer::from(pk.as_ref()), 1, 2, context()).expect("failed to encrypt vote");
+  let input = (Bytes::from(acc.to_vec()), Bytes::from(vote.to_vec())).abi_encode_sequence();
+  let acc = add_votes(input.into()).expect("failed to add vote");
+
+  let tally = decrypt_result_with_proof(sk, Buffer::from(acc.as_ref()), None, None)
+    .expect("failed to decrypt");
+  assert_eq!(tally.counts, vec![0, 1]);
+
+  let input = (
+    Bytes::from(pk.to_vec()),
+    Bytes::from(acc.to_vec()),
+    tally
+      .counts
+      .iter()
+      .map(|c| U256::from(*c))
+      .collect::<Vec<U256>>(),
+    Bytes::from(tally.proof.to_vec()),
+  )
+    .abi_encode_sequence();
+  let output = verify_tally(input.into()).expect("failed to verify tally");
+  assert_eq!(output[31], 1);
+}
+
+#[test]
+fn test_multi_choice_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  // Pick exactly 2 out of 3 candidates
+  let vote = encrypt_multi_vote(Buffer::from(pk.as_ref()), vec![0, 2], 3, 2, 2, context())
+    .expect("failed to encrypt vote");
+  assert!(encrypt_multi_vote(Buffer::from(pk.as_ref()), vec![0], 3, 2, 2, context()).is_err());
+
+  let input = (
+    U256::from(3),
+    U256::from(2),
+    U256::from(2),
+    Bytes::from(context().to_vec()),
+    Bytes::from(pk.to_vec()),
+    Bytes::from(vote.to_vec()),
+  )
+    .abi_encode_sequence();
+  let output = verify_multi_vote(input.into()).expect("failed to verify vote");
+  assert_eq!(output[31], 1);
+
+  let acc = generate_acc(Buffer::from(
+    (U256::from(3), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .expect("failed to generate acc");
+  let input = (Bytes::from(acc.to_vec()), Bytes::from(vote.to_vec())).abi_encode_sequence();
+  let acc = add_multi_votes(input.into()).expect("failed to add vote");
+
+  let results = decrypt_result(sk, acc, None, None).expect("failed to decrypt");
+  assert_eq!(results, vec![1, 0, 1]);
+}
+
+#[test]
+fn test_score_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  // 0 to 5 stars for each of 3 candidates
+  let votes = [vec![5, 2, 0], vec![4, 4, 1]];
+  assert!(
+    encrypt_score_vote(Buffer::from(pk.as_ref()), vec![6, 0, 0], 5, None, context()).is_err()
+  );
+
+  let mut acc: Vec<u8> = generate_acc(Buffer::from(
+    (U256::from(3), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .expect("failed to generate acc")
+  .into();
+
+  for scores in votes {
+    let vote = encrypt_score_vote(Buffer::from(pk.as_ref()), scores, 5, None, context())
+      .expect("failed to encrypt vote");
+
+    let input = (
+      U256::from(3),
+      U256::from(5),
+      U256::from(0),
+      Bytes::from(context().to_vec()),
+      Bytes::from(pk.to_vec()),
+      Bytes::from(vote.to_vec()),
+    )
+      .abi_encode_sequence();
+    let output = verify_score_vote(input.into()).expect("failed to verify vote");
+    assert_eq!(output[31], 1);
+
+    let input = (Bytes::from(acc), Bytes::from(vote.to_vec())).abi_encode_sequence();
+    acc = add_score_votes(input.into())
+      .expect("failed to add vote")
+      .into();
+  }
+
+  let results = decrypt_score_result(sk, acc.into(), 5, None).expect("failed to decrypt");
+  assert_eq!(results, vec![9, 6, 1]);
+}
+
+#[test]
+fn test_verify_votes_batch_flow() {
+  let ExportedKeyPair { public: pk, .. } =
+    generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let valid = encrypt_vote(Buffer::from(pk.as_ref()), 1, 3, context()).expect("failed to encrypt");
+  let wrong_size =
+    encrypt_vote(Buffer::from(pk.as_ref()), 1, 4, context()).expect("failed to encrypt");
+
+  let input = (
+    U256::from(3),
+    U256::from(1),
+    U256::from(1),
+    vec![Bytes::from(context().to_vec()); 2],
+    Bytes::from(pk.to_vec()),
+    vec![
+      Bytes::from(valid.to_vec()),
+      Bytes::from(wrong_size.to_vec()),
+    ],
+  )
+    .abi_encode_sequence();
+  let output = verify_votes_batch(input.into()).expect("failed to verify votes");
+
+  let valid = <Vec<bool>>::abi_decode(&output).expect("failed to decode output");
+  assert_eq!(valid, vec![true, false]);
+}
+
+#[test]
+fn test_add_votes_batch_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let acc = generate_acc(Buffer::from(
+    (U256::from(3), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .expect("failed to generate acc");
+  let ballots = [0, 2, 2]
+    .into_iter()
+    .map(|choice| {
+      let vote =
+        encrypt_vote(Buffer::from(pk.as_ref()), choice, 3, context()).expect("failed to encrypt");
+      Bytes::from(vote.to_vec())
+    })
+    .collect::<Vec<_>>();
+
+  let input = (
+    Bytes::from(acc.to_vec()),
+    ballots.clone(),
+    true,
+    U256::from(1),
+    U256::from(1),
+    vec![Bytes::from(context().to_vec()); ballots.len()],
+    Bytes::from(pk.to_vec()),
+  )
+    .abi_encode_sequence();
+  let output = add_votes_batch(input.into()).expect("failed to add votes");
+
+  let (acc, rejected) =
+    <(Bytes, Vec<U256>)>::abi_decode_sequence(&output).expect("failed to decode output");
+  assert!(rejected.is_empty());
+
+  let results = decrypt_result(sk, acc.to_vec().into(), None, None).expect("failed to decrypt");
+  assert_eq!(results, vec![1, 0, 2]);
+}
+
+#[test]
+fn test_audit_ballot_flow() {
+  let ExportedKeyPair { public: pk, .. } =
+    generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let spoiled = encrypt_vote_for_audit(Buffer::from(pk.as_ref()), 2, 3, context())
+    .expect("failed to encrypt vote");
+  let choices = audit_ballot(
+    Buffer::from(pk.as_ref()),
+    Buffer::from(spoiled.ballot.as_ref()),
+    Buffer::from(spoiled.randomness.as_ref()),
+    3,
+    1,
+    1,
+    context(),
+  )
+  .expect("failed to audit ballot");
+  assert_eq!(choices, vec![2]);
+
+  // A spoiled ballot is still a well-formed ballot
+  let input = (
+    U256::from(3),
+    U256::from(1),
+    U256::from(1),
+    Bytes::from(context().to_vec()),
+    Bytes::from(pk.to_vec()),
+    Bytes::from(spoiled.ballot.to_vec()),
+  )
+    .abi_encode_sequence();
+  let output = verify_multi_vote(input.into()).expect("failed to verify vote");
+  assert_eq!(output[31], 1);
+
+  let multi =
+    encrypt_multi_vote_for_audit(Buffer::from(pk.as_ref()), vec![0, 2], 3, 0, 3, context())
+      .expect("failed to encrypt vote");
+  assert!(audit_ballot(pk, spoiled.ballot, multi.randomness, 3, 1, 1, context()).is_err());
+}
+
+#[test]
+fn test_ballot_tracker_flow() {
+  let first = ballot_tracker(
+    Buffer::from(vec![1; 32]),
+    Buffer::from(vec![0; 32]),
+    Buffer::from(b"ballot".to_vec()),
+  )
+  .expect("failed to compute tracker");
+  let second = ballot_tracker(
+    Buffer::from(vec![1; 32]),
+    Buffer::from(first.as_ref()),
+    Buffer::from(b"ballot".to_vec()),
+  )
+  .expect("failed to compute tracker");
+
+  // Same vectors as the wasm bindings
+  assert_eq!(tracker_code(first).unwrap(), "YQ0S-VZCC-A5RM-2QWH");
+  assert_eq!(tracker_code(second).unwrap(), "AH09-TS42-3TB1-7SXC");
+}
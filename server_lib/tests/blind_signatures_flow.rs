@@ -1,3 +1,4 @@
+use primitives::envelope::{RSA_BLIND_PSS, RSA_BLIND_PSS_DETERMINISTIC};
 use server_utilities::ExportedKeyPair;
 use server_utilities::signatures::{
     create_request, generate_rsa_keypair, sign, unblind, verify,
@@ -17,18 +18,19 @@ fn test_blind_signature_flow() {
 
     // 1. Create Request
     // Note: server_utilities::create_request internally encodes (client_addr, election_id, iat_delay) as the message
-    let result = create_request(Buffer::from(public_key.as_ref()), client_addr.clone(), iat_delay, election_id.clone())
+    let result = create_request(Buffer::from(public_key.as_ref()), client_addr.clone(), iat_delay, election_id.clone(), RSA_BLIND_PSS.into())
         .expect("failed to create request");
     
     let blinded_msg = result.blind_msg;
     let secret = result.secret;
+    let msg_randomizer = result.msg_randomizer.expect("randomized requests have a message randomizer");
 
     // 2. Sign (Blindly)
     // Server signs the blinded hash of the encoded message
     let blinded_signature = sign(private_key, blinded_msg).expect("failed to sign blinded msg");
 
     // 3. Unblind
-    let signature = unblind(Buffer::from(public_key.as_ref()), secret, blinded_signature, client_addr.clone(), iat_delay, election_id.clone())
+    let signature = unblind(Buffer::from(public_key.as_ref()), secret, Some(Buffer::from(msg_randomizer.as_ref())), blinded_signature, client_addr.clone(), iat_delay, election_id.clone(), RSA_BLIND_PSS.into())
         .expect("failed to unblind");
 
     // 4. Verify
@@ -48,6 +50,288 @@ fn test_blind_signature_flow() {
     
     let encoded_msg = (client_addr, election_id, U256::from(iat_delay)).abi_encode_sequence();
     
-    let is_valid = verify(public_key, signature, Buffer::from(encoded_msg));
+    let is_valid = verify(public_key, signature, Some(msg_randomizer), Buffer::from(encoded_msg), RSA_BLIND_PSS.into());
     assert!(is_valid.is_ok());
 }
+
+#[test]
+fn test_partially_blind_signature_flow() {
+    use napi::bindgen_prelude::Buffer;
+    use primitives::alloy_primitives::U256;
+    use primitives::alloy_sol_types::SolValue;
+    use server_utilities::signatures::{
+        create_partially_blind_request, nullifier, sign_partially_blind,
+        unblind_partially_blind, verify_partially_blind,
+    };
+
+    // Partially blind keys take minutes to generate, so a fixed one is used
+    let public_key = include_bytes!("../../primitives/tests/data/partially_blind_key.pub.der");
+    let private_key = include_bytes!("../../primitives/tests/data/partially_blind_key.der");
+
+    let client_addr = "0x0000000000000000000000000000000000000000".to_string();
+    let election_id = [7u8; 32];
+    let iat_delay = 0;
+    let expiry = 1_900_000_000;
+
+    let result = create_partially_blind_request(
+        Buffer::from(public_key.as_ref()),
+        client_addr.clone(),
+        iat_delay,
+        Buffer::from(election_id.as_ref()),
+        expiry,
+    )
+    .expect("failed to create request");
+    let msg_randomizer = result.msg_randomizer.expect("missing message randomizer");
+
+    let blinded_signature = sign_partially_blind(
+        Buffer::from(private_key.as_ref()),
+        result.blind_msg,
+        Buffer::from(election_id.as_ref()),
+        expiry,
+    )
+    .expect("failed to sign blinded msg");
+
+    let signature = unblind_partially_blind(
+        Buffer::from(public_key.as_ref()),
+        result.secret,
+        Buffer::from(msg_randomizer.as_ref()),
+        blinded_signature,
+        client_addr.clone(),
+        iat_delay,
+        Buffer::from(election_id.as_ref()),
+        expiry,
+    )
+    .expect("failed to unblind");
+
+    // The election ID and expiry are bound as metadata, not hidden in the message
+    let encoded_msg = (client_addr, U256::from(iat_delay)).abi_encode_sequence();
+
+    let is_valid = verify_partially_blind(
+        Buffer::from(public_key.as_ref()),
+        Buffer::from(signature.as_ref()),
+        Buffer::from(msg_randomizer.as_ref()),
+        Buffer::from(encoded_msg.as_ref()),
+        Buffer::from(election_id.as_ref()),
+        expiry,
+    );
+    assert!(is_valid.unwrap());
+
+    // A token has a single nullifier, whichever party computes it
+    let spent = nullifier(
+        Buffer::from(encoded_msg.as_ref()),
+        Buffer::from(signature.as_ref()),
+    );
+    assert_eq!(
+        spent.to_vec(),
+        primitives::blind_signatures::nullifier(&encoded_msg, &signature.to_vec())
+    );
+
+    let is_valid = verify_partially_blind(
+        Buffer::from(public_key.as_ref()),
+        signature,
+        msg_randomizer,
+        Buffer::from(encoded_msg),
+        Buffer::from(election_id.as_ref()),
+        expiry + 1,
+    );
+    assert!(!is_valid.unwrap());
+}
+
+#[test]
+fn test_threshold_blind_signature_flow() {
+    use napi::bindgen_prelude::Buffer;
+    use server_utilities::signatures::{
+        combine_threshold_partials, deal_threshold_key, sign_threshold_partial,
+        verify_threshold_partial,
+    };
+
+    // The modulus of a partially blind key is a product of safe primes, as dealing requires
+    let private_key = include_bytes!("../../primitives/tests/data/partially_blind_key.der");
+    let key = deal_threshold_key(Buffer::from(private_key.as_ref()), 2, 3)
+        .expect("failed to deal key");
+
+    let client_addr = "0x0000000000000000000000000000000000000000".to_string();
+    let election_id = "election_1".to_string();
+    let iat_delay = 0;
+
+    let result = create_request(
+        Buffer::from(key.public.as_ref()),
+        client_addr.clone(),
+        iat_delay,
+        election_id.clone(),
+        RSA_BLIND_PSS.into(),
+    )
+    .expect("failed to create request");
+
+    // Registrars 1 and 3 sign, and the client checks their partial signatures
+    let partials: Vec<Buffer> = [&key.shares[0], &key.shares[2]]
+        .into_iter()
+        .map(|share| {
+            sign_threshold_partial(
+                Buffer::from(key.key_set.as_ref()),
+                Buffer::from(share.as_ref()),
+                Buffer::from(result.blind_msg.as_ref()),
+            )
+            .expect("failed to sign partial")
+        })
+        .collect();
+    for partial in &partials {
+        let is_valid = verify_threshold_partial(
+            Buffer::from(key.key_set.as_ref()),
+            Buffer::from(result.blind_msg.as_ref()),
+            Buffer::from(partial.as_ref()),
+        );
+        assert!(is_valid.unwrap());
+    }
+
+    let blinded_signature = combine_threshold_partials(
+        Buffer::from(key.key_set.as_ref()),
+        result.blind_msg,
+        partials,
+    )
+    .expect("failed to combine partials");
+
+    let signature = unblind(
+        Buffer::from(key.public.as_ref()),
+        result.secret,
+        result.msg_randomizer,
+        blinded_signature,
+        client_addr,
+        iat_delay,
+        election_id,
+        RSA_BLIND_PSS.into(),
+    );
+    assert!(signature.is_ok());
+}
+
+#[test]
+fn test_eligibility_bundle_flow() {
+    use napi::bindgen_prelude::Buffer;
+    use primitives::alloy_primitives::U256;
+    use primitives::alloy_sol_types::SolValue;
+    use server_utilities::signatures::{encode_eligibility_bundle, verify_eligibility_bundle};
+
+    let client_addr = "0x0000000000000000000000000000000000000000".to_string();
+    let election_id = "election_1".to_string();
+    let iat_delay = 0;
+
+    // Two registrars sign the same hidden message with their own keys, in the deterministic mode
+    let scheme = u32::from(RSA_BLIND_PSS_DETERMINISTIC);
+    let registrars: Vec<ExportedKeyPair> = (0..2)
+        .map(|_| generate_rsa_keypair().expect("failed to generate keypair"))
+        .collect();
+    let signatures: Vec<Buffer> = registrars
+        .iter()
+        .map(|registrar| {
+            let result = create_request(
+                Buffer::from(registrar.public.as_ref()),
+                client_addr.clone(),
+                iat_delay,
+                election_id.clone(),
+                scheme,
+            )
+            .expect("failed to create request");
+            assert!(result.msg_randomizer.is_none());
+            let blinded_signature = sign(Buffer::from(registrar.private.as_ref()), result.blind_msg)
+                .expect("failed to sign blinded msg");
+            unblind(
+                Buffer::from(registrar.public.as_ref()),
+                result.secret,
+                None,
+                blinded_signature,
+                client_addr.clone(),
+                iat_delay,
+                election_id.clone(),
+                scheme,
+            )
+            .expect("failed to unblind")
+        })
+        .collect();
+
+    let no_randomizers = vec![Buffer::from(vec![]), Buffer::from(vec![])];
+    let bundle = encode_eligibility_bundle(vec![0, 1], signatures, no_randomizers, Buffer::from(vec![1; 32]), scheme)
+        .expect("failed to encode bundle");
+
+    let encoded_msg = (client_addr, election_id, U256::from(iat_delay)).abi_encode_sequence();
+    let verify_k = |k: u32| {
+        verify_eligibility_bundle(
+            registrars
+                .iter()
+                .map(|registrar| Buffer::from(registrar.public.as_ref()))
+                .collect(),
+            k,
+            Buffer::from(bundle.as_ref()),
+            Buffer::from(encoded_msg.as_ref()),
+            Buffer::from(vec![1; 32]),
+            scheme,
+        )
+        .unwrap()
+    };
+    assert!(verify_k(2));
+    assert!(verify_k(1));
+}
+
+#[test]
+fn test_key_rotation_flow() {
+    use napi::bindgen_prelude::Buffer;
+    use primitives::alloy_primitives::U256;
+    use primitives::alloy_sol_types::SolValue;
+    use server_utilities::signatures::{key_id, register_key, retire_key, verify_by_key_id};
+
+    let client_addr = "0x0000000000000000000000000000000000000000".to_string();
+    let election_id = "election_1".to_string();
+    let iat_delay = 0;
+
+    let old = generate_rsa_keypair().expect("failed to generate keypair");
+    let new = generate_rsa_keypair().expect("failed to generate keypair");
+    let registry = register_key(Buffer::from(vec![]), Buffer::from(old.public.as_ref()), 0, 1000)
+        .expect("failed to register key");
+    let registry = register_key(registry, Buffer::from(new.public.as_ref()), 500, 2000)
+        .expect("failed to register key");
+
+    // The request names the key it must be signed with
+    let result = create_request(
+        Buffer::from(old.public.as_ref()),
+        client_addr.clone(),
+        iat_delay,
+        election_id.clone(),
+        RSA_BLIND_PSS.into(),
+    )
+    .expect("failed to create request");
+    let old_id = key_id(Buffer::from(old.public.as_ref())).unwrap();
+    assert_eq!(result.key_id.to_vec(), old_id.to_vec());
+
+    let blinded_signature = sign(Buffer::from(old.private.as_ref()), result.blind_msg)
+        .expect("failed to sign blinded msg");
+    let msg_randomizer = result.msg_randomizer.expect("randomized requests have a message randomizer");
+    let signature = unblind(
+        Buffer::from(old.public.as_ref()),
+        result.secret,
+        Some(Buffer::from(msg_randomizer.as_ref())),
+        blinded_signature,
+        client_addr.clone(),
+        iat_delay,
+        election_id.clone(),
+        RSA_BLIND_PSS.into(),
+    )
+    .expect("failed to unblind");
+
+    let encoded_msg = (client_addr, election_id, U256::from(iat_delay)).abi_encode_sequence();
+    let verify_at = |registry: &Buffer, timestamp: u32| {
+        verify_by_key_id(
+            Buffer::from(registry.as_ref()),
+            Buffer::from(old_id.as_ref()),
+            Buffer::from(signature.as_ref()),
+            Some(Buffer::from(msg_randomizer.as_ref())),
+            Buffer::from(encoded_msg.as_ref()),
+            timestamp,
+            RSA_BLIND_PSS.into(),
+        )
+        .unwrap()
+    };
+    assert!(verify_at(&registry, 100));
+    assert!(!verify_at(&registry, 1500));
+
+    let registry = retire_key(registry, Buffer::from(old_id.as_ref())).expect("failed to retire key");
+    assert!(!verify_at(&registry, 100));
+}
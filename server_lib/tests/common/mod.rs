@@ -0,0 +1,12 @@
+use napi::bindgen_prelude::Buffer;
+use server_utilities::ballots::ballot_context;
+
+/// Context of the ballots cast with the test token in the election `[1; 32]`.
+pub fn context() -> Buffer {
+  ballot_context(
+    Buffer::from(vec![1; 32]),
+    Buffer::from(vec![2; 20]),
+    Buffer::from(b"token".to_vec()),
+  )
+  .expect("failed to encode context")
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::context;
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, U256};
+use primitives::alloy_sol_types::SolValue;
+use primitives::contests::{SCORE, SINGLE_CHOICE};
+use server_utilities::ballots::{decrypt_result, generate_elgamal_keypair};
+use server_utilities::contests::{
+  add_contest_votes, encrypt_contest_votes, generate_contest_acc, split_contest_results,
+  verify_contest_votes,
+};
+use server_utilities::ExportedKeyPair;
+
+#[test]
+fn test_contests_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  // A treasurer out of 2 and 0 to 5 stars for 3 proposals
+  let manifest = vec![
+    (
+      U256::from(SINGLE_CHOICE),
+      U256::from(2),
+      U256::ZERO,
+      U256::ZERO,
+    ),
+    (U256::from(SCORE), U256::from(3), U256::from(5), U256::ZERO),
+  ]
+  .abi_encode();
+
+  let mut acc: Vec<u8> =
+    generate_contest_acc(Buffer::from(manifest.clone()), Buffer::from(vec![1; 32]))
+      .expect("failed to generate acc")
+      .into();
+
+  for selections in [vec![vec![0], vec![5, 1, 0]], vec![vec![0], vec![2, 4, 3]]] {
+    let vote = encrypt_contest_votes(
+      Buffer::from(pk.as_ref()),
+      Buffer::from(manifest.clone()),
+      selections,
+      context(),
+    )
+    .expect("failed to encrypt vote");
+
+    let input = (
+      Bytes::from(manifest.clone()),
+      Bytes::from(context().to_vec()),
+      Bytes::from(pk.to_vec()),
+      Bytes::from(vote.to_vec()),
+    )
+      .abi_encode_sequence();
+    let output = verify_contest_votes(input.into()).expect("failed to verify vote");
+    assert_eq!(output[31], 1);
+
+    let input = (
+      Bytes::from(manifest.clone()),
+      Bytes::from(acc),
+      Bytes::from(vote.to_vec()),
+    )
+      .abi_encode_sequence();
+    acc = add_contest_votes(input.into())
+      .expect("failed to add vote")
+      .into();
+  }
+
+  let results = decrypt_result(sk, acc.into(), Some(5), None).expect("failed to decrypt");
+  let results =
+    split_contest_results(Buffer::from(manifest), results).expect("failed to split results");
+  assert_eq!(results, vec![vec![2, 0], vec![7, 5, 3]]);
+}
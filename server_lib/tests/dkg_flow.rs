@@ -0,0 +1,121 @@
+mod common;
+
+use common::context;
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use server_utilities::ballots::{
+  add_votes, combine_decryption_shares, decrypt_share, encrypt_vote, generate_acc,
+};
+use server_utilities::dkg::{
+  derive_key_set, derive_public_key, generate_participant, get_commitment, get_public_info,
+  get_secret_share, get_shared_public_key, insert_commitments, insert_public_infos,
+  insert_secret_shares,
+};
+
+fn to_buffers(items: &[Vec<u8>]) -> Vec<Buffer> {
+  items
+    .iter()
+    .map(|item| Buffer::from(item.clone()))
+    .collect()
+}
+
+#[test]
+fn test_dkg_flow() {
+  let (k, n) = (2, 3);
+
+  let states: Vec<Vec<u8>> = (0..n)
+    .map(|i| {
+      generate_participant(k, n, i)
+        .expect("failed to generate participant")
+        .into()
+    })
+    .collect();
+
+  // 1. Commitments
+  let commitments: Vec<Vec<u8>> = states
+    .iter()
+    .map(|state| get_commitment(state.clone().into()).unwrap().into())
+    .collect();
+  let states: Vec<Vec<u8>> = states
+    .into_iter()
+    .map(|state| {
+      insert_commitments(state.into(), to_buffers(&commitments))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  // 2. Public polynomials
+  let infos: Vec<Vec<u8>> = states
+    .iter()
+    .map(|state| get_public_info(state.clone().into()).unwrap().into())
+    .collect();
+  let states: Vec<Vec<u8>> = states
+    .into_iter()
+    .map(|state| {
+      insert_public_infos(state.into(), to_buffers(&infos))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  // 3. Secret shares
+  let key_shares: Vec<Vec<u8>> = (0..n)
+    .map(|i| {
+      let shares: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| get_secret_share(state.clone().into(), i).unwrap().into())
+        .collect();
+      insert_secret_shares(states[i as usize].clone().into(), to_buffers(&shares))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  let public_key: Vec<u8> = get_shared_public_key(key_shares[0].clone().into())
+    .unwrap()
+    .into();
+  for key_share in &key_shares {
+    let key: Vec<u8> = get_shared_public_key(key_share.clone().into())
+      .unwrap()
+      .into();
+    assert_eq!(key, public_key);
+  }
+
+  let derived: Vec<u8> = derive_public_key(k, n, to_buffers(&infos)).unwrap().into();
+  assert_eq!(derived, public_key);
+
+  // 4. Threshold decryption with trustees 0 and 1
+  let acc: Vec<u8> = generate_acc(
+    (U256::from(2), B256::from([1; 32]))
+      .abi_encode_sequence()
+      .into(),
+  )
+  .unwrap()
+  .into();
+  let vote = encrypt_vote(public_key.into(), 1, 2, context()).unwrap();
+  let input = (Bytes::from(acc), Bytes::from(vote.to_vec())).abi_encode_sequence();
+  let acc: Vec<u8> = add_votes(input.into()).unwrap().into();
+
+  let key_set = derive_key_set(k, n, to_buffers(&infos)).unwrap();
+  let shares: Vec<Vec<u8>> = key_shares[..2]
+    .iter()
+    .map(|key_share| {
+      decrypt_share(key_share.clone().into(), acc.clone().into())
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  let results = combine_decryption_shares(
+    key_set,
+    acc.into(),
+    vec![0, 1],
+    to_buffers(&shares),
+    None,
+    None,
+  )
+  .unwrap();
+  assert_eq!(results, vec![0, 1]);
+}
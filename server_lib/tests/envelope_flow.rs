@@ -0,0 +1,76 @@
+mod common;
+
+use common::context;
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use primitives::envelope::{
+  ACCUMULATOR, HEADER_LEN, MULTI_CHOICE_BALLOT, RSA_BLIND_PSS, TOKEN, VERSION,
+};
+use server_utilities::ballots::{
+  add_votes, decrypt_result, encode_token, encrypt_vote, generate_acc, generate_elgamal_keypair,
+};
+use server_utilities::envelope::{
+  inspect_envelope, migrate_acc, migrate_ballot, migrate_token, ExportedEnvelopeHeader,
+};
+use server_utilities::ExportedKeyPair;
+
+#[test]
+fn test_envelope_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let ballot: Vec<u8> = encrypt_vote(pk, 1, 2, context()).unwrap().into();
+  let ExportedEnvelopeHeader {
+    version,
+    kind,
+    election_id,
+    ..
+  } = inspect_envelope(Buffer::from(ballot.clone())).unwrap();
+  assert_eq!(
+    (version, kind),
+    (VERSION.into(), MULTI_CHOICE_BALLOT.into())
+  );
+  assert_eq!(election_id.to_vec(), vec![1; 32]);
+
+  // Ballots of the current format were never written without an envelope
+  let bare = Buffer::from(ballot[HEADER_LEN..].to_vec());
+  assert!(migrate_ballot(bare, Buffer::from(vec![1; 32])).is_err());
+  assert!(inspect_envelope(Buffer::from(ballot[HEADER_LEN..].to_vec())).is_err());
+
+  let acc: Vec<u8> = generate_acc(Buffer::from(
+    (U256::from(2), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .unwrap()
+  .into();
+  let input = (Bytes::from(acc), Bytes::from(ballot)).abi_encode_sequence();
+  let acc: Vec<u8> = add_votes(Buffer::from(input)).unwrap().into();
+  assert_eq!(
+    inspect_envelope(Buffer::from(acc.clone())).unwrap().kind,
+    u32::from(ACCUMULATOR)
+  );
+
+  // Bare accumulators have no ballot count, which takes a single byte here
+  let bare = Buffer::from(acc[HEADER_LEN + 1..].to_vec());
+  let migrated = migrate_acc(bare, Buffer::from(vec![1; 32]), 1).unwrap();
+  assert_eq!(migrated.to_vec(), acc);
+  assert_eq!(decrypt_result(sk, migrated, None, None).unwrap(), vec![0, 1]);
+
+  let token: Vec<u8> = encode_token(
+    Buffer::from(vec![5; 256]),
+    Some(Buffer::from(vec![6; 32])),
+    Buffer::from(vec![1; 32]),
+    RSA_BLIND_PSS.into(),
+  )
+  .unwrap()
+  .into();
+  assert_eq!(
+    inspect_envelope(Buffer::from(token.clone())).unwrap().kind,
+    u32::from(TOKEN)
+  );
+
+  // A token that already has an envelope is not migrated twice
+  assert!(migrate_token(Buffer::from(token), Buffer::from(vec![1; 32])).is_err());
+}
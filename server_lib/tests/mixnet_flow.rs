@@ -0,0 +1,154 @@
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, B256};
+use primitives::alloy_sol_types::SolValue;
+use server_utilities::dkg::{
+  derive_key_set, generate_participant, get_commitment, get_public_info, get_secret_share,
+  get_shared_public_key, insert_commitments, insert_public_infos, insert_secret_shares,
+};
+use server_utilities::mixnet::{
+  combine_mix_shares, decrypt_mix_share, encrypt_message, shuffle, verify_mix,
+};
+
+fn to_buffers(items: &[Vec<u8>]) -> Vec<Buffer> {
+  items
+    .iter()
+    .map(|item| Buffer::from(item.clone()))
+    .collect()
+}
+
+/// Runs a key generation ceremony and returns the infos and key shares of every trustee.
+fn run_ceremony(k: u32, n: u32) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+  let states: Vec<Vec<u8>> = (0..n)
+    .map(|i| generate_participant(k, n, i).unwrap().into())
+    .collect();
+
+  let commitments: Vec<Vec<u8>> = states
+    .iter()
+    .map(|state| get_commitment(state.clone().into()).unwrap().into())
+    .collect();
+  let states: Vec<Vec<u8>> = states
+    .into_iter()
+    .map(|state| {
+      insert_commitments(state.into(), to_buffers(&commitments))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  let infos: Vec<Vec<u8>> = states
+    .iter()
+    .map(|state| get_public_info(state.clone().into()).unwrap().into())
+    .collect();
+  let states: Vec<Vec<u8>> = states
+    .into_iter()
+    .map(|state| {
+      insert_public_infos(state.into(), to_buffers(&infos))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  let key_shares = (0..n)
+    .map(|i| {
+      let shares: Vec<Vec<u8>> = states
+        .iter()
+        .map(|state| get_secret_share(state.clone().into(), i).unwrap().into())
+        .collect();
+      insert_secret_shares(states[i as usize].clone().into(), to_buffers(&shares))
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  (infos, key_shares)
+}
+
+#[test]
+fn test_mixnet_flow() {
+  let (k, n) = (2, 3);
+  let (infos, key_shares) = run_ceremony(k, n);
+  let public_key: Vec<u8> = get_shared_public_key(key_shares[0].clone().into())
+    .unwrap()
+    .into();
+
+  let answers = ["Dave", "Erin", "Frank"];
+  let messages: Vec<Vec<u8>> = answers
+    .iter()
+    .map(|answer| {
+      encrypt_message(
+        public_key.clone().into(),
+        answer.as_bytes().to_vec().into(),
+        1,
+      )
+      .expect("failed to encrypt message")
+      .into()
+    })
+    .collect();
+
+  // Every trustee shuffles the output of the previous one
+  let mut chain: Vec<u8> = Vec::new();
+  for _ in 0..n {
+    chain = shuffle(
+      public_key.clone().into(),
+      Buffer::from(vec![1; 32]),
+      to_buffers(&messages),
+      chain.into(),
+    )
+    .expect("failed to shuffle")
+    .into();
+  }
+
+  let messages_abi = messages
+    .iter()
+    .cloned()
+    .map(Bytes::from)
+    .collect::<Vec<_>>();
+  let input = (
+    Bytes::from(public_key.clone()),
+    B256::from([1; 32]),
+    messages_abi.clone(),
+    Bytes::from(chain.clone()),
+  )
+    .abi_encode_sequence();
+  let output = verify_mix(input.into()).expect("failed to verify mix");
+  assert_eq!(output[31], 1);
+
+  // The chain does not verify in another election
+  let input = (
+    Bytes::from(public_key.clone()),
+    B256::from([9; 32]),
+    messages_abi.clone(),
+    Bytes::from(chain.clone()),
+  )
+    .abi_encode_sequence();
+  assert_eq!(verify_mix(input.into()).unwrap()[31], 0);
+
+  // The chain does not verify against fewer messages
+  let input = (
+    Bytes::from(public_key),
+    B256::from([1; 32]),
+    messages_abi[1..].to_vec(),
+    Bytes::from(chain.clone()),
+  )
+    .abi_encode_sequence();
+  assert_eq!(verify_mix(input.into()).unwrap()[31], 0);
+
+  let key_set = derive_key_set(k, n, to_buffers(&infos)).unwrap();
+  let shares: Vec<Vec<u8>> = key_shares[1..]
+    .iter()
+    .map(|key_share| {
+      decrypt_mix_share(key_share.clone().into(), chain.clone().into())
+        .unwrap()
+        .into()
+    })
+    .collect();
+
+  let plaintexts = combine_mix_shares(key_set, chain.into(), vec![1, 2], to_buffers(&shares))
+    .expect("failed to combine shares");
+  let mut revealed: Vec<String> = plaintexts
+    .into_iter()
+    .map(|plaintext| String::from_utf8(plaintext.unwrap().to_vec()).unwrap())
+    .collect();
+  revealed.sort();
+  assert_eq!(revealed, answers);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::context;
+use napi::bindgen_prelude::Buffer;
+use primitives::alloy_primitives::{Bytes, B256, U256};
+use primitives::alloy_sol_types::SolValue;
+use server_utilities::ballots::{decrypt_result, generate_elgamal_keypair};
+use server_utilities::ranked_ballots::{
+  add_ranked_votes, condorcet_winner, encrypt_ranked_vote, generate_ranked_acc, schulze_winners,
+  verify_ranked_vote,
+};
+use server_utilities::ExportedKeyPair;
+
+#[test]
+fn test_ranked_ballot_flow() {
+  let ExportedKeyPair {
+    public: pk,
+    private: sk,
+  } = generate_elgamal_keypair().expect("failed to generate keypair");
+
+  let options_count = 3;
+  let mut acc: Vec<u8> = generate_ranked_acc(Buffer::from(
+    (U256::from(options_count), B256::from([1; 32])).abi_encode_sequence(),
+  ))
+  .expect("failed to generate acc")
+  .into();
+
+  for ranking in [vec![1, 2, 0], vec![1, 0, 2], vec![0, 1, 2]] {
+    let vote = encrypt_ranked_vote(Buffer::from(pk.as_ref()), ranking, options_count, context())
+      .expect("failed to encrypt vote");
+
+    let input = (
+      U256::from(options_count),
+      Bytes::from(context().to_vec()),
+      Bytes::from(pk.to_vec()),
+      Bytes::from(vote.to_vec()),
+    )
+      .abi_encode_sequence();
+    let output = verify_ranked_vote(input.into()).expect("failed to verify vote");
+    assert_eq!(output[31], 1);
+
+    let input = (Bytes::from(acc), Bytes::from(vote.to_vec())).abi_encode_sequence();
+    acc = add_ranked_votes(input.into())
+      .expect("failed to add vote")
+      .into();
+  }
+
+  let matrix = decrypt_result(sk, acc.into(), None, None).expect("failed to decrypt");
+  assert_eq!(matrix, vec![0, 1, 2, 2, 0, 3, 1, 0, 0]);
+
+  assert_eq!(
+    condorcet_winner(matrix.clone(), options_count).expect("failed to compute winner"),
+    Some(1)
+  );
+  assert_eq!(
+    schulze_winners(matrix, options_count).expect("failed to compute winners"),
+    vec![1]
+  );
+}